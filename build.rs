@@ -12,6 +12,8 @@ fn main() {
     }
     println!("cargo:rerun-if-env-changed=CARGO_FEATURE_BRANDING");
 
+    export_locked_versions();
+
     #[cfg(feature = "branding")]
     check_branding_files();
 
@@ -25,6 +27,30 @@ fn is_windows_msvc_target() -> bool {
         && std::env::var("CARGO_CFG_TARGET_ENV").as_deref() == Ok("msvc")
 }
 
+/// Expose resolved memflow crate versions to the report environment block
+fn export_locked_versions() {
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    let Ok(lock) = std::fs::read_to_string("Cargo.lock") else {
+        return;
+    };
+
+    for (package, var) in [
+        ("memflow", "DMA_SPEEDTEST_MEMFLOW_VERSION"),
+        ("memflow-native", "DMA_SPEEDTEST_MEMFLOW_NATIVE_VERSION"),
+    ] {
+        let name_line = format!("name = \"{package}\"");
+        let version = lock
+            .lines()
+            .skip_while(|line| *line != name_line)
+            .nth(1)
+            .and_then(|line| line.strip_prefix("version = \""))
+            .and_then(|rest| rest.strip_suffix('"'));
+        if let Some(version) = version {
+            println!("cargo:rustc-env={var}={version}");
+        }
+    }
+}
+
 /// Configure linker settings for Windows builds
 fn configure_linker() {
     // Basic Configuration
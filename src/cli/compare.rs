@@ -0,0 +1,361 @@
+//! `compare` subcommand: diff saved JSON reports against a baseline.
+
+use anyhow::{Result, bail};
+use owo_colors::OwoColorize;
+use owo_colors::{Stream, Style};
+use std::path::PathBuf;
+
+use super::CliOutcome;
+use crate::bench_config::format_chunk_size;
+use crate::speedtest::{
+    BenchmarkReport, CompareMetric, CompareThresholds, MetricDelta, PassComparison,
+    ReportComparison, compare_reports, format_labels, load_report_from_path,
+};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct CompareArgs {
+    /// Baseline report followed by one or more candidate reports (JSON).
+    #[arg(required = true, num_args = 2.., value_name = "REPORT")]
+    pub reports: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        default_value_t = CompareThresholds::default().max_throughput_drop_pct,
+        help = "Fail when average MiB/s drops by more than this percentage."
+    )]
+    pub max_throughput_drop: f64,
+
+    #[arg(
+        long,
+        default_value_t = CompareThresholds::default().max_latency_rise_pct,
+        help = "Fail when average latency rises by more than this percentage."
+    )]
+    pub max_latency_rise: f64,
+
+    #[arg(
+        long,
+        help = "Do not fail when a baseline pass is missing from a candidate report."
+    )]
+    pub allow_missing: bool,
+}
+
+impl CompareArgs {
+    fn thresholds(&self) -> Result<CompareThresholds> {
+        for (flag, value) in [
+            ("--max-throughput-drop", self.max_throughput_drop),
+            ("--max-latency-rise", self.max_latency_rise),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                bail!("{flag} must be a non-negative percentage");
+            }
+        }
+        Ok(CompareThresholds {
+            max_throughput_drop_pct: self.max_throughput_drop,
+            max_latency_rise_pct: self.max_latency_rise,
+        })
+    }
+}
+
+/// Compare every candidate against the first report; regressions and baseline passes missing from
+/// a candidate (unless `--allow-missing`) set [`CliOutcome::Regression`].
+pub fn run_compare(args: &CompareArgs) -> Result<CliOutcome> {
+    let thresholds = args.thresholds()?;
+    let Some((baseline_path, candidate_paths)) = args.reports.split_first() else {
+        bail!("compare needs a baseline and at least one candidate report");
+    };
+    if candidate_paths.is_empty() {
+        bail!("compare needs a baseline and at least one candidate report");
+    }
+
+    let so = Stream::Stdout;
+    let baseline = load_report_from_path(baseline_path)?;
+    let mut regressions = 0;
+    let mut missing = 0;
+
+    for candidate_path in candidate_paths {
+        let candidate = load_report_from_path(candidate_path)?;
+        println!(
+            "\n{} {} {} {}",
+            "Comparing".if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+            candidate_path
+                .display()
+                .if_supports_color(so, |t| t.bright_white()),
+            "against baseline"
+                .if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+            baseline_path
+                .display()
+                .if_supports_color(so, |t| t.bright_white()),
+        );
+        print_report_tags(so, "baseline", &baseline);
+        print_report_tags(so, "candidate", &candidate);
+        if candidate.connector != baseline.connector || candidate.mode != baseline.mode {
+            println!(
+                "  {}",
+                format!(
+                    "note: baseline is {} / {}, candidate is {} / {}",
+                    baseline.connector, baseline.mode, candidate.connector, candidate.mode
+                )
+                .if_supports_color(so, |t| t.yellow()),
+            );
+        }
+
+        let comparison = compare_reports(&baseline, &candidate, thresholds);
+        print_comparison(so, &comparison, &baseline);
+        regressions += comparison.regression_count();
+        missing += comparison.missing_in_candidate.len();
+    }
+
+    println!();
+    let missing_fails = missing > 0 && !args.allow_missing;
+    if missing_fails {
+        println!(
+            "{}",
+            format!(
+                "{missing} baseline pass(es) missing from candidate reports (pass --allow-missing to ignore)."
+            )
+            .if_supports_color(so, |t| t.style(Style::new().red().bold())),
+        );
+    }
+    if regressions > 0 {
+        println!(
+            "{}",
+            format!(
+                "{regressions} pass(es) regressed beyond thresholds (throughput drop > {}%, latency rise > {}%).",
+                thresholds.max_throughput_drop_pct, thresholds.max_latency_rise_pct
+            )
+            .if_supports_color(so, |t| t.style(Style::new().red().bold())),
+        );
+    }
+    if regressions > 0 || missing_fails {
+        return Ok(CliOutcome::Regression);
+    }
+
+    println!(
+        "{}",
+        "No regressions beyond thresholds."
+            .if_supports_color(so, |t| t.style(Style::new().green().bold())),
+    );
+    Ok(CliOutcome::Completed)
+}
+
+/// `  baseline: card=A1, slot=2 | after firmware update`; nothing for untagged reports.
+fn print_report_tags(so: Stream, role: &str, report: &BenchmarkReport) {
+    let tags = report_tags(report);
+    if tags.is_empty() {
+        return;
+    }
+    println!(
+        "  {} {}",
+        format!("{role}:").if_supports_color(so, |t| t.cyan()),
+        tags.if_supports_color(so, |t| t.bright_white()),
+    );
+}
+
+fn report_tags(report: &BenchmarkReport) -> String {
+    let labels = format_labels(&report.labels);
+    match (&report.note, labels.is_empty()) {
+        (Some(note), true) => note.clone(),
+        (Some(note), false) => format!("{labels} | {note}"),
+        (None, _) => labels,
+    }
+}
+
+fn print_comparison(so: Stream, comparison: &ReportComparison, baseline: &BenchmarkReport) {
+    println!(
+        "  {}  {}  {}  {}  {}  {}  {}",
+        format!("{:<6}", "Op").if_supports_color(so, |t| t.style(Style::new().green().bold())),
+        format!("{:<10}", "Size")
+            .if_supports_color(so, |t| t.style(Style::new().bright_yellow().bold())),
+        format!("{:<10}", "Metric").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:>12}", "Baseline").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:>12}", "Candidate").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:>12}", "Delta").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:>9}", "Change").if_supports_color(so, |t| t.style(Style::new().bold())),
+    );
+
+    let mut current_step = None;
+    for pass in &comparison.passes {
+        if pass.step != current_step {
+            current_step = pass.step;
+            if let Some(name) = baseline.step_name(pass.step) {
+                println!(
+                    "  {}",
+                    format!("step: {name}").if_supports_color(so, |t| t.cyan()),
+                );
+            }
+        }
+        for &(metric, delta) in &pass.metrics {
+            print_metric_row(so, pass, metric, delta);
+        }
+    }
+
+    for (label, missing) in [
+        ("missing from candidate", &comparison.missing_in_candidate),
+        ("not in baseline", &comparison.missing_in_baseline),
+    ] {
+        for (op, chunk_bytes) in missing {
+            println!(
+                "  {}",
+                format!("{} {} {label}", op.label(), format_chunk_size(*chunk_bytes))
+                    .if_supports_color(so, |t| t.dimmed()),
+            );
+        }
+    }
+}
+
+fn print_metric_row(so: Stream, pass: &PassComparison, metric: CompareMetric, delta: MetricDelta) {
+    let regressed = pass.regressions.contains(&metric);
+    let change = format!("{:>9}", format_percent_change(delta));
+    let precision = if metric == CompareMetric::Latency {
+        1
+    } else {
+        2
+    };
+    println!(
+        "  {}  {}  {:<10}  {:>12.precision$}  {:>12.precision$}  {:>+12.precision$}  {}",
+        format!("{:<6}", op_cell(pass, metric))
+            .if_supports_color(so, |t| t.style(Style::new().green().bold())),
+        format!("{:<10}", size_cell(pass, metric))
+            .if_supports_color(so, |t| t.style(Style::new().bright_yellow().bold())),
+        metric.label(),
+        delta.baseline,
+        delta.candidate,
+        delta.delta(),
+        if regressed {
+            change
+                .if_supports_color(so, |t| t.style(Style::new().red().bold()))
+                .to_string()
+        } else {
+            change.if_supports_color(so, |t| t.dimmed()).to_string()
+        },
+    );
+}
+
+/// Op/size only on the first metric row of each pass.
+fn op_cell(pass: &PassComparison, metric: CompareMetric) -> &'static str {
+    if metric == CompareMetric::ALL[0] {
+        pass.op.label()
+    } else {
+        ""
+    }
+}
+
+fn size_cell(pass: &PassComparison, metric: CompareMetric) -> String {
+    if metric == CompareMetric::ALL[0] {
+        format_chunk_size(pass.chunk_bytes)
+    } else {
+        String::new()
+    }
+}
+
+fn format_percent_change(delta: MetricDelta) -> String {
+    delta
+        .percent_change()
+        .map(|pct| format!("{pct:+.1}%"))
+        .unwrap_or_else(|| "n/a".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::BenchOp;
+
+    #[test]
+    fn percent_change_cell_handles_zero_baseline() {
+        assert_eq!(
+            format_percent_change(MetricDelta {
+                baseline: 100.0,
+                candidate: 90.0
+            }),
+            "-10.0%"
+        );
+        assert_eq!(
+            format_percent_change(MetricDelta {
+                baseline: 0.0,
+                candidate: 1.0
+            }),
+            "n/a"
+        );
+    }
+
+    #[test]
+    fn rejects_negative_thresholds() {
+        let args = CompareArgs {
+            reports: vec![PathBuf::from("a.json"), PathBuf::from("b.json")],
+            max_throughput_drop: -1.0,
+            max_latency_rise: 10.0,
+            allow_missing: false,
+        };
+
+        let err = run_compare(&args).unwrap_err();
+        assert!(err.to_string().contains("--max-throughput-drop"));
+    }
+
+    #[test]
+    fn missing_candidate_passes_regress_unless_allowed() {
+        use crate::speedtest::{BenchMode, PassAggregator, ReportFormat, write_report_to_path};
+
+        let report = |sizes: &[usize]| {
+            BenchmarkReport::test_report(
+                BenchMode::Read,
+                sizes,
+                sizes
+                    .iter()
+                    .map(|&size| PassAggregator::new(BenchOp::Read, size).finish())
+                    .collect(),
+            )
+        };
+        let dir = std::env::temp_dir().join(format!(
+            "dma-speedtest-compare-missing-{}",
+            std::process::id()
+        ));
+        let baseline = dir.join("baseline.json");
+        let candidate = dir.join("candidate.json");
+        write_report_to_path(&report(&[4096, 8192]), ReportFormat::Json, &baseline).unwrap();
+        write_report_to_path(&report(&[4096]), ReportFormat::Json, &candidate).unwrap();
+
+        let mut args = CompareArgs {
+            reports: vec![baseline, candidate],
+            max_throughput_drop: 5.0,
+            max_latency_rise: 10.0,
+            allow_missing: false,
+        };
+        assert_eq!(run_compare(&args).unwrap(), CliOutcome::Regression);
+        args.allow_missing = true;
+        assert_eq!(run_compare(&args).unwrap(), CliOutcome::Completed);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn op_label_only_on_first_metric_row() {
+        let pass = PassComparison {
+            op: BenchOp::Read,
+            chunk_bytes: 4096,
+            step: None,
+            metrics: Vec::new(),
+            regressions: Vec::new(),
+        };
+        assert_eq!(op_cell(&pass, CompareMetric::Throughput), "read");
+        assert_eq!(op_cell(&pass, CompareMetric::Latency), "");
+    }
+
+    #[test]
+    fn report_tags_join_labels_and_note() {
+        use crate::speedtest::BenchMode;
+
+        let report = BenchmarkReport::test_report(BenchMode::Read, &[4096], Vec::new());
+        assert_eq!(report_tags(&report), "");
+
+        let report = report.with_note("  ").with_labels(
+            [("card".to_string(), "A1".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        assert_eq!(report_tags(&report), "card=A1");
+        assert_eq!(
+            report_tags(&report.with_note("new firmware")),
+            "card=A1 | new firmware"
+        );
+    }
+}
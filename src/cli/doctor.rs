@@ -0,0 +1,151 @@
+//! `doctor` subcommand: run the connection stages one by one and say which one breaks.
+
+use anyhow::Result;
+use memflow::prelude::v1::Address;
+use owo_colors::OwoColorize;
+use owo_colors::{Stream, Style};
+use std::path::PathBuf;
+
+use super::{CliConnector, CliOutcome, parse_address};
+use crate::bench_config::DEFAULT_CHUNK_SIZES;
+use crate::speedtest::{
+    DoctorSettings, DoctorStage, StageStatus, WRITE_MUTATION_WARNING, WriteTargetPolicy, run_doctor,
+};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct DoctorArgs {
+    #[arg(long, value_enum, default_value_t = CliConnector::Pcileech)]
+    pub connector: CliConnector,
+
+    #[arg(
+        long,
+        default_value = "FPGA",
+        help = "PCILeech device string (ignored for native connector)."
+    )]
+    pub device: String,
+
+    #[arg(
+        long,
+        default_value_t = DEFAULT_CHUNK_SIZES[DEFAULT_CHUNK_SIZES.len() - 1],
+        help = "Largest chunk size the write region must hold."
+    )]
+    pub max_chunk_bytes: usize,
+
+    #[arg(
+        long,
+        value_parser = parse_address,
+        help = "Check this write probe address instead of auto-selecting one."
+    )]
+    pub write_addr: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Write and read back a canary in the chosen region, then restore it (default: nothing is written)."
+    )]
+    pub verify_write: bool,
+
+    #[arg(long, value_name = "PATH", help = "Save the stage results as JSON.")]
+    pub output: Option<PathBuf>,
+}
+
+impl DoctorArgs {
+    pub fn settings(&self) -> DoctorSettings {
+        DoctorSettings {
+            connector: self.connector.into(),
+            device: self.device.clone(),
+            max_chunk_bytes: self.max_chunk_bytes,
+            write_policy: WriteTargetPolicy {
+                address: self.write_addr.map(Address::from),
+                ..WriteTargetPolicy::default()
+            },
+            verify_write: self.verify_write,
+        }
+    }
+}
+
+pub fn run_doctor_cmd(args: &DoctorArgs) -> Result<CliOutcome> {
+    let so = Stream::Stdout;
+    let settings = args.settings();
+    println!(
+        "{}",
+        "Connection doctor".if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+    );
+    if settings.verify_write {
+        println!(
+            "{} {}",
+            "warning:".if_supports_color(so, |t| t.style(Style::new().yellow().bold())),
+            WRITE_MUTATION_WARNING.if_supports_color(so, |t| t.yellow()),
+        );
+    }
+
+    let report = run_doctor(&settings, |stage| print_stage(so, stage));
+
+    if let Some(path) = &args.output {
+        report.save_json(path)?;
+        println!(
+            "{} {}",
+            "Saved doctor report to".if_supports_color(so, |t| t.cyan()),
+            path.display()
+                .to_string()
+                .if_supports_color(so, |t| t.bright_white()),
+        );
+    }
+
+    match report.first_failure() {
+        None => {
+            println!(
+                "{}",
+                "All stages passed."
+                    .if_supports_color(so, |t| t.style(Style::new().green().bold())),
+            );
+            Ok(CliOutcome::Completed)
+        }
+        Some(failed) => {
+            println!(
+                "{} {}",
+                "Stopped at:".if_supports_color(so, |t| t.style(Style::new().red().bold())),
+                failed.stage.label(),
+            );
+            Ok(CliOutcome::DiagnosticsFailed)
+        }
+    }
+}
+
+fn print_stage(so: Stream, stage: &DoctorStage) {
+    let status = format!("{:<7}", stage.status.label());
+    let status = match stage.status {
+        StageStatus::Ok => status
+            .if_supports_color(so, |t| t.style(Style::new().green().bold()))
+            .to_string(),
+        StageStatus::Failed => status
+            .if_supports_color(so, |t| t.style(Style::new().red().bold()))
+            .to_string(),
+        StageStatus::Skipped => status.if_supports_color(so, |t| t.dimmed()).to_string(),
+    };
+    let elapsed = match stage.status {
+        StageStatus::Skipped => String::new(),
+        _ => format!("{:.1} ms", stage.elapsed_ms),
+    };
+    println!(
+        "  {status} {}  {}",
+        format!("{:<15}", stage.stage.label()).if_supports_color(so, |t| t.bright_white()),
+        format!("{elapsed:>10}").if_supports_color(so, |t| t.cyan()),
+    );
+    println!(
+        "          {}",
+        stage.detail.if_supports_color(so, |t| t.dimmed())
+    );
+    if let Some(kind) = stage.error_kind {
+        println!(
+            "          {} {}",
+            "Category:".if_supports_color(so, |t| t.cyan()),
+            kind.label(),
+        );
+    }
+    for (index, hint) in stage.hints.iter().enumerate() {
+        println!(
+            "          {} {hint}",
+            format!("{}.", index + 1).if_supports_color(so, |t| t.dimmed()),
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Run events for `--stream ndjson` (stdout) and `--agent-listen` (TCP viewers), and the
+//! session observer that prints a headless run.
+
+use owo_colors::{OwoColorize, Stream, Style};
+use std::io::{self, Write};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use super::{
+    StreamFormat, format_chunk_size, print_between_read_size_sections, print_colored_live_sample,
+    print_op_probe_detail, print_probe_details, print_step_header,
+};
+use crate::speedtest::{
+    AgentServer, BenchMetrics, BenchObserver, BenchOp, BenchSample, ConnectPhase, ConnectTimings,
+    PassSummary, SessionStep, SpeedTest, StreamEvent, TargetLost, WriteRestoreOutcome,
+};
+
+#[derive(Clone)]
+pub(super) struct RunEvents {
+    stdout: bool,
+    agent: Option<Arc<AgentServer>>,
+}
+
+impl RunEvents {
+    pub(super) fn new(stream: Option<StreamFormat>, agent: Option<AgentServer>) -> Self {
+        Self {
+            stdout: matches!(stream, Some(StreamFormat::Ndjson)),
+            agent: agent.map(Arc::new),
+        }
+    }
+
+    /// `true` when samples go to stdout as events instead of the live table.
+    pub(super) fn on_stdout(&self) -> bool {
+        self.stdout
+    }
+
+    pub(super) fn agent(&self) -> Option<&AgentServer> {
+        self.agent.as_deref()
+    }
+
+    pub(super) fn emit(&self, event: &StreamEvent) {
+        if self.stdout {
+            emit_stream_event(event);
+        }
+        if let Some(agent) = &self.agent {
+            agent.publish(event);
+        }
+    }
+}
+
+/// Prints the run on `so` and forwards it to [`RunEvents`] and the Prometheus metrics.
+pub(super) struct CliObserver {
+    so: Stream,
+    events: RunEvents,
+    metrics: Option<BenchMetrics>,
+    /// No separator before the first pass of the run or of a step.
+    first_block: AtomicBool,
+}
+
+impl CliObserver {
+    pub(super) fn new(so: Stream, events: RunEvents, metrics: Option<BenchMetrics>) -> Self {
+        Self {
+            so,
+            events,
+            metrics,
+            first_block: AtomicBool::new(true),
+        }
+    }
+}
+
+impl BenchObserver for CliObserver {
+    fn connect_phase(&self, phase: ConnectPhase) {
+        let so = self.so;
+        outln!(
+            so,
+            "{}",
+            format!("{}...", phase.label()).if_supports_color(so, |t| t.dimmed())
+        );
+    }
+
+    fn connect_failed(&self, timings: &ConnectTimings) {
+        let so = self.so;
+        for line in timings.detail_lines() {
+            outln!(so, "{}", line.if_supports_color(so, |t| t.dimmed()));
+        }
+    }
+
+    fn step_start(&self, index: usize, step: &SessionStep) {
+        print_step_header(self.so, step);
+        self.events.emit(&StreamEvent::StepStart {
+            index,
+            name: step.name.clone(),
+            mode: step.mode,
+        });
+        self.first_block.store(true, Ordering::Relaxed);
+    }
+
+    fn pass_start(&self, test: &SpeedTest, op: BenchOp, chunk_bytes: usize, step: &SessionStep) {
+        let so = self.so;
+        if !self.first_block.swap(false, Ordering::Relaxed) {
+            print_between_read_size_sections(so);
+        }
+        outln!(
+            so,
+            "{} {} {} ({})",
+            op.label()
+                .if_supports_color(so, |t| t.style(Style::new().green().bold())),
+            "size".if_supports_color(so, |t| t.white()),
+            format_chunk_size(chunk_bytes)
+                .if_supports_color(so, |t| { t.style(Style::new().bright_yellow().bold()) }),
+            format!("{chunk_bytes} B").if_supports_color(so, |t| t.dimmed()),
+        );
+        print_op_probe_detail(so, &test.probe_targets(), op, chunk_bytes);
+        self.events.emit(&StreamEvent::PassStart {
+            op,
+            chunk_bytes,
+            duration_secs: step.duration_secs,
+        });
+    }
+
+    fn sample(&self, sample: &BenchSample) {
+        if !self.events.on_stdout() {
+            print_colored_live_sample(sample);
+        }
+        self.events.emit(&StreamEvent::Sample(*sample));
+        if let Some(metrics) = &self.metrics
+            && let Err(e) = metrics.record_sample(sample)
+        {
+            self.warning(&format!("warning: {e:#}"));
+        }
+    }
+
+    fn warning(&self, message: &str) {
+        outln!(self.so, "  {message}");
+        self.events.emit(&StreamEvent::Warning {
+            message: message.to_string(),
+        });
+    }
+
+    fn pass_end(&self, summary: &PassSummary) {
+        if let Some(metrics) = &self.metrics
+            && let Err(e) = metrics.finish_pass(summary)
+        {
+            self.warning(&format!("warning: {e:#}"));
+        }
+        self.events.emit(&StreamEvent::PassSummary(summary.clone()));
+    }
+
+    fn reconnecting(&self, lost: &TargetLost) {
+        eprintln!(
+            "{} {lost}; reconnecting and resuming with the next pass",
+            warning_label()
+        );
+    }
+
+    fn reconnect_failed(&self, error: &anyhow::Error) {
+        eprintln!("{} reconnect failed: {error}", warning_label());
+    }
+
+    fn reconnected(&self, test: &SpeedTest) {
+        let so = self.so;
+        outln!(
+            so,
+            "{}",
+            "Reconnected.".if_supports_color(so, |t| t.dimmed())
+        );
+        print_probe_details(so, &test.probe_connect_detail_lines());
+    }
+
+    fn write_restored(&self, outcome: &anyhow::Result<WriteRestoreOutcome>) {
+        let so = self.so;
+        match outcome {
+            Ok(WriteRestoreOutcome::Restored) => outln!(
+                so,
+                "{}",
+                "Write probe original bytes restored.".if_supports_color(so, |t| t.dimmed()),
+            ),
+            Ok(WriteRestoreOutcome::SkippedForeignData) => eprintln!(
+                "{} write probe restore skipped; the target process is using the region",
+                warning_label(),
+            ),
+            Ok(WriteRestoreOutcome::SkippedTargetLost) => eprintln!(
+                "{} write probe restore skipped; the target is no longer reachable",
+                warning_label(),
+            ),
+            Ok(WriteRestoreOutcome::NotNeeded) => {}
+            Err(e) => eprintln!(
+                "{} failed to restore write probe bytes: {e}",
+                warning_label()
+            ),
+        }
+    }
+}
+
+fn warning_label() -> impl std::fmt::Display {
+    "Warning:".if_supports_color(Stream::Stderr, |t| t.style(Style::new().yellow().bold()))
+}
+
+/// Write one NDJSON line and flush so consumers see each event as it happens.
+fn emit_stream_event(event: &StreamEvent) {
+    let mut stdout = io::stdout().lock();
+    // A closed pipe should not abort the benchmark; the run still restores and reports.
+    let _ = writeln!(stdout, "{}", event.to_json_line());
+    let _ = stdout.flush();
+}
@@ -0,0 +1,257 @@
+//! `history` subcommand: list, show and trend runs recorded with `--history`.
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+use owo_colors::{Stream, Style};
+use std::path::PathBuf;
+
+use super::{CliOutcome, print_summary};
+use crate::bench_config::format_chunk_size;
+use crate::speedtest::{
+    BenchOp, CompareMetric, DEFAULT_HISTORY_PATH, HistoryEntry, RunHistory, format_labels,
+    metric_trend,
+};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct HistoryArgs {
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        default_value = DEFAULT_HISTORY_PATH,
+        help = "History file written by --history."
+    )]
+    pub file: PathBuf,
+
+    #[command(subcommand)]
+    pub command: HistoryCommand,
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum HistoryCommand {
+    /// List recorded runs, oldest first.
+    List,
+    /// Print the summary table of one recorded run.
+    Show {
+        /// Run id from `history list`.
+        id: u64,
+    },
+    /// Print one pass metric across recorded runs.
+    Trend {
+        #[arg(long, value_enum)]
+        op: HistoryOp,
+        #[arg(long, value_name = "BYTES", help = "Chunk size in bytes (e.g. 4096).")]
+        size: usize,
+        #[arg(long, value_enum, default_value_t = TrendMetric::Throughput)]
+        metric: TrendMetric,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum HistoryOp {
+    Read,
+    Write,
+}
+
+impl From<HistoryOp> for BenchOp {
+    fn from(op: HistoryOp) -> Self {
+        match op {
+            HistoryOp::Read => BenchOp::Read,
+            HistoryOp::Write => BenchOp::Write,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum TrendMetric {
+    /// Average MiB/s.
+    Throughput,
+    /// Average ops/s.
+    Ops,
+    /// Average latency in μs.
+    Latency,
+}
+
+impl From<TrendMetric> for CompareMetric {
+    fn from(metric: TrendMetric) -> Self {
+        match metric {
+            TrendMetric::Throughput => CompareMetric::Throughput,
+            TrendMetric::Ops => CompareMetric::OpsPerSec,
+            TrendMetric::Latency => CompareMetric::Latency,
+        }
+    }
+}
+
+pub fn run_history(args: &HistoryArgs) -> Result<CliOutcome> {
+    let history = RunHistory::new(&args.file);
+    let so = Stream::Stdout;
+    match &args.command {
+        HistoryCommand::List => print_run_list(so, &history.load()?),
+        HistoryCommand::Show { id } => print_run(so, &history.get(*id)?),
+        HistoryCommand::Trend { op, size, metric } => {
+            let (op, metric) = ((*op).into(), (*metric).into());
+            print_trend(so, &history.load()?, op, *size, metric);
+        }
+    }
+    Ok(CliOutcome::Completed)
+}
+
+fn print_run_list(so: Stream, entries: &[HistoryEntry]) {
+    if entries.is_empty() {
+        println!(
+            "{}",
+            "No runs recorded.".if_supports_color(so, |t| t.dimmed())
+        );
+        return;
+    }
+
+    println!(
+        "  {}  {}  {}  {}  {}  {}  {}",
+        format!("{:>5}", "Run").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:>12}", "Generated").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:<10}", "Connector").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:<6}", "Mode").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:<16}", "Host").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:>6}", "Passes").if_supports_color(so, |t| t.style(Style::new().bold())),
+        "Labels".if_supports_color(so, |t| t.style(Style::new().bold())),
+    );
+    for entry in entries {
+        let report = &entry.report;
+        let host = report
+            .environment
+            .as_ref()
+            .map(|environment| environment.hostname.as_str())
+            .unwrap_or("-");
+        println!(
+            "  {}  {:>12}  {:<10}  {:<6}  {:<16}  {:>6}  {}",
+            format!("{:>5}", entry.id).if_supports_color(so, |t| t.bright_white()),
+            report.generated_unix_secs,
+            report.connector,
+            report.mode,
+            host,
+            report.passes.len(),
+            format_labels(&report.labels).if_supports_color(so, |t| t.cyan()),
+        );
+    }
+}
+
+fn print_run(so: Stream, entry: &HistoryEntry) {
+    let report = &entry.report;
+    println!(
+        "{} {}",
+        "Run".if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+        entry
+            .id
+            .if_supports_color(so, |t| t.style(Style::new().bright_white().bold())),
+    );
+    let mut fields = vec![
+        ("version", report.version.clone()),
+        (
+            "generated_unix_secs",
+            report.generated_unix_secs.to_string(),
+        ),
+        ("connector", report.connector.clone()),
+        ("mode", report.mode.clone()),
+        ("duration", format!("{}s", report.duration_secs)),
+    ];
+    if let Some(environment) = &report.environment {
+        fields.extend([
+            ("host", environment.hostname.clone()),
+            ("target_pid", environment.target_pid.to_string()),
+        ]);
+    }
+    if !report.labels.is_empty() {
+        fields.push(("labels", format_labels(&report.labels)));
+    }
+    if let Some(note) = &report.note {
+        fields.push(("note", note.clone()));
+    }
+    for (name, value) in fields {
+        println!(
+            "  {}={}",
+            name.if_supports_color(so, |t| t.cyan()),
+            value.if_supports_color(so, |t| t.bright_white()),
+        );
+    }
+    print_summary(so, report);
+}
+
+fn print_trend(
+    so: Stream,
+    entries: &[HistoryEntry],
+    op: BenchOp,
+    chunk_bytes: usize,
+    metric: CompareMetric,
+) {
+    let points = metric_trend(entries, op, chunk_bytes, metric);
+    println!(
+        "{} {} {} ({})",
+        metric
+            .label()
+            .if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+        op.label()
+            .if_supports_color(so, |t| t.style(Style::new().green().bold())),
+        format_chunk_size(chunk_bytes)
+            .if_supports_color(so, |t| t.style(Style::new().bright_yellow().bold())),
+        format!("{} run(s)", points.len()).if_supports_color(so, |t| t.dimmed()),
+    );
+    let Some(first) = points.first() else {
+        return;
+    };
+
+    let precision = if metric == CompareMetric::Latency {
+        1
+    } else {
+        2
+    };
+    for point in &points {
+        let change = if first.value != 0.0 {
+            format!("{:+.1}%", (point.value - first.value) / first.value * 100.0)
+        } else {
+            "n/a".to_string()
+        };
+        println!(
+            "  {}  {:>12}  {:>12.precision$}  {}",
+            format!("{:>5}", point.run_id).if_supports_color(so, |t| t.bright_white()),
+            point.generated_unix_secs,
+            point.value,
+            format!("{change:>9}").if_supports_color(so, |t| t.dimmed()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{CliArgs, CliCommand};
+    use clap::Parser;
+
+    #[test]
+    fn parses_history_subcommands() {
+        let args = CliArgs::parse_from([
+            "cli-dma-speedtest",
+            "history",
+            "trend",
+            "--op",
+            "write",
+            "--size",
+            "4096",
+            "--metric",
+            "latency",
+            "--file",
+            "runs.jsonl",
+        ]);
+        let Some(CliCommand::History(history)) = args.command else {
+            panic!("expected history subcommand");
+        };
+        assert_eq!(history.file, PathBuf::from("runs.jsonl"));
+        assert!(matches!(
+            history.command,
+            HistoryCommand::Trend {
+                op: HistoryOp::Write,
+                size: 4096,
+                metric: TrendMetric::Latency
+            }
+        ));
+    }
+}
@@ -0,0 +1,1929 @@
+//! CLI benchmarking implementation used by the prefixed CLI binary.
+
+/// `println!` on the human-output stream: stdout, or stderr while `--stream` owns stdout.
+macro_rules! outln {
+    ($so:expr) => {
+        outln!($so, "")
+    };
+    ($so:expr, $($arg:tt)*) => {
+        match $so {
+            owo_colors::Stream::Stderr => eprintln!($($arg)*),
+            _ => println!($($arg)*),
+        }
+    };
+}
+
+mod compare;
+mod doctor;
+mod events;
+mod history;
+mod profile;
+mod serve;
+mod verdict;
+
+pub use compare::{CompareArgs, run_compare};
+pub use doctor::{DoctorArgs, run_doctor_cmd};
+pub use history::{HistoryArgs, HistoryCommand, run_history};
+pub use profile::{parse_cli_args, profile_from_args};
+pub use serve::{ServeArgs, run_serve};
+
+use events::{CliObserver, RunEvents};
+
+use anyhow::{Result, anyhow, bail};
+use clap::Parser;
+use memflow::prelude::v1::Address;
+use owo_colors::OwoColorize;
+use owo_colors::{Stream, Style};
+use std::{
+    io::{self, IsTerminal, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::bench_config::{
+    DEFAULT_CHUNK_SIZES, chunk_sizes_from_optional_csv, default_chunk_sizes_csv, format_byte_count,
+    format_chunk_size,
+};
+use crate::speedtest::{
+    AgentServer, BenchErrorKind, BenchMetrics, BenchMode, BenchOp, BenchmarkReport,
+    BenchmarkSession, CompareThresholds, Connector, DEFAULT_HISTORY_PATH, GateCriteria,
+    MetricsServer, PassSummary, ProbeTargets, ReportFormat, RunHistory, Scenario, SessionStep,
+    SizeLimit, SpeedTest, StreamEvent, WRITE_MUTATION_WARNING, WriteCandidate, WritePageFlag,
+    WriteTargetPolicy, default_report_path, evaluate_gate, format_page_type, live_sample_columns,
+    load_report_from_path, parse_label, resolve_report_format, select_write_candidate,
+    summary_groups, write_report_to_path,
+};
+
+/// Alias for [`DEFAULT_CHUNK_SIZES`].
+pub const DEFAULT_READ_SIZES: [usize; 4] = DEFAULT_CHUNK_SIZES;
+
+/// Visual break between per-size benchmark sections in CLI output.
+const BETWEEN_READ_SIZE_SECTIONS: &str =
+    "--------------------------------------------------------------------";
+
+fn print_between_read_size_sections(so: Stream) {
+    outln!(
+        so,
+        "{}",
+        BETWEEN_READ_SIZE_SECTIONS.if_supports_color(so, |t| t.dimmed()),
+    );
+}
+
+#[derive(Copy, Clone, Default, Debug, clap::ValueEnum)]
+pub enum CliConnector {
+    #[default]
+    Pcileech,
+    Native,
+}
+
+impl From<CliConnector> for Connector {
+    fn from(h: CliConnector) -> Self {
+        match h {
+            CliConnector::Pcileech => Connector::Pcileech,
+            CliConnector::Native => Connector::Native,
+        }
+    }
+}
+
+/// Machine-readable live output selected with `--stream`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum StreamFormat {
+    /// One JSON object per line: pass starts, samples, warnings and pass summaries.
+    Ndjson,
+}
+
+#[derive(Copy, Clone, Default, Debug, clap::ValueEnum)]
+pub enum CliBenchMode {
+    #[default]
+    Read,
+    Write,
+    Both,
+}
+
+impl From<CliBenchMode> for BenchMode {
+    fn from(m: CliBenchMode) -> Self {
+        match m {
+            CliBenchMode::Read => BenchMode::Read,
+            CliBenchMode::Write => BenchMode::Write,
+            CliBenchMode::Both => BenchMode::Both,
+        }
+    }
+}
+
+/// How a CLI run ended when it did not error out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CliOutcome {
+    Completed,
+    /// A comparison found regressions beyond the configured thresholds.
+    Regression,
+    /// The benchmark ran but failed `--baseline` / `--min-throughput` / `--max-latency` gating.
+    GateFailed,
+    /// `doctor` found a connection stage that fails.
+    DiagnosticsFailed,
+}
+
+impl CliOutcome {
+    /// Process exit code; regressions use 2 so scripts can tell them from errors (1).
+    pub fn exit_code(self) -> u8 {
+        match self {
+            CliOutcome::Completed => 0,
+            CliOutcome::Regression => 2,
+            CliOutcome::GateFailed => 3,
+            CliOutcome::DiagnosticsFailed => 4,
+        }
+    }
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum CliCommand {
+    /// Compare saved JSON reports against a baseline and flag regressions.
+    Compare(CompareArgs),
+    /// Diagnose the connection stage by stage: timings, failures and hints.
+    Doctor(DoctorArgs),
+    /// List, show and trend runs recorded with --history.
+    History(HistoryArgs),
+    /// Serve a local JSON API to connect, start, stop and follow runs from other tools.
+    Serve(ServeArgs),
+}
+
+#[derive(Parser)]
+#[command(
+    name = "cli-dma-speedtest-memflow-rs",
+    version,
+    args_conflicts_with_subcommands = true,
+    about = "DMA benchmark (terminal). Defaults: PCILeech, device FPGA, 10 s per size, chunk sizes 4–32 KiB. Other granularities are optional: pass --sizes to select them (same idea as ticking sizes in the GUI)."
+)]
+pub struct CliArgs {
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+
+    #[arg(long, value_enum, default_value_t = CliConnector::Pcileech)]
+    pub connector: CliConnector,
+
+    #[arg(
+        long,
+        default_value = "FPGA",
+        help = "PCILeech device string (ignored for native connector)."
+    )]
+    pub device: String,
+
+    #[arg(long, default_value_t = 10, help = "Seconds per chunk size (1–60).")]
+    pub duration: u64,
+
+    #[arg(long, value_enum, default_value_t = CliBenchMode::Read)]
+    pub mode: CliBenchMode,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Optional override: comma-separated chunk sizes in bytes, max 16 MiB each. If omitted, only the default 4–32 KiB set runs (same as the GUI defaults). When set, this list replaces the default entirely."
+    )]
+    pub sizes: Option<Vec<usize>>,
+
+    #[arg(
+        long,
+        help = "Optional report output path (.csv, .json, .md, .html, or .xml for JUnit)."
+    )]
+    pub output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Report format when --output extension is missing or should be overridden."
+    )]
+    pub output_format: Option<ReportFormat>,
+
+    #[arg(
+        long,
+        value_parser = parse_address,
+        help = "Pin the write probe to this virtual address (hex, e.g. 0x1f0000) instead of auto-selecting one."
+    )]
+    pub write_addr: Option<u64>,
+
+    #[arg(
+        long,
+        requires = "write_addr",
+        help = "Write region length in bytes at --write-addr (default: rest of the writable segment)."
+    )]
+    pub write_region_bytes: Option<u64>,
+
+    #[arg(long, help = "Skip write regions smaller than this many bytes.")]
+    pub write_min_region: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Skip write regions larger than this many bytes (large segments are usually heaps)."
+    )]
+    pub write_max_region: Option<u64>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        help = "Rank write regions with these page types first (comma-separated)."
+    )]
+    pub write_prefer: Vec<WritePageFlag>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        help = "Never select write regions with these page types (comma-separated)."
+    )]
+    pub write_avoid: Vec<WritePageFlag>,
+
+    #[arg(
+        long,
+        help = "Connect, list write probe candidates with sizes and page flags, and exit without writing."
+    )]
+    pub list_write_candidates: bool,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "If the target process exits or the device drops mid-run, reconnect up to this many times and resume the remaining passes."
+    )]
+    pub reconnect_attempts: u32,
+
+    #[arg(
+        long,
+        help = "Include every 100 ms interval sample in the report (JSON body, a companion <name>-samples.csv for CSV, time-series charts for HTML)."
+    )]
+    pub include_samples: bool,
+
+    #[arg(
+        long,
+        help = "Gate the run against this JSON report; regressions beyond --max-throughput-drop / --max-latency-rise fail it."
+    )]
+    pub baseline: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "baseline",
+        default_value_t = CompareThresholds::default().max_throughput_drop_pct,
+        help = "Largest tolerated drop in average MiB/s versus --baseline, in percent."
+    )]
+    pub max_throughput_drop: f64,
+
+    #[arg(
+        long,
+        requires = "baseline",
+        default_value_t = CompareThresholds::default().max_latency_rise_pct,
+        help = "Largest tolerated rise in average latency versus --baseline, in percent."
+    )]
+    pub max_latency_rise: f64,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = SizeLimit::parse,
+        help = "Fail passes below this average MiB/s: VALUE for every size, or SIZE_BYTES=VALUE (comma-separated)."
+    )]
+    pub min_throughput: Vec<SizeLimit>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = SizeLimit::parse,
+        help = "Fail passes above this average latency in μs: VALUE for every size, or SIZE_BYTES=VALUE (comma-separated)."
+    )]
+    pub max_latency: Vec<SizeLimit>,
+
+    #[arg(
+        long,
+        value_name = "ADDR",
+        help = "Serve Prometheus metrics at http://ADDR/metrics while the run is active (e.g. 127.0.0.1:9899)."
+    )]
+    pub metrics_listen: Option<SocketAddr>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Mirror Prometheus metrics to a node-exporter textfile (.prom), rewritten at most once per second."
+    )]
+    pub metrics_textfile: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        requires = "metrics_listen",
+        help = "Keep the metrics endpoint up this many seconds after the run so the per-pass summary can be scraped."
+    )]
+    pub metrics_linger: u64,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        help = "Write live events to stdout as one JSON object per line; human-readable output moves to stderr."
+    )]
+    pub stream: Option<StreamFormat>,
+
+    #[arg(
+        long,
+        value_name = "ADDR",
+        conflicts_with = "list_write_candidates",
+        help = "Run as a remote agent: wait for a viewer (e.g. the GUI) on this TCP address, then stream the run's events to it as NDJSON."
+    )]
+    pub agent_listen: Option<SocketAddr>,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        requires = "agent_listen",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Give up if no viewer connects to --agent-listen within this many seconds (default: wait until Ctrl-C)."
+    )]
+    pub agent_wait_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_HISTORY_PATH,
+        help = "Append the finished run to a local history file (default reports/history.jsonl); see the history subcommand."
+    )]
+    pub history: Option<PathBuf>,
+
+    #[arg(
+        long = "label",
+        value_name = "KEY=VALUE",
+        value_parser = parse_label,
+        help = "Tag the run; repeatable. Stored in the report and used in the default report file name."
+    )]
+    pub labels: Vec<(String, String)>,
+
+    #[arg(
+        long,
+        value_name = "TEXT",
+        help = "Free-form note stored with the report."
+    )]
+    pub note: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Load settings from a TOML or JSON profile; flags given on the command line override its fields."
+    )]
+    pub profile: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["mode", "sizes", "duration", "list_write_candidates"],
+        help = "Run the ordered steps of a TOML or JSON scenario over one connection; each step sets its own mode, sizes and duration."
+    )]
+    pub scenario: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write the effective settings to a TOML or JSON profile and exit without connecting."
+    )]
+    pub save_profile: Option<PathBuf>,
+}
+
+fn parse_address(input: &str) -> std::result::Result<u64, String> {
+    let trimmed = input.trim();
+    let digits = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+    u64::from_str_radix(digits, 16).map_err(|_| format!("invalid hex address {input:?}"))
+}
+
+impl CliArgs {
+    /// Gate limits from the flags; loads `--baseline` so a bad path fails before connecting.
+    pub fn gate_criteria(&self) -> Result<GateCriteria> {
+        let baseline_thresholds = CompareThresholds {
+            max_throughput_drop_pct: self.max_throughput_drop,
+            max_latency_rise_pct: self.max_latency_rise,
+        };
+        for (flag, value) in [
+            (
+                "--max-throughput-drop",
+                baseline_thresholds.max_throughput_drop_pct,
+            ),
+            (
+                "--max-latency-rise",
+                baseline_thresholds.max_latency_rise_pct,
+            ),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                bail!("{flag} must be a non-negative percentage");
+            }
+        }
+
+        Ok(GateCriteria {
+            min_throughput: self.min_throughput.clone(),
+            max_latency: self.max_latency.clone(),
+            baseline: self
+                .baseline
+                .as_deref()
+                .map(load_report_from_path)
+                .transpose()?,
+            baseline_thresholds,
+        })
+    }
+
+    pub fn write_target_policy(&self) -> WriteTargetPolicy {
+        WriteTargetPolicy {
+            address: self.write_addr.map(Address::from),
+            region_bytes: self.write_region_bytes,
+            min_region_bytes: self.write_min_region,
+            max_region_bytes: self.write_max_region,
+            prefer: self.write_prefer.clone(),
+            avoid: self.write_avoid.clone(),
+        }
+    }
+}
+
+pub fn default_cli_args() -> CliArgs {
+    CliArgs {
+        command: None,
+        connector: CliConnector::default(),
+        device: "FPGA".to_owned(),
+        duration: 10,
+        mode: CliBenchMode::Read,
+        sizes: None,
+        output: None,
+        output_format: None,
+        write_addr: None,
+        write_region_bytes: None,
+        write_min_region: None,
+        write_max_region: None,
+        write_prefer: Vec::new(),
+        write_avoid: Vec::new(),
+        list_write_candidates: false,
+        reconnect_attempts: 0,
+        include_samples: false,
+        baseline: None,
+        max_throughput_drop: CompareThresholds::default().max_throughput_drop_pct,
+        max_latency_rise: CompareThresholds::default().max_latency_rise_pct,
+        min_throughput: Vec::new(),
+        max_latency: Vec::new(),
+        metrics_listen: None,
+        metrics_textfile: None,
+        metrics_linger: 0,
+        stream: None,
+        agent_listen: None,
+        agent_wait_timeout: None,
+        history: None,
+        labels: Vec::new(),
+        note: None,
+        profile: None,
+        scenario: None,
+        save_profile: None,
+    }
+}
+
+pub fn print_startup_help() {
+    let so = Stream::Stdout;
+
+    println!(
+        "{}: {} [OPTIONS]",
+        "Usage".if_supports_color(so, |t| t.style(Style::new().cyan().bold())),
+        "cli-dma-speedtest-memflow-rs.exe"
+            .if_supports_color(so, |t| { t.style(Style::new().bright_white().bold()) }),
+    );
+    println!();
+    println!(
+        "{}",
+        "Options:".if_supports_color(so, |t| t.style(Style::new().magenta().bold())),
+    );
+    println!(
+        "  {} {} {}",
+        "Argument".if_supports_color(so, |t| t.style(Style::new().yellow().bold())),
+        format!("{:<16}", "[default]").if_supports_color(so, |t| t.dimmed()),
+        "Description".if_supports_color(so, |t| t.white()),
+    );
+    println!(
+        "{}",
+        "  --------------------------------------------------------------------------"
+            .if_supports_color(so, |t| t.dimmed()),
+    );
+
+    let row = |flag: &str, default: &str, desc: &str| {
+        println!(
+            "  {} {} {}",
+            flag.if_supports_color(so, |t| t.yellow()),
+            format!("{default:<16}").if_supports_color(so, |t| t.dimmed()),
+            desc,
+        );
+    };
+
+    row("--connector <CONNECTOR>", "[pcileech]", "pcileech | native");
+    row(
+        "--device <DEVICE>",
+        "[FPGA]",
+        "PCILeech device string (ignored for native)",
+    );
+    row(
+        "--duration <SECONDS>",
+        "[10]",
+        "seconds per chunk size (1–60)",
+    );
+    row("--mode <MODE>", "[read]", "read | write | both");
+    row(
+        "--sizes <CSV_BYTES>",
+        &default_chunk_sizes_csv(),
+        "optional override; replaces default list when set (max 16 MiB each)",
+    );
+    row(
+        "--output <PATH>",
+        "",
+        "optional report path (.csv/.json/.md/.html/.xml)",
+    );
+    row(
+        "--output-format <FMT>",
+        "",
+        "csv|json|markdown|html|junit; overrides extension",
+    );
+    row(
+        "--write-addr <HEX>",
+        "[auto]",
+        "pin the write probe address (write/both)",
+    );
+    row(
+        "--write-min-region <BYTES>",
+        "",
+        "skip smaller write regions",
+    );
+    row(
+        "--write-max-region <BYTES>",
+        "",
+        "skip larger write regions",
+    );
+    row(
+        "--write-prefer <FLAGS>",
+        "",
+        "noexec | exec | unknown; ranked first",
+    );
+    row(
+        "--write-avoid <FLAGS>",
+        "",
+        "noexec | exec | unknown; never selected",
+    );
+    row(
+        "--list-write-candidates",
+        "",
+        "list write regions and exit (no writes)",
+    );
+    row(
+        "--include-samples",
+        "",
+        "add per-interval samples to the report",
+    );
+    row(
+        "--baseline <PATH>",
+        "",
+        "pass/fail against a JSON report (exit 3 on fail)",
+    );
+    row(
+        "--min-throughput <LIMITS>",
+        "",
+        "MiB/s floor: VALUE or SIZE=VALUE, comma-separated",
+    );
+    row(
+        "--max-latency <LIMITS>",
+        "",
+        "μs ceiling: VALUE or SIZE=VALUE, comma-separated",
+    );
+    row(
+        "--metrics-listen <ADDR>",
+        "",
+        "serve Prometheus metrics at http://ADDR/metrics",
+    );
+    row(
+        "--metrics-textfile <PATH>",
+        "",
+        "write metrics for node-exporter's textfile collector",
+    );
+    row(
+        "--history [PATH]",
+        "",
+        "record the run in reports/history.jsonl",
+    );
+    row(
+        "--label <KEY=VALUE>",
+        "",
+        "tag the run (repeatable); --note <TEXT> adds a note",
+    );
+    row(
+        "--profile <PATH>",
+        "",
+        "load a .toml/.json profile; flags override it",
+    );
+    row(
+        "--scenario <PATH>",
+        "",
+        "run the ordered steps of a .toml/.json scenario",
+    );
+    row(
+        "--save-profile <PATH>",
+        "",
+        "write the effective settings as a profile and exit",
+    );
+    row(
+        "--stream ndjson",
+        "",
+        "JSON event per line on stdout; human text on stderr",
+    );
+    row(
+        "--agent-listen <ADDR>",
+        "",
+        "wait for a remote viewer, then stream events to it",
+    );
+    row(
+        "--agent-wait-timeout <SECS>",
+        "",
+        "give up if no viewer connects in time",
+    );
+    row(
+        "--reconnect-attempts <N>",
+        "[0]",
+        "reconnect after target exit / device loss",
+    );
+    row(
+        "compare <REPORTS>...",
+        "",
+        "diff JSON reports against the first (baseline)",
+    );
+    row(
+        "history list|show|trend",
+        "",
+        "inspect runs recorded with --history",
+    );
+    row(
+        "serve [--listen ADDR] [--token TOKEN]",
+        "[127.0.0.1:9898]",
+        "JSON control API for lab automation",
+    );
+    row(
+        "doctor [--verify-write] [--output PATH]",
+        "",
+        "check each connection stage and say which one fails",
+    );
+    row("-h, --help", "", "print clap help");
+    row("-V, --version", "", "print version");
+}
+
+pub fn print_more_options_hint() {
+    let so = Stream::Stdout;
+    println!(
+        "{}",
+        "For more options, run with --help.\n\n".if_supports_color(so, |t| t.dimmed()),
+    );
+}
+
+const INTERACTIVE_DEFAULTS_PROMPT_SECS: u64 = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LaunchMenuChoice {
+    RunDefaults,
+    Customize,
+}
+
+struct RawModeGuard;
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
+
+fn timed_defaults_yes_no_prompt(so: Stream) -> Result<LaunchMenuChoice> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+
+    const TICK: Duration = Duration::from_millis(100);
+
+    crossterm::terminal::enable_raw_mode()
+        .map_err(|e| anyhow::anyhow!("could not enable raw terminal mode: {e}"))?;
+    let _raw_guard = RawModeGuard;
+
+    let deadline =
+        std::time::Instant::now() + Duration::from_secs(INTERACTIVE_DEFAULTS_PROMPT_SECS);
+    let mut last_shown_secs: Option<u64> = None;
+
+    loop {
+        let left = deadline.saturating_duration_since(std::time::Instant::now());
+        if left.is_zero() {
+            print!("\r\x1b[2K");
+            io::stdout().flush()?;
+            println!();
+            return Ok(LaunchMenuChoice::RunDefaults);
+        }
+
+        let secs = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+        if last_shown_secs != Some(secs) {
+            last_shown_secs = Some(secs);
+            print!(
+                "\r\x1b[2K{} {}",
+                "Run with defaults? [Y/n]".if_supports_color(so, |t| t.style(Style::new().bold())),
+                format!("({secs}s)").if_supports_color(so, |t| t.dimmed()),
+            );
+            io::stdout().flush()?;
+        }
+
+        let poll_wait = TICK.min(left);
+        if event::poll(poll_wait)?
+            && let Event::Key(key) = event::read()?
+        {
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    print!("\r\x1b[2K");
+                    io::stdout().flush()?;
+                    println!();
+                    return Ok(LaunchMenuChoice::RunDefaults);
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') => {
+                    print!("\r\x1b[2K");
+                    io::stdout().flush()?;
+                    println!();
+                    return Ok(LaunchMenuChoice::Customize);
+                }
+                KeyCode::Esc => {
+                    print!("\r\x1b[2K");
+                    io::stdout().flush()?;
+                    println!();
+                    return Ok(LaunchMenuChoice::RunDefaults);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Prompt Y/n for defaults with a short idle countdown (Yes). Non-TTY stdin/stdout returns [`default_cli_args`] immediately.
+pub fn interactive_launch_cli_args() -> Result<CliArgs> {
+    use dialoguer::{Input, Select, theme::ColorfulTheme};
+
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        eprintln!(
+            "{}",
+            "stdin or stdout is not a terminal; using default benchmark settings (pass CLI flags to configure)."
+                .if_supports_color(Stream::Stderr, |t| t.dimmed()),
+        );
+        return Ok(default_cli_args());
+    }
+
+    let so = Stream::Stdout;
+    let prompt_choice = timed_defaults_yes_no_prompt(so)?;
+
+    match prompt_choice {
+        LaunchMenuChoice::RunDefaults => {
+            print_more_options_hint();
+            Ok(default_cli_args())
+        }
+        LaunchMenuChoice::Customize => {
+            let so = Stream::Stdout;
+            println!(
+                "{}",
+                "Customize — defaults if you press Enter on each prompt."
+                    .if_supports_color(so, |t| t.style(Style::new().cyan().bold())),
+            );
+            println!(
+                "{}",
+                "Defaults: PCILeech, device FPGA, 10 s per chunk size, sizes 4–32 KiB."
+                    .if_supports_color(so, |t| t.dimmed()),
+            );
+            println!();
+
+            let theme = ColorfulTheme::default();
+
+            let mode_idx = Select::with_theme(&theme)
+                .with_prompt("Benchmark mode")
+                .items(["read", "write", "both"])
+                .default(0)
+                .interact()
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+            let mode = match mode_idx {
+                0 => CliBenchMode::Read,
+                1 => CliBenchMode::Write,
+                2 => CliBenchMode::Both,
+                _ => CliBenchMode::Read,
+            };
+
+            let connector_idx = Select::with_theme(&theme)
+                .with_prompt("Connector")
+                .items(["pcileech", "native"])
+                .default(0)
+                .interact()
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+            let connector = if connector_idx == 0 {
+                CliConnector::Pcileech
+            } else {
+                CliConnector::Native
+            };
+
+            let duration = loop {
+                let s: String = Input::with_theme(&theme)
+                    .with_prompt("Seconds per chunk size (1–60)")
+                    .default("10".to_string())
+                    .interact_text()
+                    .map_err(|e| anyhow::anyhow!("{e}"))?;
+                match s.trim().parse::<u64>() {
+                    Ok(n) if (1..=60).contains(&n) => break n,
+                    _ => eprintln!("Please enter an integer from 1 to 60."),
+                }
+            };
+
+            let device = if matches!(connector, CliConnector::Pcileech) {
+                Input::with_theme(&theme)
+                    .with_prompt("PCILeech device")
+                    .default("FPGA".to_string())
+                    .interact_text()
+                    .map_err(|e| anyhow::anyhow!("{e}"))?
+            } else {
+                default_cli_args().device
+            };
+
+            let sizes_default = default_chunk_sizes_csv();
+
+            let sizes_str: String = Input::with_theme(&theme)
+                .with_prompt("Chunk sizes in bytes, comma-separated")
+                .default(sizes_default)
+                .interact_text()
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+            let sizes = chunk_sizes_from_optional_csv(sizes_str.trim())?;
+
+            let args = CliArgs {
+                connector,
+                device,
+                duration,
+                mode,
+                sizes,
+                ..default_cli_args()
+            };
+
+            let profile_path: String = Input::with_theme(&theme)
+                .with_prompt("Save as profile (.toml/.json path, Enter to skip)")
+                .allow_empty(true)
+                .interact_text()
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+            let profile_path = profile_path.trim().trim_matches('"');
+            if !profile_path.is_empty() {
+                let path = Path::new(profile_path);
+                profile_from_args(&args).save(path)?;
+                print_saved_profile(so, path);
+            }
+            Ok(args)
+        }
+    }
+}
+
+/// Run a subcommand if one was given, otherwise the benchmark.
+pub async fn run_cli_args(mut args: CliArgs) -> Result<CliOutcome> {
+    match args.command.take() {
+        Some(CliCommand::Compare(compare)) => run_compare(&compare),
+        Some(CliCommand::Doctor(doctor)) => run_doctor_cmd(&doctor),
+        Some(CliCommand::History(history)) => run_history(&history),
+        Some(CliCommand::Serve(serve)) => run_serve(&serve),
+        None => {
+            if let Some(path) = args.save_profile.take() {
+                profile_from_args(&args).save(&path)?;
+                print_saved_profile(Stream::Stdout, &path);
+                return Ok(CliOutcome::Completed);
+            }
+            run_headless(args).await
+        }
+    }
+}
+
+fn print_saved_profile(so: Stream, path: &Path) {
+    outln!(
+        so,
+        "{} {}",
+        "Saved profile to".if_supports_color(so, |t| t.cyan()),
+        path.display()
+            .to_string()
+            .if_supports_color(so, |t| t.bright_white()),
+    );
+}
+
+pub async fn run_headless(args: CliArgs) -> Result<CliOutcome> {
+    let connector: Connector = args.connector.into();
+    let output_format = args.output_format;
+    let scenario = args.scenario.as_deref().map(Scenario::load).transpose()?;
+    let write_policy = args.write_target_policy();
+    let session = match &scenario {
+        Some(scenario) => BenchmarkSession::from_scenario(connector, scenario),
+        None => BenchmarkSession::new(
+            connector,
+            args.mode.into(),
+            args.sizes
+                .clone()
+                .unwrap_or_else(|| DEFAULT_CHUNK_SIZES.to_vec()),
+            args.duration,
+        ),
+    }
+    .with_device(&args.device)
+    .with_write_policy(write_policy.clone())
+    .with_reconnect_attempts(args.reconnect_attempts)
+    .with_include_samples(args.include_samples)
+    .with_labels(args.labels.iter().cloned().collect());
+    let session = match &args.note {
+        Some(note) => session.with_note(note),
+        None => session,
+    };
+    if matches!(connector, Connector::Pcileech) && args.device.trim().is_empty() {
+        bail!("PCILeech requires a non-empty --device string");
+    }
+    session.validate()?;
+    let gate = args.gate_criteria()?;
+    let report_output = match args.output {
+        Some(path) => Some((resolve_report_format(output_format, &path)?, path)),
+        None => {
+            if output_format.is_some() {
+                bail!("--output-format requires --output");
+            }
+            None
+        }
+    };
+    let bench_mode = session.mode();
+    let duration_secs = session.duration_secs();
+    let sizes = session.sizes();
+
+    let stream = args.stream;
+    let so = if stream.is_some() {
+        Stream::Stderr
+    } else {
+        Stream::Stdout
+    };
+    if args.list_write_candidates {
+        let candidates = SpeedTest::preview_write_candidates(
+            connector,
+            args.device.trim(),
+            session.max_write_chunk_bytes(),
+            &write_policy,
+        )?;
+        print_write_candidates(so, &candidates, &write_policy);
+        return Ok(CliOutcome::Completed);
+    }
+
+    if scenario.is_some() {
+        outln!(
+            so,
+            "{}={} {}={}",
+            "connector".if_supports_color(so, |t| t.cyan()),
+            connector
+                .to_string()
+                .if_supports_color(so, |t| { t.style(Style::new().bright_white().bold()) }),
+            "scenario".if_supports_color(so, |t| t.cyan()),
+            format!("{} steps", session.steps().len())
+                .if_supports_color(so, |t| { t.style(Style::new().bright_white().bold()) }),
+        );
+    } else {
+        outln!(
+            so,
+            "{}={} {}={} {}={} {}={}",
+            "connector".if_supports_color(so, |t| t.cyan()),
+            connector
+                .to_string()
+                .if_supports_color(so, |t| { t.style(Style::new().bright_white().bold()) }),
+            "duration".if_supports_color(so, |t| t.cyan()),
+            format!("{duration_secs}s")
+                .if_supports_color(so, |t| { t.style(Style::new().bright_white().bold()) }),
+            "mode".if_supports_color(so, |t| t.cyan()),
+            bench_mode
+                .label()
+                .if_supports_color(so, |t| t.bright_white()),
+            "sizes".if_supports_color(so, |t| t.cyan()),
+            format!("{sizes:?}").if_supports_color(so, |t| t.bright_white()),
+        );
+    }
+    if bench_mode.needs_write_target() {
+        outln!(
+            so,
+            "{} {}",
+            "warning:".if_supports_color(so, |t| t.style(Style::new().yellow().bold())),
+            WRITE_MUTATION_WARNING.if_supports_color(so, |t| t.yellow()),
+        );
+    }
+
+    let metrics = (args.metrics_listen.is_some() || args.metrics_textfile.is_some()).then(|| {
+        let metrics = BenchMetrics::new(connector, bench_mode);
+        match args.metrics_textfile.clone() {
+            Some(path) => metrics.with_textfile(path),
+            None => metrics,
+        }
+    });
+    let metrics_server = match (args.metrics_listen, &metrics) {
+        (Some(addr), Some(metrics)) => {
+            let server = MetricsServer::bind(addr, metrics.clone())?;
+            outln!(
+                so,
+                "{} {}",
+                "metrics".if_supports_color(so, |t| t.cyan()),
+                format!("http://{}/metrics", server.local_addr())
+                    .if_supports_color(so, |t| t.bright_white()),
+            );
+            Some(server)
+        }
+        _ => None,
+    };
+    let agent = args.agent_listen.map(AgentServer::bind).transpose()?;
+    let events = RunEvents::new(stream, agent);
+    // Wait before connecting, so the device and write probe are not held while nobody watches.
+    if let Some(agent) = events.agent() {
+        outln!(
+            so,
+            "{} {}",
+            "Waiting for a viewer on".if_supports_color(so, |t| t.cyan()),
+            agent
+                .local_addr()
+                .to_string()
+                .if_supports_color(so, |t| t.bright_white()),
+        );
+        let timeout = args.agent_wait_timeout.map(Duration::from_secs);
+        let waited = tokio::select! {
+            waited = agent.wait_for_viewer(timeout) => waited,
+            _ = tokio::signal::ctrl_c() => Err(anyhow!("interrupted while waiting for a viewer")),
+        };
+        if let Err(e) = waited {
+            events.emit(&StreamEvent::RunEnd {
+                error: Some(e.to_string()),
+            });
+            return Err(e);
+        }
+    }
+
+    let observer = Arc::new(CliObserver::new(so, events.clone(), metrics.clone()));
+    let test = match session.connect(observer.as_ref()) {
+        Ok(test) => test,
+        Err(e) => {
+            // Stream consumers still get a terminating line when the run never started.
+            events.emit(&StreamEvent::RunEnd {
+                error: Some(e.to_string()),
+            });
+            return Err(e);
+        }
+    };
+    print_probe_details(so, &test.probe_connect_detail_lines());
+    // The wait above took over Ctrl-C; it now stops the run like `/stop`, so the write probe is
+    // restored and the passes measured so far are reported.
+    let ctrl_c = events.agent().map(|_| {
+        let cancel = test.cancel_flag();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+        })
+    });
+    events.emit(&StreamEvent::run_start(
+        &test,
+        connector,
+        bench_mode,
+        duration_secs,
+        sizes,
+    ));
+
+    let result = session.run(test, observer).await;
+    if let Some(ctrl_c) = ctrl_c {
+        ctrl_c.abort();
+    }
+    if let Some(metrics) = &metrics
+        && let Err(e) = metrics.finish_run()
+    {
+        outln!(
+            so,
+            "{} {}",
+            "warning:".if_supports_color(so, |t| t.style(Style::new().yellow().bold())),
+            format!("{e:#}").if_supports_color(so, |t| t.yellow()),
+        );
+    }
+    // A failed run still gets its summary, gate verdict, history entry and report for the
+    // passes it finished; the error decides the exit code afterwards.
+    let (mut report, run_error) = match result {
+        Ok(report) => (report, None),
+        Err(failure) => (*failure.report, Some(failure.error)),
+    };
+    events.emit(&StreamEvent::RunEnd {
+        error: run_error.as_ref().map(|e| e.to_string()),
+    });
+
+    print_summary(so, &report);
+    let mut outcome = CliOutcome::Completed;
+    if !gate.is_empty() {
+        let verdict = evaluate_gate(&report, &gate);
+        verdict::print_verdict(so, &verdict);
+        if !verdict.passed {
+            outcome = CliOutcome::GateFailed;
+        }
+        report = report.with_verdict(verdict);
+    }
+    if let Some((format, output_path)) = report_output {
+        write_report(so, &report, format, &output_path)?;
+    } else if stream.is_none() {
+        prompt_report_export(&report)?;
+    }
+    if let Some(path) = &args.history {
+        // The report is already written; a history problem must not turn the run into an error.
+        match RunHistory::new(path).append(&report) {
+            Ok(id) => outln!(
+                so,
+                "Recorded as run {id} in {} (history show {id}).",
+                path.display()
+            ),
+            Err(e) => outln!(
+                so,
+                "{} {}",
+                "warning:".if_supports_color(so, |t| t.style(Style::new().yellow().bold())),
+                format!("run not recorded in history: {e}").if_supports_color(so, |t| t.yellow()),
+            ),
+        }
+    }
+
+    if let Some(server) = &metrics_server
+        && args.metrics_linger > 0
+    {
+        outln!(
+            so,
+            "{}",
+            format!(
+                "Serving final metrics at http://{}/metrics for {} s...",
+                server.local_addr(),
+                args.metrics_linger
+            )
+            .if_supports_color(so, |t| t.dimmed()),
+        );
+        tokio::time::sleep(Duration::from_secs(args.metrics_linger)).await;
+    }
+
+    match run_error {
+        Some(e) => Err(e),
+        None => Ok(outcome),
+    }
+}
+
+fn prompt_report_export(report: &BenchmarkReport) -> Result<()> {
+    let Some(format) = prompt_report_export_format()? else {
+        return Ok(());
+    };
+
+    let path = default_report_path(format, &report.labels);
+    write_report(Stream::Stdout, report, format, &path)
+}
+
+fn write_report(
+    so: Stream,
+    report: &BenchmarkReport,
+    format: ReportFormat,
+    path: &Path,
+) -> Result<()> {
+    write_report_to_path(report, format, path)?;
+    outln!(so, "Report written: {}", path.display());
+    if let Some(samples_path) = report.samples_companion_path(format, path) {
+        outln!(so, "Samples written: {}", samples_path.display());
+    }
+    Ok(())
+}
+
+fn prompt_report_export_format() -> Result<Option<ReportFormat>> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Ok(None);
+    }
+
+    loop {
+        print!(
+            "\n{} ",
+            "Export benchmark report? [n/csv/json/md/html]"
+                .if_supports_color(Stream::Stdout, |t| t.style(Style::new().cyan().bold())),
+        );
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        match report_export_format_from_input(&line) {
+            Some(ReportExportAnswer::Skip) => return Ok(None),
+            Some(ReportExportAnswer::Format(format)) => return Ok(Some(format)),
+            None => eprintln!("Please enter n, csv, json, md, or html."),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ReportExportAnswer {
+    Skip,
+    Format(ReportFormat),
+}
+
+fn report_export_format_from_input(input: &str) -> Option<ReportExportAnswer> {
+    match input.trim().to_ascii_lowercase().as_str() {
+        "" | "n" | "no" => Some(ReportExportAnswer::Skip),
+        "c" | "csv" => Some(ReportExportAnswer::Format(ReportFormat::Csv)),
+        "j" | "json" => Some(ReportExportAnswer::Format(ReportFormat::Json)),
+        "m" | "md" | "markdown" => Some(ReportExportAnswer::Format(ReportFormat::Markdown)),
+        "h" | "html" => Some(ReportExportAnswer::Format(ReportFormat::Html)),
+        _ => None,
+    }
+}
+
+fn print_colored_live_sample(sample: &crate::speedtest::BenchSample) {
+    let so = Stream::Stdout;
+    let [t, mib, ops, lat, sz] = live_sample_columns(sample);
+    println!(
+        "  {}  {}  {}  {}  {}",
+        t.if_supports_color(so, |c| c.style(Style::new().bright_blue().bold())),
+        mib.if_supports_color(so, |c| c.style(Style::new().bright_green().bold())),
+        ops.if_supports_color(so, |c| c.cyan()),
+        lat.if_supports_color(so, |c| c.magenta()),
+        sz.if_supports_color(so, |c| c.style(Style::new().bright_yellow().bold())),
+    );
+}
+
+fn print_step_header(so: Stream, step: &SessionStep) {
+    let sizes = step
+        .sizes
+        .iter()
+        .map(|&size| format_chunk_size(size))
+        .collect::<Vec<_>>()
+        .join(", ");
+    outln!(
+        so,
+        "\n{} {}",
+        format!("Step {}:", step.name)
+            .if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+        format!(
+            "{}, {} s per size, {sizes}",
+            step.mode.label(),
+            step.duration_secs
+        )
+        .if_supports_color(so, |t| t.bright_white()),
+    );
+}
+
+fn print_summary(so: Stream, report: &BenchmarkReport) {
+    if report.passes.is_empty() {
+        return;
+    }
+
+    outln!(
+        so,
+        "\n{}",
+        "Summary (weighted averages):"
+            .if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+    );
+
+    for (step, passes) in report.step_sections() {
+        if let Some(step) = step {
+            outln!(so);
+            outln!(
+                so,
+                "{}",
+                format!("Step {}:", step.name)
+                    .if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+            );
+        }
+        print_summary_groups(so, &passes);
+    }
+}
+
+fn print_summary_groups(so: Stream, summaries: &[PassSummary]) {
+    for (idx, group) in summary_groups(summaries).iter().enumerate() {
+        if let Some(title) = group.title {
+            outln!(so);
+            outln!(
+                so,
+                "{}",
+                title.if_supports_color(so, |t| t.style(Style::new().green().bold()))
+            );
+        } else if idx > 0 {
+            outln!(so);
+        }
+
+        print_summary_table(so, group.op, &group.rows);
+    }
+}
+
+fn print_summary_table(so: Stream, group_op: Option<BenchOp>, summaries: &[&PassSummary]) {
+    if let Some(op) = group_op {
+        let ops_heading = format!("Avg {}", op.ops_per_sec_label());
+        outln!(
+            so,
+            "{}  {}  {}  {}  {}",
+            format!("{:<10}", "Size")
+                .if_supports_color(so, |t| t.style(Style::new().bright_yellow().bold())),
+            format!("{:>10}", "Avg MiB/s")
+                .if_supports_color(so, |t| t.style(Style::new().bright_green().bold())),
+            format!("{ops_heading:>12}")
+                .if_supports_color(so, |t| t.style(Style::new().cyan().bold())),
+            format!("{:>10}", "Avg μs")
+                .if_supports_color(so, |t| t.style(Style::new().magenta().bold())),
+            format!("{:>7}", "Samples").if_supports_color(so, |t| t.style(Style::new().bold())),
+        );
+        outln!(
+            so,
+            "{}  {}  {}  {}  {}",
+            format!("{:<10}", "----------").if_supports_color(so, |t| t.dimmed()),
+            format!("{:>10}", "----------").if_supports_color(so, |t| t.dimmed()),
+            format!("{:>12}", "------------").if_supports_color(so, |t| t.dimmed()),
+            format!("{:>10}", "----------").if_supports_color(so, |t| t.dimmed()),
+            format!("{:>7}", "-------").if_supports_color(so, |t| t.dimmed()),
+        );
+
+        for &s in summaries {
+            let sz = format!("{:<10}", format_chunk_size(s.chunk_bytes));
+            let mib = format!("{:>10.2}", s.avg_mib_s);
+            let ops = format!("{:>12.0}", s.avg_ops_s);
+            let lat = format!("{:>10.1}", s.avg_latency_us);
+            let n = format!("{:>7}", s.samples);
+            outln!(
+                so,
+                "{}  {}  {}  {}  {}",
+                sz.if_supports_color(so, |t| t.style(Style::new().bright_yellow().bold())),
+                mib.if_supports_color(so, |t| t.style(Style::new().bright_green().bold())),
+                ops.if_supports_color(so, |t| t.cyan()),
+                lat.if_supports_color(so, |t| t.magenta()),
+                n.if_supports_color(so, |t| t.bright_white()),
+            );
+        }
+        return;
+    }
+
+    outln!(
+        so,
+        "{}  {}  {}  {}  {}  {}",
+        format!("{:<6}", "Op").if_supports_color(so, |t| t.style(Style::new().green().bold())),
+        format!("{:<10}", "Size")
+            .if_supports_color(so, |t| t.style(Style::new().bright_yellow().bold())),
+        format!("{:>10}", "Avg MiB/s")
+            .if_supports_color(so, |t| t.style(Style::new().bright_green().bold())),
+        format!("{:>10}", "Avg ops/s")
+            .if_supports_color(so, |t| t.style(Style::new().cyan().bold())),
+        format!("{:>10}", "Avg μs")
+            .if_supports_color(so, |t| t.style(Style::new().magenta().bold())),
+        format!("{:>7}", "Samples").if_supports_color(so, |t| t.style(Style::new().bold())),
+    );
+    outln!(
+        so,
+        "{}  {}  {}  {}  {}  {}",
+        format!("{:<6}", "------").if_supports_color(so, |t| t.dimmed()),
+        format!("{:<10}", "----------").if_supports_color(so, |t| t.dimmed()),
+        format!("{:>10}", "----------").if_supports_color(so, |t| t.dimmed()),
+        format!("{:>10}", "----------").if_supports_color(so, |t| t.dimmed()),
+        format!("{:>10}", "----------").if_supports_color(so, |t| t.dimmed()),
+        format!("{:>7}", "-------").if_supports_color(so, |t| t.dimmed()),
+    );
+
+    for &s in summaries {
+        let op = format!("{:<6}", s.op.label());
+        let sz = format!("{:<10}", format_chunk_size(s.chunk_bytes));
+        let mib = format!("{:>10.2}", s.avg_mib_s);
+        let ops = format!("{:>10.0}", s.avg_ops_s);
+        let lat = format!("{:>10.1}", s.avg_latency_us);
+        let n = format!("{:>7}", s.samples);
+        outln!(
+            so,
+            "{}  {}  {}  {}  {}  {}",
+            op.if_supports_color(so, |t| t.style(Style::new().green().bold())),
+            sz.if_supports_color(so, |t| t.style(Style::new().bright_yellow().bold())),
+            mib.if_supports_color(so, |t| t.style(Style::new().bright_green().bold())),
+            ops.if_supports_color(so, |t| t.cyan()),
+            lat.if_supports_color(so, |t| t.magenta()),
+            n.if_supports_color(so, |t| t.bright_white()),
+        );
+    }
+}
+
+fn print_write_candidates(so: Stream, candidates: &[WriteCandidate], policy: &WriteTargetPolicy) {
+    outln!(so);
+    outln!(
+        so,
+        "{}",
+        "Write probe candidates (dry run; nothing written):"
+            .if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+    );
+    if candidates.is_empty() {
+        outln!(
+            so,
+            "  {}",
+            "No writable regions outside loaded modules and the read probe page."
+                .if_supports_color(so, |t| t.yellow()),
+        );
+        return;
+    }
+
+    let selected = if policy.is_explicit() {
+        None
+    } else {
+        select_write_candidate(candidates, policy)
+    };
+    outln!(
+        so,
+        "  {}  {}  {}  {}",
+        format!("{:<18}", "Base").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:>24}", "Size").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:<20}", "Page type").if_supports_color(so, |t| t.style(Style::new().bold())),
+        "Status".if_supports_color(so, |t| t.style(Style::new().bold())),
+    );
+    for candidate in candidates {
+        let status = if selected == Some(*candidate) {
+            "selected".to_string()
+        } else if let Some(reason) = policy.rejection_reason(candidate) {
+            format!("skipped: {reason}")
+        } else {
+            "eligible".to_string()
+        };
+        outln!(
+            so,
+            "  {}  {}  {}  {}",
+            format!("{:<18}", ProbeTargets::format_va(candidate.base))
+                .if_supports_color(so, |t| t.bright_white()),
+            format!(
+                "{:>24}",
+                format_byte_count(usize::try_from(candidate.size).unwrap_or(usize::MAX))
+            )
+            .if_supports_color(so, |t| t.bright_yellow()),
+            format!("{:<20}", format_page_type(candidate.page_type))
+                .if_supports_color(so, |t| t.cyan()),
+            status.if_supports_color(so, |t| t.dimmed()),
+        );
+    }
+    if let Some(addr) = policy.address {
+        outln!(
+            so,
+            "  {}",
+            format!(
+                "--write-addr {} overrides auto-selection; it must fall inside one of these regions.",
+                ProbeTargets::format_va(addr)
+            )
+            .if_supports_color(so, |t| t.dimmed()),
+        );
+    }
+}
+
+fn print_probe_details(so: Stream, detail_lines: &[String]) {
+    outln!(so);
+    outln!(
+        so,
+        "{}",
+        "Probe targets:".if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+    );
+    for line in detail_lines {
+        outln!(so, "  {}", line.if_supports_color(so, |t| t.bright_white()),);
+    }
+    outln!(so);
+}
+
+fn print_op_probe_detail(so: Stream, targets: &ProbeTargets, op: BenchOp, chunk_bytes: usize) {
+    let detail = match op {
+        BenchOp::Read => Some(targets.format_read_pass(chunk_bytes)),
+        BenchOp::Write => targets.format_write_pass(chunk_bytes),
+    };
+    if let Some(line) = detail {
+        outln!(so, "  {}", line.if_supports_color(so, |t| t.dimmed()),);
+    }
+}
+
+/// Category and suggested fixes under the `Error:` line for typed benchmark errors.
+pub fn print_error_hints(error: &anyhow::Error) {
+    let Some(kind) = BenchErrorKind::of(error) else {
+        return;
+    };
+    let so = Stream::Stderr;
+    eprintln!(
+        "{} {}",
+        "Category:".if_supports_color(so, |t| t.cyan()),
+        kind.label(),
+    );
+    for (index, hint) in kind.hints().iter().enumerate() {
+        eprintln!(
+            "  {} {hint}",
+            format!("{}.", index + 1).if_supports_color(so, |t| t.dimmed()),
+        );
+    }
+}
+
+pub fn prompt_exit(success: bool) -> Result<()> {
+    let status = if success {
+        "Benchmark finished."
+            .if_supports_color(Stream::Stdout, |t| t.style(Style::new().green().bold()))
+            .to_string()
+    } else {
+        "Benchmark failed."
+            .if_supports_color(Stream::Stdout, |t| t.style(Style::new().red().bold()))
+            .to_string()
+    };
+    println!(
+        "\n{} {}",
+        status,
+        "Press Enter to exit.".if_supports_color(Stream::Stdout, |t| t.dimmed()),
+    );
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+pub fn ensure_stdio_for_headless() {
+    use winapi::um::consoleapi::AllocConsole;
+    use winapi::um::wincon::{ATTACH_PARENT_PROCESS, AttachConsole, GetConsoleWindow};
+    // SAFETY: standard Win32 console attach/alloc; null check avoids calling on an existing console.
+    unsafe {
+        if GetConsoleWindow().is_null() {
+            let attached = AttachConsole(ATTACH_PARENT_PROCESS) != 0;
+            if !attached {
+                let _ = AllocConsole();
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn ensure_stdio_for_headless() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::{BenchMode, Connector};
+
+    #[test]
+    fn cli_connector_maps_to_speedtest_connector() {
+        let pcileech: Connector = CliConnector::Pcileech.into();
+        let native: Connector = CliConnector::Native.into();
+        assert!(matches!(pcileech, Connector::Pcileech));
+        assert!(matches!(native, Connector::Native));
+    }
+
+    #[test]
+    fn default_cli_args_matches_clap_equivalent() {
+        let d = default_cli_args();
+        assert!(matches!(d.connector, CliConnector::Pcileech));
+        assert_eq!(d.device, "FPGA");
+        assert_eq!(d.duration, 10);
+        assert!(matches!(d.mode, CliBenchMode::Read));
+        assert!(d.sizes.is_none());
+    }
+
+    #[test]
+    fn cli_bench_mode_maps_to_speedtest_bench_mode() {
+        let read: BenchMode = CliBenchMode::Read.into();
+        let write: BenchMode = CliBenchMode::Write.into();
+        let both: BenchMode = CliBenchMode::Both.into();
+        assert!(matches!(read, BenchMode::Read));
+        assert!(matches!(write, BenchMode::Write));
+        assert!(matches!(both, BenchMode::Both));
+    }
+
+    #[test]
+    fn clap_parses_reconnect_attempts() {
+        use clap::Parser;
+
+        let args = CliArgs::parse_from(["cli-dma-speedtest", "--reconnect-attempts", "3"]);
+        assert_eq!(args.reconnect_attempts, 3);
+        assert_eq!(default_cli_args().reconnect_attempts, 0);
+    }
+
+    #[test]
+    fn clap_parses_metrics_flags() {
+        use clap::Parser;
+
+        let args = CliArgs::parse_from([
+            "cli-dma-speedtest",
+            "--metrics-listen",
+            "127.0.0.1:9899",
+            "--metrics-textfile",
+            "/var/lib/node_exporter/dma.prom",
+            "--metrics-linger",
+            "30",
+        ]);
+        assert_eq!(args.metrics_listen, Some("127.0.0.1:9899".parse().unwrap()));
+        assert_eq!(
+            args.metrics_textfile,
+            Some(PathBuf::from("/var/lib/node_exporter/dma.prom"))
+        );
+        assert_eq!(args.metrics_linger, 30);
+
+        let err = CliArgs::try_parse_from(["cli-dma-speedtest", "--metrics-linger", "5"])
+            .err()
+            .expect("linger without an endpoint");
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn clap_parses_agent_wait_timeout() {
+        use clap::Parser;
+
+        let args = CliArgs::parse_from([
+            "cli-dma-speedtest",
+            "--agent-listen",
+            "127.0.0.1:9900",
+            "--agent-wait-timeout",
+            "120",
+        ]);
+        assert_eq!(args.agent_listen, Some("127.0.0.1:9900".parse().unwrap()));
+        assert_eq!(args.agent_wait_timeout, Some(120));
+
+        let err = CliArgs::try_parse_from(["cli-dma-speedtest", "--agent-wait-timeout", "5"])
+            .err()
+            .expect("timeout without an agent");
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+        assert!(
+            CliArgs::try_parse_from([
+                "cli-dma-speedtest",
+                "--agent-listen",
+                "127.0.0.1:9900",
+                "--agent-wait-timeout",
+                "0",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn clap_parses_stream_format() {
+        use clap::Parser;
+
+        let args = CliArgs::parse_from(["cli-dma-speedtest", "--stream", "ndjson"]);
+        assert_eq!(args.stream, Some(StreamFormat::Ndjson));
+        assert_eq!(default_cli_args().stream, None);
+        assert!(CliArgs::try_parse_from(["cli-dma-speedtest", "--stream", "csv"]).is_err());
+    }
+
+    #[test]
+    fn clap_parses_labels_and_note() {
+        use clap::Parser;
+
+        let args = CliArgs::parse_from([
+            "cli-dma-speedtest",
+            "--label",
+            "card=A1",
+            "--label",
+            "slot = 2",
+            "--note",
+            "after firmware update",
+        ]);
+        assert_eq!(
+            args.labels,
+            vec![
+                ("card".to_string(), "A1".to_string()),
+                ("slot".to_string(), "2".to_string())
+            ]
+        );
+        assert_eq!(args.note.as_deref(), Some("after firmware update"));
+        assert!(CliArgs::try_parse_from(["cli-dma-speedtest", "--label", "card"]).is_err());
+    }
+
+    #[test]
+    fn clap_parses_gate_limits() {
+        use clap::Parser;
+
+        let args = CliArgs::parse_from([
+            "cli-dma-speedtest",
+            "--min-throughput",
+            "100,4096=80",
+            "--max-latency",
+            "50",
+        ]);
+        assert_eq!(args.min_throughput.len(), 2);
+        assert_eq!(args.min_throughput[1].chunk_bytes, Some(4096));
+        assert_eq!(args.max_latency[0].value, 50.0);
+
+        assert!(
+            CliArgs::try_parse_from(["cli-dma-speedtest", "--max-throughput-drop", "3"]).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_headless_rejects_missing_baseline_before_connecting() {
+        let args = CliArgs {
+            baseline: Some(PathBuf::from("does-not-exist/baseline.json")),
+            ..default_cli_args()
+        };
+
+        let err = run_headless(args).await.unwrap_err();
+        assert!(err.to_string().contains("could not read report"));
+    }
+
+    #[test]
+    fn clap_parses_compare_subcommand() {
+        use clap::Parser;
+
+        let args = CliArgs::parse_from([
+            "cli-dma-speedtest",
+            "compare",
+            "base.json",
+            "new.json",
+            "--max-throughput-drop",
+            "2.5",
+        ]);
+        let Some(CliCommand::Compare(compare)) = args.command else {
+            panic!("expected compare subcommand");
+        };
+        assert_eq!(compare.reports.len(), 2);
+        assert_eq!(compare.max_throughput_drop, 2.5);
+        assert_eq!(compare.max_latency_rise, 10.0);
+
+        assert!(CliArgs::try_parse_from(["cli-dma-speedtest", "compare", "base.json"]).is_err());
+    }
+
+    #[test]
+    fn clap_parses_serve_subcommand() {
+        use clap::Parser;
+
+        let args = CliArgs::parse_from(["cli-dma-speedtest", "serve"]);
+        let Some(CliCommand::Serve(serve)) = args.command else {
+            panic!("expected serve subcommand");
+        };
+        assert_eq!(
+            serve.listen.to_string(),
+            crate::speedtest::DEFAULT_CONTROL_ADDR
+        );
+
+        assert!(!serve.allow_remote);
+        assert!(serve.token.is_none());
+
+        let args = CliArgs::parse_from([
+            "cli-dma-speedtest",
+            "serve",
+            "--listen",
+            "0.0.0.0:7000",
+            "--allow-remote",
+            "--token",
+            "s3cret",
+        ]);
+        let Some(CliCommand::Serve(serve)) = args.command else {
+            panic!("expected serve subcommand");
+        };
+        assert_eq!(serve.listen.port(), 7000);
+        assert!(serve.allow_remote);
+        assert_eq!(serve.token.as_deref(), Some("s3cret"));
+
+        assert!(CliArgs::try_parse_from(["cli-dma-speedtest", "serve", "--allow-remote"]).is_err());
+        let args = CliArgs::parse_from(["cli-dma-speedtest", "serve", "--listen", "0.0.0.0:7000"]);
+        let Some(CliCommand::Serve(serve)) = args.command else {
+            panic!("expected serve subcommand");
+        };
+        let err = super::run_serve(&serve).unwrap_err();
+        assert!(err.to_string().contains("--allow-remote"), "{err}");
+    }
+
+    #[test]
+    fn clap_parses_doctor_subcommand() {
+        use clap::Parser;
+
+        let args = CliArgs::parse_from(["cli-dma-speedtest", "doctor"]);
+        let Some(CliCommand::Doctor(doctor)) = args.command else {
+            panic!("expected doctor subcommand");
+        };
+        let settings = doctor.settings();
+        assert_eq!(settings.connector, Connector::Pcileech);
+        assert_eq!(settings.device, "FPGA");
+        assert_eq!(settings.max_chunk_bytes, 32768);
+        assert!(!settings.verify_write);
+        assert!(doctor.output.is_none());
+
+        let args = CliArgs::parse_from([
+            "cli-dma-speedtest",
+            "doctor",
+            "--connector",
+            "native",
+            "--write-addr",
+            "0x1f0000",
+            "--verify-write",
+            "--output",
+            "doctor.json",
+        ]);
+        let Some(CliCommand::Doctor(doctor)) = args.command else {
+            panic!("expected doctor subcommand");
+        };
+        let settings = doctor.settings();
+        assert_eq!(settings.connector, Connector::Native);
+        assert_eq!(
+            settings.write_policy.address,
+            Some(Address::from(0x1f0000u64))
+        );
+        assert!(settings.verify_write);
+        assert_eq!(CliOutcome::DiagnosticsFailed.exit_code(), 4);
+    }
+
+    #[test]
+    fn clap_parses_mode_flag() {
+        use clap::Parser;
+
+        let read = CliArgs::parse_from(["cli-dma-speedtest", "--mode", "read"]);
+        assert!(matches!(read.mode, CliBenchMode::Read));
+
+        let write = CliArgs::parse_from(["cli-dma-speedtest", "--mode", "write"]);
+        assert!(matches!(write.mode, CliBenchMode::Write));
+
+        let both = CliArgs::parse_from(["cli-dma-speedtest", "--mode", "both"]);
+        assert!(matches!(both.mode, CliBenchMode::Both));
+    }
+
+    #[test]
+    fn clap_parses_write_target_policy_flags() {
+        use clap::Parser;
+
+        let args = CliArgs::parse_from([
+            "cli-dma-speedtest",
+            "--mode",
+            "write",
+            "--write-addr",
+            "0x1F0000",
+            "--write-region-bytes",
+            "65536",
+            "--write-avoid",
+            "exec,unknown",
+        ]);
+        let policy = args.write_target_policy();
+
+        assert_eq!(policy.address, Some(Address::from(0x1f0000_u64)));
+        assert_eq!(policy.region_bytes, Some(65536));
+        assert_eq!(
+            policy.avoid,
+            vec![WritePageFlag::Exec, WritePageFlag::Unknown]
+        );
+        assert!(parse_address("zz").is_err());
+    }
+
+    #[tokio::test]
+    async fn run_headless_rejects_conflicting_write_policy_before_connecting() {
+        let args = CliArgs {
+            mode: CliBenchMode::Write,
+            write_min_region: Some(64 * 1024),
+            write_max_region: Some(32 * 1024),
+            ..default_cli_args()
+        };
+
+        let err = run_headless(args).await.unwrap_err();
+        assert!(err.to_string().contains("exceeds --write-max-region"));
+    }
+
+    #[tokio::test]
+    async fn run_headless_rejects_zero_size_before_connecting() {
+        let args = CliArgs {
+            sizes: Some(vec![0]),
+            ..default_cli_args()
+        };
+
+        let err = run_headless(args).await.unwrap_err();
+        assert!(err.to_string().contains("chunk sizes must be positive"));
+    }
+
+    #[tokio::test]
+    async fn run_headless_rejects_output_format_without_output_before_connecting() {
+        let args = CliArgs {
+            output_format: Some(ReportFormat::Json),
+            ..default_cli_args()
+        };
+
+        let err = run_headless(args).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("--output-format requires --output")
+        );
+    }
+
+    #[tokio::test]
+    async fn run_headless_rejects_unknown_output_extension_before_connecting() {
+        let args = CliArgs {
+            output: Some(PathBuf::from("report.txt")),
+            ..default_cli_args()
+        };
+
+        let err = run_headless(args).await.unwrap_err();
+        assert!(err.to_string().contains("could not infer report format"));
+    }
+
+    #[test]
+    fn report_export_prompt_input_maps_to_expected_choice() {
+        assert_eq!(
+            report_export_format_from_input(""),
+            Some(ReportExportAnswer::Skip)
+        );
+        assert_eq!(
+            report_export_format_from_input("no"),
+            Some(ReportExportAnswer::Skip)
+        );
+        assert_eq!(
+            report_export_format_from_input("csv"),
+            Some(ReportExportAnswer::Format(ReportFormat::Csv))
+        );
+        assert_eq!(
+            report_export_format_from_input("j"),
+            Some(ReportExportAnswer::Format(ReportFormat::Json))
+        );
+        assert_eq!(
+            report_export_format_from_input("md"),
+            Some(ReportExportAnswer::Format(ReportFormat::Markdown))
+        );
+        assert_eq!(
+            report_export_format_from_input("HTML"),
+            Some(ReportExportAnswer::Format(ReportFormat::Html))
+        );
+        assert_eq!(report_export_format_from_input("xml"), None);
+    }
+}
@@ -0,0 +1,175 @@
+//! `--profile` / `--save-profile`: merge a [`BenchProfile`] under the command-line flags.
+
+use anyhow::{Result, bail};
+use clap::{ArgMatches, CommandFactory, FromArgMatches, parser::ValueSource};
+
+use super::{CliArgs, CliBenchMode, CliConnector};
+use crate::bench_config::DEFAULT_CHUNK_SIZES;
+use crate::speedtest::{
+    BenchMode, BenchProfile, Connector, ProfileOutput, ProfileTarget, ProfileThresholds,
+};
+
+/// Parse the process arguments and fill every flag left at its default from `--profile`.
+/// Exits like `CliArgs::parse()` on usage errors, `--help` and `--version`.
+pub fn parse_cli_args() -> Result<CliArgs> {
+    let matches = CliArgs::command().get_matches();
+    args_with_profile(&matches)
+}
+
+fn args_with_profile(matches: &ArgMatches) -> Result<CliArgs> {
+    let mut args = CliArgs::from_arg_matches(matches).map_err(|e| e.exit())?;
+    if let Some(path) = args.profile.clone() {
+        let profile = BenchProfile::load(&path)?;
+        apply_profile(&mut args, &profile, |id| {
+            matches.value_source(id) == Some(ValueSource::CommandLine)
+        })?;
+    }
+    Ok(args)
+}
+
+/// Copy profile fields into `args` unless `on_command_line(field)` says the flag was given.
+fn apply_profile(
+    args: &mut CliArgs,
+    profile: &BenchProfile,
+    on_command_line: impl Fn(&str) -> bool,
+) -> Result<()> {
+    macro_rules! take {
+        ($field:ident, $value:expr) => {
+            if !on_command_line(stringify!($field))
+                && let Some(value) = $value
+            {
+                args.$field = value;
+            }
+        };
+    }
+
+    let connector = match profile.connector {
+        Some(Connector::Pcileech) => Some(CliConnector::Pcileech),
+        Some(Connector::Native) => Some(CliConnector::Native),
+        Some(other) => bail!("profile connector {other} is not available in the CLI"),
+        None => None,
+    };
+    take!(connector, connector);
+    take!(device, profile.device.clone());
+    take!(mode, profile.mode.map(cli_bench_mode));
+    take!(sizes, profile.sizes.clone().map(Some));
+    take!(duration, profile.duration);
+
+    let target = &profile.target;
+    take!(write_addr, target.write_addr.map(Some));
+    take!(write_region_bytes, target.write_region_bytes.map(Some));
+    take!(write_min_region, target.write_min_region.map(Some));
+    take!(write_max_region, target.write_max_region.map(Some));
+    take!(write_prefer, target.write_prefer.clone());
+    take!(write_avoid, target.write_avoid.clone());
+
+    let thresholds = &profile.thresholds;
+    take!(baseline, thresholds.baseline.clone().map(Some));
+    take!(max_throughput_drop, thresholds.max_throughput_drop);
+    take!(max_latency_rise, thresholds.max_latency_rise);
+    take!(min_throughput, thresholds.min_throughput.clone());
+    take!(max_latency, thresholds.max_latency.clone());
+
+    let output = &profile.output;
+    take!(output, output.path.clone().map(Some));
+    take!(output_format, output.format.map(Some));
+    take!(include_samples, output.include_samples);
+    Ok(())
+}
+
+fn cli_bench_mode(mode: BenchMode) -> CliBenchMode {
+    match mode {
+        BenchMode::Read => CliBenchMode::Read,
+        BenchMode::Write => CliBenchMode::Write,
+        BenchMode::Both => CliBenchMode::Both,
+    }
+}
+
+/// The settings a run with `args` would use, for `--save-profile`.
+pub fn profile_from_args(args: &CliArgs) -> BenchProfile {
+    let connector: Connector = args.connector.into();
+    BenchProfile {
+        connector: Some(connector),
+        device: matches!(connector, Connector::Pcileech).then(|| args.device.trim().to_string()),
+        mode: Some(args.mode.into()),
+        sizes: Some(
+            args.sizes
+                .clone()
+                .unwrap_or_else(|| DEFAULT_CHUNK_SIZES.to_vec()),
+        ),
+        duration: Some(args.duration),
+        target: ProfileTarget {
+            write_addr: args.write_addr,
+            write_region_bytes: args.write_region_bytes,
+            write_min_region: args.write_min_region,
+            write_max_region: args.write_max_region,
+            write_prefer: non_empty(&args.write_prefer),
+            write_avoid: non_empty(&args.write_avoid),
+        },
+        thresholds: ProfileThresholds {
+            baseline: args.baseline.clone(),
+            max_throughput_drop: args.baseline.as_ref().map(|_| args.max_throughput_drop),
+            max_latency_rise: args.baseline.as_ref().map(|_| args.max_latency_rise),
+            min_throughput: non_empty(&args.min_throughput),
+            max_latency: non_empty(&args.max_latency),
+        },
+        output: ProfileOutput {
+            path: args.output.clone(),
+            format: args.output_format,
+            include_samples: args.include_samples.then_some(true),
+        },
+    }
+}
+
+fn non_empty<T: Clone>(items: &[T]) -> Option<Vec<T>> {
+    (!items.is_empty()).then(|| items.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::{SizeLimit, WritePageFlag};
+
+    #[test]
+    fn command_line_flags_override_profile_fields() {
+        let dir =
+            std::env::temp_dir().join(format!("dma-speedtest-cli-profile-{}", std::process::id()));
+        let path = dir.join("bench.toml");
+        BenchProfile {
+            connector: Some(Connector::Native),
+            mode: Some(BenchMode::Both),
+            sizes: Some(vec![4096, 65536]),
+            duration: Some(30),
+            target: ProfileTarget {
+                write_avoid: Some(vec![WritePageFlag::Exec]),
+                ..ProfileTarget::default()
+            },
+            thresholds: ProfileThresholds {
+                min_throughput: Some(vec![SizeLimit::parse("100").unwrap()]),
+                ..ProfileThresholds::default()
+            },
+            ..BenchProfile::default()
+        }
+        .save(&path)
+        .unwrap();
+
+        let profile_arg = path.display().to_string();
+        let matches = CliArgs::command()
+            .try_get_matches_from(["bench", "--profile", &profile_arg, "--duration", "5"])
+            .unwrap();
+        let args = args_with_profile(&matches).unwrap();
+        assert!(matches!(args.connector, CliConnector::Native));
+        assert!(matches!(args.mode, CliBenchMode::Both));
+        assert_eq!(args.sizes, Some(vec![4096, 65536]));
+        assert_eq!(args.duration, 5);
+        assert_eq!(args.write_avoid, vec![WritePageFlag::Exec]);
+        assert_eq!(args.min_throughput.len(), 1);
+
+        let saved = profile_from_args(&args);
+        assert_eq!(saved.duration, Some(5));
+        assert_eq!(saved.device, None);
+        assert_eq!(saved.target.write_avoid, Some(vec![WritePageFlag::Exec]));
+        assert_eq!(saved.thresholds.max_latency_rise, None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
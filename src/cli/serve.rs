@@ -0,0 +1,93 @@
+//! `serve` subcommand: keep a [`ControlServer`] up for lab automation.
+
+use anyhow::{Result, bail};
+use owo_colors::OwoColorize;
+use owo_colors::{Stream, Style};
+use std::net::SocketAddr;
+
+use super::CliOutcome;
+use crate::speedtest::{ControlServer, DEFAULT_CONTROL_ADDR, WRITE_MUTATION_WARNING};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ServeArgs {
+    #[arg(
+        long,
+        value_name = "ADDR",
+        default_value = DEFAULT_CONTROL_ADDR,
+        help = "Address for the JSON control API; only loopback addresses unless --allow-remote is given."
+    )]
+    pub listen: SocketAddr,
+
+    #[arg(
+        long,
+        requires = "token",
+        help = "Allow a non-loopback --listen address; every request must then send the --token."
+    )]
+    pub allow_remote: bool,
+
+    #[arg(
+        long,
+        value_name = "TOKEN",
+        help = "Require `Authorization: Bearer TOKEN` on every request (mandatory with --allow-remote)."
+    )]
+    pub token: Option<String>,
+}
+
+const ENDPOINTS: [(&str, &str); 8] = [
+    (
+        "GET  /status",
+        "state, connection, current pass and last error",
+    ),
+    (
+        "POST /connect",
+        "open a session: connector, device, mode, max_chunk_bytes, target",
+    ),
+    (
+        "POST /start",
+        "run passes: mode, sizes, duration, include_samples, labels, note",
+    ),
+    ("POST /stop", "cancel the running passes"),
+    (
+        "GET  /events?from=N",
+        "NDJSON events of the current run, as --stream ndjson",
+    ),
+    ("GET  /report", "JSON report of the last run"),
+    ("POST /disconnect", "close the session"),
+    ("POST /shutdown", "stop any run and exit"),
+];
+
+pub fn run_serve(args: &ServeArgs) -> Result<CliOutcome> {
+    let so = Stream::Stdout;
+    if !args.listen.ip().is_loopback() && !args.allow_remote {
+        bail!(
+            "--listen {} is not a loopback address; pass --allow-remote --token TOKEN to expose the control API",
+            args.listen
+        );
+    }
+    let server = ControlServer::bind(args.listen, args.token.clone())?;
+    println!(
+        "{} {}",
+        "Control API listening on"
+            .if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+        format!("http://{}", server.local_addr()).if_supports_color(so, |t| t.bright_white()),
+    );
+    for (endpoint, description) in ENDPOINTS {
+        println!(
+            "  {}  {}",
+            format!("{endpoint:<20}").if_supports_color(so, |t| t.cyan()),
+            description.if_supports_color(so, |t| t.dimmed()),
+        );
+    }
+    println!(
+        "{} {}",
+        "warning:".if_supports_color(so, |t| t.style(Style::new().yellow().bold())),
+        WRITE_MUTATION_WARNING.if_supports_color(so, |t| t.yellow()),
+    );
+
+    server.wait();
+    println!(
+        "{}",
+        "Control API shut down.".if_supports_color(so, |t| t.style(Style::new().green().bold())),
+    );
+    Ok(CliOutcome::Completed)
+}
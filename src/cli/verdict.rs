@@ -0,0 +1,61 @@
+//! Pass/fail verdict table for gated runs.
+
+use owo_colors::OwoColorize;
+use owo_colors::{Stream, Style};
+
+use crate::bench_config::format_chunk_size;
+use crate::speedtest::GateVerdict;
+
+pub(super) fn print_verdict(so: Stream, verdict: &GateVerdict) {
+    outln!(
+        so,
+        "\n{}",
+        "Gate verdict:".if_supports_color(so, |t| t.style(Style::new().bright_blue().bold())),
+    );
+    outln!(
+        so,
+        "  {}  {}  {}  {}  {}",
+        format!("{:<6}", "Op").if_supports_color(so, |t| t.style(Style::new().green().bold())),
+        format!("{:<10}", "Size")
+            .if_supports_color(so, |t| t.style(Style::new().bright_yellow().bold())),
+        format!("{:<28}", "Check").if_supports_color(so, |t| t.style(Style::new().bold())),
+        format!("{:>12}", "Actual").if_supports_color(so, |t| t.style(Style::new().bold())),
+        "Result".if_supports_color(so, |t| t.style(Style::new().bold())),
+    );
+
+    for check in &verdict.checks {
+        let result = if check.passed {
+            "PASS"
+                .if_supports_color(so, |t| t.style(Style::new().green().bold()))
+                .to_string()
+        } else {
+            "FAIL"
+                .if_supports_color(so, |t| t.style(Style::new().red().bold()))
+                .to_string()
+        };
+        outln!(
+            so,
+            "  {}  {}  {:<28}  {:>12}  {result}",
+            format!("{:<6}", check.op.label())
+                .if_supports_color(so, |t| t.style(Style::new().green().bold())),
+            format!("{:<10}", format_chunk_size(check.chunk_bytes))
+                .if_supports_color(so, |t| t.style(Style::new().bright_yellow().bold())),
+            check.check,
+            check.format_actual(),
+        );
+    }
+
+    let (label, style) = if verdict.passed {
+        ("PASS", Style::new().green().bold())
+    } else {
+        ("FAIL", Style::new().red().bold())
+    };
+    let failed = verdict.checks.iter().filter(|check| !check.passed).count();
+    outln!(
+        so,
+        "\n{} {}",
+        format!("Overall: {label}").if_supports_color(so, |t| t.style(style)),
+        format!("({failed} of {} checks failed)", verdict.checks.len())
+            .if_supports_color(so, |t| t.dimmed()),
+    );
+}
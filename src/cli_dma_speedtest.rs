@@ -1,8 +1,8 @@
-use clap::Parser;
 use dma_speedtest_memflow_rs::cli::{
-    CliArgs, ensure_stdio_for_headless, interactive_launch_cli_args, print_startup_help,
-    prompt_exit, run_headless,
+    CliOutcome, ensure_stdio_for_headless, interactive_launch_cli_args, parse_cli_args,
+    print_error_hints, print_startup_help, prompt_exit, run_cli_args,
 };
+use dma_speedtest_memflow_rs::speedtest::BenchError;
 use owo_colors::OwoColorize;
 use owo_colors::{Stream, Style};
 use std::process::ExitCode;
@@ -11,18 +11,26 @@ use std::process::ExitCode;
 async fn main() -> ExitCode {
     ensure_stdio_for_headless();
 
-    let result = run_cli().await;
-    let success = result.is_ok();
+    // `--stream` hands stdout to another program; keep it free of help text and prompts.
+    let streaming = std::env::args().any(|a| a == "--stream" || a.starts_with("--stream="));
+    // `serve` is driven over its API and usually runs unattended.
+    let serving = std::env::args().nth(1).is_some_and(|a| a == "serve");
+    let result = run_cli(streaming).await;
+    let success = matches!(result, Ok(CliOutcome::Completed));
 
     if let Err(ref e) = result {
         eprintln!(
             "{} {e}",
             "Error:".if_supports_color(Stream::Stderr, |t| t.style(Style::new().red().bold())),
         );
+        print_error_hints(e);
     }
 
-    // Always wait for Enter, even on failures (e.g. PCILeech init errors).
-    if let Err(e) = prompt_exit(success) {
+    // Wait for Enter outside streaming and serve mode, even on failures (e.g. PCILeech init errors).
+    if !streaming
+        && !serving
+        && let Err(e) = prompt_exit(success)
+    {
         eprintln!(
             "{} {e}",
             "Error:".if_supports_color(Stream::Stderr, |t| t.style(Style::new().red().bold())),
@@ -30,14 +38,13 @@ async fn main() -> ExitCode {
         return ExitCode::FAILURE;
     }
 
-    if success {
-        ExitCode::SUCCESS
-    } else {
-        ExitCode::FAILURE
+    match result {
+        Ok(outcome) => ExitCode::from(outcome.exit_code()),
+        Err(e) => ExitCode::from(BenchError::exit_code_for(&e)),
     }
 }
 
-async fn run_cli() -> anyhow::Result<()> {
+async fn run_cli(streaming: bool) -> anyhow::Result<CliOutcome> {
     let user_arg_count = std::env::args_os().skip(1).count();
 
     // Print help on startup when non-interactive so available flags are visible by default.
@@ -46,14 +53,17 @@ async fn run_cli() -> anyhow::Result<()> {
     let wants_help_or_version = argv
         .iter()
         .any(|a| matches!(a.as_str(), "-h" | "--help" | "-V" | "--version"));
+    let is_subcommand = argv
+        .get(1)
+        .is_some_and(|a| matches!(a.as_str(), "compare" | "doctor" | "history" | "serve"));
 
     let args = if user_arg_count == 0 {
         interactive_launch_cli_args()?
     } else {
-        if !wants_help_or_version {
+        if !wants_help_or_version && !is_subcommand && !streaming {
             print_startup_help();
         }
-        CliArgs::parse()
+        parse_cli_args()?
     };
-    run_headless(args).await
+    run_cli_args(args).await
 }
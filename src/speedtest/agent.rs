@@ -0,0 +1,332 @@
+//! Remote agent: send a run's [`StreamEvent`]s to TCP viewers as NDJSON, one event per line.
+//!
+//! The CLI publishes with `--agent-listen`; the GUI follows with [`AgentViewer`]. Viewers that
+//! join mid-run first get every event published so far. Each viewer has its own writer thread,
+//! so a slow viewer never holds up the run or the other viewers.
+
+use super::StreamEvent;
+use anyhow::{Result, bail};
+use parking_lot::Mutex;
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, SyncSender},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+/// A viewer that cannot take a line within this long is dropped.
+const VIEWER_WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Lines queued for one viewer before it counts as too slow and is dropped.
+const VIEWER_QUEUE_LINES: usize = 1024;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Viewer {
+    queue: SyncSender<Arc<str>>,
+    stream: TcpStream,
+    writer: JoinHandle<()>,
+}
+
+impl Viewer {
+    /// Let the writer flush what is queued, then close the connection.
+    fn close(self) {
+        drop(self.queue);
+        let _ = self.writer.join();
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+#[derive(Default)]
+struct AgentInner {
+    viewers: Vec<Viewer>,
+    /// Every line published so far, replayed to late viewers.
+    lines: Vec<Arc<str>>,
+}
+
+#[derive(Default)]
+struct AgentShared {
+    inner: Mutex<AgentInner>,
+    stop: AtomicBool,
+}
+
+/// Accepts viewers on a background thread; dropping it ends every viewer's stream.
+pub struct AgentServer {
+    addr: SocketAddr,
+    shared: Arc<AgentShared>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl AgentServer {
+    pub fn bind(addr: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| anyhow::anyhow!("could not bind agent on {addr}: {e}"))?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(AgentShared::default());
+        let handle = thread::Builder::new()
+            .name("agent-server".to_string())
+            .spawn({
+                let shared = Arc::clone(&shared);
+                move || accept_loop(&listener, &shared)
+            })?;
+
+        Ok(Self {
+            addr,
+            shared,
+            handle: Some(handle),
+        })
+    }
+
+    /// Bound address (resolves port `0` to the assigned port).
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn viewer_count(&self) -> usize {
+        self.shared.inner.lock().viewers.len()
+    }
+
+    /// Wait until at least one viewer is connected, or fail once `timeout` has passed.
+    ///
+    /// Sleeps on the runtime's timer, so the caller can race it against other futures.
+    pub async fn wait_for_viewer(&self, timeout: Option<Duration>) -> Result<()> {
+        let start = Instant::now();
+        while self.viewer_count() == 0 {
+            if let Some(timeout) = timeout
+                && start.elapsed() >= timeout
+            {
+                bail!(
+                    "no viewer connected to the agent on {} within {}s",
+                    self.addr,
+                    timeout.as_secs_f64()
+                );
+            }
+            tokio::time::sleep(ACCEPT_POLL).await;
+        }
+        Ok(())
+    }
+
+    /// Queue one event for every viewer without waiting on the network; viewers whose writer
+    /// failed or whose queue is full are dropped.
+    pub fn publish(&self, event: &StreamEvent) {
+        let line: Arc<str> = format!("{}\n", event.to_json_line()).into();
+        let mut inner = self.shared.inner.lock();
+        inner.viewers.retain(|viewer| {
+            let queued = viewer.queue.try_send(Arc::clone(&line)).is_ok();
+            if !queued {
+                let _ = viewer.stream.shutdown(Shutdown::Both);
+            }
+            queued
+        });
+        inner.lines.push(line);
+    }
+}
+
+impl Drop for AgentServer {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        let viewers = std::mem::take(&mut self.shared.inner.lock().viewers);
+        for viewer in viewers {
+            viewer.close();
+        }
+    }
+}
+
+fn accept_loop(listener: &TcpListener, shared: &AgentShared) {
+    while !shared.stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let _ = add_viewer(stream, shared);
+            }
+            // WouldBlock between connections; transient accept errors get the same back-off.
+            Err(_) => thread::sleep(ACCEPT_POLL),
+        }
+    }
+}
+
+fn add_viewer(stream: TcpStream, shared: &AgentShared) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_write_timeout(Some(VIEWER_WRITE_TIMEOUT))?;
+    stream.set_nodelay(true)?;
+    let writer_stream = stream.try_clone()?;
+    let (queue, lines) = mpsc::sync_channel(VIEWER_QUEUE_LINES);
+
+    // Snapshot the backlog and register under one lock so no event lands between the two; the
+    // writer thread sends both without holding it.
+    let mut inner = shared.inner.lock();
+    let backlog = inner.lines.clone();
+    let writer = thread::Builder::new()
+        .name("agent-viewer".to_string())
+        .spawn(move || write_viewer(writer_stream, backlog, lines))?;
+    inner.viewers.push(Viewer {
+        queue,
+        stream,
+        writer,
+    });
+    Ok(())
+}
+
+/// Send the backlog, then every queued line, until the viewer fails or the agent drops the queue.
+fn write_viewer(mut stream: TcpStream, backlog: Vec<Arc<str>>, lines: Receiver<Arc<str>>) {
+    for line in backlog.into_iter().chain(lines) {
+        if stream.write_all(line.as_bytes()).is_err() {
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+    }
+}
+
+/// Follows an agent's events until it closes the connection.
+pub struct AgentViewer {
+    reader: BufReader<TcpStream>,
+}
+
+impl AgentViewer {
+    /// `addr` is `HOST:PORT`.
+    pub fn connect(addr: &str) -> Result<Self> {
+        let socket_addr = addr
+            .trim()
+            .to_socket_addrs()
+            .map_err(|e| anyhow::anyhow!("invalid agent address {addr:?}: {e}"))?
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("agent address {addr:?} did not resolve"))?;
+        let stream = TcpStream::connect_timeout(&socket_addr, CONNECT_TIMEOUT)
+            .map_err(|e| anyhow::anyhow!("could not connect to agent {addr}: {e}"))?;
+        Ok(Self {
+            reader: BufReader::new(stream),
+        })
+    }
+
+    /// Handle that ends a blocked [`Self::next_event`] from another thread.
+    pub fn stop_handle(&self) -> Result<AgentStopHandle> {
+        Ok(AgentStopHandle(self.reader.get_ref().try_clone()?))
+    }
+
+    /// Next event, or `None` once the agent has closed the stream.
+    pub fn next_event(&mut self) -> Result<Option<StreamEvent>> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event = serde_json::from_str(&line)
+                .map_err(|e| anyhow::anyhow!("invalid agent event: {e}"))?;
+            return Ok(Some(event));
+        }
+    }
+}
+
+/// Closes an [`AgentViewer`]'s connection; its next read returns `None`.
+pub struct AgentStopHandle(TcpStream);
+
+impl AgentStopHandle {
+    pub fn stop(&self) {
+        let _ = self.0.shutdown(Shutdown::Both);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::BenchOp;
+
+    #[tokio::test]
+    async fn viewers_get_the_backlog_then_live_events_until_the_agent_closes() {
+        let agent = AgentServer::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        agent.publish(&StreamEvent::PassStart {
+            op: BenchOp::Read,
+            chunk_bytes: 4096,
+            duration_secs: 5,
+        });
+
+        let mut viewer = AgentViewer::connect(&agent.local_addr().to_string()).unwrap();
+        agent.wait_for_viewer(None).await.unwrap();
+        agent.publish(&StreamEvent::Warning {
+            message: "retrying".to_string(),
+        });
+        drop(agent);
+
+        assert!(matches!(
+            viewer.next_event().unwrap(),
+            Some(StreamEvent::PassStart {
+                chunk_bytes: 4096,
+                ..
+            })
+        ));
+        assert!(matches!(
+            viewer.next_event().unwrap(),
+            Some(StreamEvent::Warning { message }) if message == "retrying"
+        ));
+        assert!(viewer.next_event().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn publish_does_not_wait_for_a_viewer_that_stopped_reading() {
+        let agent = AgentServer::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let _stalled = TcpStream::connect(agent.local_addr()).unwrap();
+        agent.wait_for_viewer(None).await.unwrap();
+
+        // Publish until the stalled viewer's socket buffer and queue are full and it is dropped;
+        // no single publish may wait on the socket.
+        let message = "x".repeat(16 * 1024);
+        let mut slowest = Duration::ZERO;
+        for _ in 0..100_000 {
+            let start = std::time::Instant::now();
+            agent.publish(&StreamEvent::Warning {
+                message: message.clone(),
+            });
+            slowest = slowest.max(start.elapsed());
+            if agent.viewer_count() == 0 {
+                break;
+            }
+        }
+        assert!(slowest < VIEWER_WRITE_TIMEOUT / 2, "{slowest:?}");
+        assert_eq!(agent.viewer_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn wait_for_viewer_gives_up_after_the_timeout() {
+        let agent = AgentServer::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let err = agent
+            .wait_for_viewer(Some(Duration::from_millis(100)))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no viewer connected"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn stop_handle_ends_a_viewer_waiting_for_the_run() {
+        let agent = AgentServer::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let mut viewer = AgentViewer::connect(&agent.local_addr().to_string()).unwrap();
+        let stop = viewer.stop_handle().unwrap();
+        let reader = thread::spawn(move || viewer.next_event().unwrap_or(None));
+        agent.wait_for_viewer(None).await.unwrap();
+        stop.stop();
+        assert!(reader.join().unwrap().is_none());
+    }
+
+    #[test]
+    fn connect_reports_unreachable_agents() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let err = AgentViewer::connect(&addr.to_string()).err().unwrap();
+        assert!(
+            err.to_string().contains("could not connect to agent"),
+            "{err}"
+        );
+        assert!(AgentViewer::connect("no-port").is_err());
+    }
+}
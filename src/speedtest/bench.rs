@@ -1,7 +1,8 @@
 //! Benchmark operation and mode types shared by CLI and GUI.
 
 /// Which memory operations to run during a session.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BenchMode {
     #[default]
     Read,
@@ -10,6 +11,14 @@ pub enum BenchMode {
 }
 
 impl BenchMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            BenchMode::Read => "read",
+            BenchMode::Write => "write",
+            BenchMode::Both => "both",
+        }
+    }
+
     pub fn needs_write_target(self) -> bool {
         matches!(self, BenchMode::Write | BenchMode::Both)
     }
@@ -24,7 +33,7 @@ impl BenchMode {
 }
 
 /// Single benchmark operation (read or write).
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum BenchOp {
     Read,
@@ -58,6 +67,8 @@ pub struct BenchStats {
     pub throughput_mib_s: f64,
     pub ops_per_sec: u64,
     pub latency_us: f64,
+    /// Ops skipped after exhausting retries during this interval.
+    pub skipped_ops: u64,
 }
 
 #[cfg(test)]
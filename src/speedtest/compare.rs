@@ -0,0 +1,251 @@
+//! Pass-by-pass comparison of saved benchmark reports.
+
+use super::{BenchOp, BenchmarkReport, PassSummary};
+
+/// Regression limits, in percent, applied to each matched pass.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CompareThresholds {
+    /// Largest tolerated drop in average MiB/s.
+    pub max_throughput_drop_pct: f64,
+    /// Largest tolerated rise in average latency.
+    pub max_latency_rise_pct: f64,
+}
+
+impl Default for CompareThresholds {
+    fn default() -> Self {
+        Self {
+            max_throughput_drop_pct: 5.0,
+            max_latency_rise_pct: 10.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MetricDelta {
+    pub baseline: f64,
+    pub candidate: f64,
+}
+
+impl MetricDelta {
+    pub fn delta(self) -> f64 {
+        self.candidate - self.baseline
+    }
+
+    /// `None` when the baseline is zero and a percentage is meaningless.
+    pub fn percent_change(self) -> Option<f64> {
+        (self.baseline != 0.0).then(|| self.delta() / self.baseline * 100.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareMetric {
+    Throughput,
+    OpsPerSec,
+    Latency,
+}
+
+impl CompareMetric {
+    pub const ALL: [Self; 3] = [Self::Throughput, Self::OpsPerSec, Self::Latency];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Throughput => "Avg MiB/s",
+            Self::OpsPerSec => "Avg ops/s",
+            Self::Latency => "Avg μs",
+        }
+    }
+
+    pub(super) fn value(self, summary: &PassSummary) -> f64 {
+        match self {
+            Self::Throughput => summary.avg_mib_s,
+            Self::OpsPerSec => summary.avg_ops_s,
+            Self::Latency => summary.avg_latency_us,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PassComparison {
+    pub op: BenchOp,
+    pub chunk_bytes: usize,
+    /// Scenario step index shared by both passes.
+    pub step: Option<usize>,
+    /// One entry per [`CompareMetric::ALL`], in order.
+    pub metrics: Vec<(CompareMetric, MetricDelta)>,
+    pub regressions: Vec<CompareMetric>,
+}
+
+impl PassComparison {
+    pub fn is_regression(&self) -> bool {
+        !self.regressions.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReportComparison {
+    pub passes: Vec<PassComparison>,
+    /// `(op, chunk_bytes)` present in the baseline but not the candidate.
+    pub missing_in_candidate: Vec<(BenchOp, usize)>,
+    /// `(op, chunk_bytes)` present in the candidate but not the baseline.
+    pub missing_in_baseline: Vec<(BenchOp, usize)>,
+}
+
+impl ReportComparison {
+    pub fn regression_count(&self) -> usize {
+        self.passes
+            .iter()
+            .filter(|pass| pass.is_regression())
+            .count()
+    }
+}
+
+/// Match passes by `(op, chunk_bytes)` and scenario step, and flag metrics beyond `thresholds`.
+pub fn compare_reports(
+    baseline: &BenchmarkReport,
+    candidate: &BenchmarkReport,
+    thresholds: CompareThresholds,
+) -> ReportComparison {
+    let mut comparison = ReportComparison::default();
+
+    for base in &baseline.passes {
+        let Some(cand) = find_pass(candidate, base) else {
+            comparison
+                .missing_in_candidate
+                .push((base.op, base.chunk_bytes));
+            continue;
+        };
+        comparison.passes.push(compare_pass(base, cand, thresholds));
+    }
+
+    comparison.missing_in_baseline = candidate
+        .passes
+        .iter()
+        .filter(|cand| find_pass(baseline, cand).is_none())
+        .map(|cand| (cand.op, cand.chunk_bytes))
+        .collect();
+
+    comparison
+}
+
+fn find_pass<'a>(report: &'a BenchmarkReport, other: &PassSummary) -> Option<&'a PassSummary> {
+    report.passes.iter().find(|pass| {
+        pass.op == other.op && pass.chunk_bytes == other.chunk_bytes && pass.step == other.step
+    })
+}
+
+fn compare_pass(
+    base: &PassSummary,
+    cand: &PassSummary,
+    thresholds: CompareThresholds,
+) -> PassComparison {
+    let metrics = CompareMetric::ALL
+        .into_iter()
+        .map(|metric| {
+            (
+                metric,
+                MetricDelta {
+                    baseline: metric.value(base),
+                    candidate: metric.value(cand),
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let regressions = metrics
+        .iter()
+        .filter(|(metric, delta)| is_regression(*metric, *delta, thresholds))
+        .map(|(metric, _)| *metric)
+        .collect();
+
+    PassComparison {
+        op: base.op,
+        chunk_bytes: base.chunk_bytes,
+        step: base.step,
+        metrics,
+        regressions,
+    }
+}
+
+fn is_regression(metric: CompareMetric, delta: MetricDelta, thresholds: CompareThresholds) -> bool {
+    let Some(pct) = delta.percent_change() else {
+        return false;
+    };
+    match metric {
+        CompareMetric::Throughput => -pct > thresholds.max_throughput_drop_pct,
+        // Ops/s tracks throughput at a fixed chunk size; gate on MiB/s only.
+        CompareMetric::OpsPerSec => false,
+        CompareMetric::Latency => pct > thresholds.max_latency_rise_pct,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::BenchMode;
+
+    fn pass(op: BenchOp, chunk_bytes: usize, mib_s: f64, latency_us: f64) -> PassSummary {
+        PassSummary {
+            op,
+            avg_ops_s: mib_s * 256.0,
+            min_latency_us: latency_us,
+            avg_latency_us: latency_us,
+            max_latency_us: latency_us,
+            ..PassSummary::test_read(chunk_bytes, mib_s)
+        }
+    }
+
+    fn report(passes: Vec<PassSummary>) -> BenchmarkReport {
+        BenchmarkReport::test_report(BenchMode::Both, &[4096, 8192], passes)
+    }
+
+    #[test]
+    fn flags_throughput_drop_and_latency_rise_beyond_thresholds() {
+        let baseline = report(vec![
+            pass(BenchOp::Read, 4096, 100.0, 10.0),
+            pass(BenchOp::Read, 8192, 100.0, 10.0),
+        ]);
+        let candidate = report(vec![
+            pass(BenchOp::Read, 4096, 96.0, 10.5),
+            pass(BenchOp::Read, 8192, 90.0, 12.0),
+        ]);
+
+        let comparison = compare_reports(&baseline, &candidate, CompareThresholds::default());
+
+        assert_eq!(comparison.passes.len(), 2);
+        assert!(!comparison.passes[0].is_regression());
+        assert_eq!(
+            comparison.passes[1].regressions,
+            vec![CompareMetric::Throughput, CompareMetric::Latency]
+        );
+        assert_eq!(comparison.regression_count(), 1);
+    }
+
+    #[test]
+    fn reports_unmatched_passes_on_both_sides() {
+        let baseline = report(vec![pass(BenchOp::Read, 4096, 100.0, 10.0)]);
+        let candidate = report(vec![pass(BenchOp::Write, 4096, 100.0, 10.0)]);
+
+        let comparison = compare_reports(&baseline, &candidate, CompareThresholds::default());
+
+        assert!(comparison.passes.is_empty());
+        assert_eq!(comparison.missing_in_candidate, vec![(BenchOp::Read, 4096)]);
+        assert_eq!(comparison.missing_in_baseline, vec![(BenchOp::Write, 4096)]);
+    }
+
+    #[test]
+    fn percent_change_is_undefined_for_zero_baseline() {
+        let delta = MetricDelta {
+            baseline: 0.0,
+            candidate: 5.0,
+        };
+        assert_eq!(delta.percent_change(), None);
+        assert_eq!(
+            MetricDelta {
+                baseline: 200.0,
+                candidate: 150.0
+            }
+            .percent_change(),
+            Some(-25.0)
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Wall-clock time of each connection setup phase, so a slow DTB scan reads differently from a slow FPGA.
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Timed connection setup phases, in run order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectStep {
+    InventoryScan,
+    ConnectorBuild,
+    /// OS plugin start, including the kernel and DTB scan.
+    OsInit,
+    ProcessLookup,
+    ModuleLookup,
+    WriteRegionSearch,
+    /// Canary write, read-back and restore of the write probe.
+    CanaryVerification,
+}
+
+impl ConnectStep {
+    pub fn label(self) -> &'static str {
+        match self {
+            ConnectStep::InventoryScan => "inventory scan",
+            ConnectStep::ConnectorBuild => "connector build",
+            ConnectStep::OsInit => "OS init",
+            ConnectStep::ProcessLookup => "process lookup",
+            ConnectStep::ModuleLookup => "module lookup",
+            ConnectStep::WriteRegionSearch => "write region search",
+            ConnectStep::CanaryVerification => "canary verification",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectTiming {
+    pub step: ConnectStep,
+    pub elapsed_us: u64,
+}
+
+/// Phases that ran during one connect; plugin phases are absent for the native connector and
+/// write phases for read-only sessions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ConnectTimings(pub Vec<ConnectTiming>);
+
+impl ConnectTimings {
+    /// Run `f` and record its duration under `step`, also when it fails.
+    pub(super) fn time<T>(&mut self, step: ConnectStep, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let value = f();
+        let elapsed_us = u64::try_from(start.elapsed().as_micros()).unwrap_or(u64::MAX);
+        self.0.push(ConnectTiming { step, elapsed_us });
+        value
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, step: ConnectStep) -> Option<u64> {
+        self.0
+            .iter()
+            .find(|timing| timing.step == step)
+            .map(|timing| timing.elapsed_us)
+    }
+
+    pub fn total_us(&self) -> u64 {
+        self.0.iter().map(|timing| timing.elapsed_us).sum()
+    }
+
+    pub fn format_total(&self) -> String {
+        format_us(self.total_us())
+    }
+
+    /// `step 12.3 ms, ...` on one line for report overviews.
+    pub fn summary(&self) -> String {
+        self.0
+            .iter()
+            .map(|timing| format!("{} {}", timing.step.label(), format_us(timing.elapsed_us)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Heading plus one aligned line per phase, for probe details and console logs.
+    pub fn detail_lines(&self) -> Vec<String> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let mut lines = vec![format!("Connect timings (total {}):", self.format_total())];
+        lines.extend(self.0.iter().map(|timing| {
+            format!(
+                "  {:<20} {:>10}",
+                timing.step.label(),
+                format_us(timing.elapsed_us)
+            )
+        }));
+        lines
+    }
+}
+
+fn format_us(us: u64) -> String {
+    if us >= 1_000_000 {
+        format!("{:.2} s", us as f64 / 1_000_000.0)
+    } else {
+        format!("{:.1} ms", us as f64 / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_steps_in_order_and_formats_them() {
+        let mut timings = ConnectTimings::default();
+        let result: anyhow::Result<()> =
+            timings.time(ConnectStep::OsInit, || Err(anyhow::anyhow!("no DTB")));
+        assert!(result.is_err());
+        assert_eq!(timings.0.len(), 1);
+
+        let timings = ConnectTimings(vec![
+            ConnectTiming {
+                step: ConnectStep::ConnectorBuild,
+                elapsed_us: 850_240,
+            },
+            ConnectTiming {
+                step: ConnectStep::OsInit,
+                elapsed_us: 12_400_000,
+            },
+        ]);
+        assert_eq!(timings.total_us(), 13_250_240);
+        assert_eq!(timings.get(ConnectStep::OsInit), Some(12_400_000));
+        assert_eq!(timings.get(ConnectStep::InventoryScan), None);
+        assert_eq!(
+            timings.summary(),
+            "connector build 850.2 ms, OS init 12.40 s"
+        );
+        let lines = timings.detail_lines();
+        assert_eq!(lines[0], "Connect timings (total 13.25 s):");
+        assert_eq!(lines.len(), 3);
+
+        let json = serde_json::to_string(&timings).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"step":"connector_build","elapsed_us":850240},{"step":"os_init","elapsed_us":12400000}]"#
+        );
+        assert!(ConnectTimings::default().detail_lines().is_empty());
+    }
+}
@@ -0,0 +1,962 @@
+//! Local control API: connect, start, stop and follow benchmark runs over a small JSON HTTP API.
+//!
+//! Runs are driven on their own thread and runtime like the GUI's test runner. Every run keeps its
+//! [`StreamEvent`]s so `/events` clients can join late, and the finished [`BenchmarkReport`] stays
+//! available at `/report` until the next run starts.
+//!
+//! Browsers are kept out: requests carrying an `Origin` header are refused and `POST` bodies must
+//! be `application/json`, so a web page cannot reach the API through the user's browser. Binding a
+//! non-loopback address needs a bearer token that every request must present.
+
+use super::metrics_server::read_request_head;
+use super::{
+    BenchErrorKind, BenchMode, BenchObserver, BenchOp, BenchSample, BenchmarkReport,
+    BenchmarkSession, Connector, PassSummary, ProfileTarget, RunFailure, RunLabels, SessionStep,
+    SpeedTest, StreamEvent, WriteRestoreOutcome,
+};
+use crate::bench_config::{DEFAULT_CHUNK_SIZES, max_chunk_bytes_in_list, validate_chunk_sizes};
+use anyhow::{Result, bail};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// `--listen` default for the `serve` subcommand.
+pub const DEFAULT_CONTROL_ADDR: &str = "127.0.0.1:9898";
+
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+const EVENT_POLL: Duration = Duration::from_millis(50);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_REQUEST_BODY_BYTES: usize = 64 * 1024;
+
+/// `POST /connect` body; unset fields use the CLI defaults.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConnectRequest {
+    pub connector: Connector,
+    /// PCILeech device string.
+    pub device: String,
+    /// Ops the session can run; a write probe is only set up for `write` and `both`.
+    pub mode: BenchMode,
+    /// Largest chunk later runs will use, for sizing the write probe.
+    pub max_chunk_bytes: usize,
+    /// Write probe selection, as in profiles.
+    pub target: ProfileTarget,
+}
+
+impl Default for ConnectRequest {
+    fn default() -> Self {
+        Self {
+            connector: Connector::default(),
+            device: "FPGA".to_string(),
+            mode: BenchMode::Read,
+            max_chunk_bytes: max_chunk_bytes_in_list(&DEFAULT_CHUNK_SIZES),
+            target: ProfileTarget::default(),
+        }
+    }
+}
+
+/// `POST /start` body; unset fields use the CLI defaults and the connected mode.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StartRequest {
+    /// Must only use ops the session connected for.
+    pub mode: Option<BenchMode>,
+    /// Chunk sizes in bytes.
+    pub sizes: Vec<usize>,
+    /// Seconds per chunk size.
+    pub duration: u64,
+    /// Keep the interval samples in the report.
+    pub include_samples: bool,
+    pub labels: RunLabels,
+    pub note: Option<String>,
+}
+
+impl Default for StartRequest {
+    fn default() -> Self {
+        Self {
+            mode: None,
+            sizes: DEFAULT_CHUNK_SIZES.to_vec(),
+            duration: 10,
+            include_samples: false,
+            labels: RunLabels::new(),
+            note: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlState {
+    Disconnected,
+    Connecting,
+    /// Connected and ready to start a run.
+    Idle,
+    Running,
+    /// A stop was requested; the current pass is winding down.
+    Stopping,
+}
+
+/// `GET /status` body.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ControlStatus {
+    pub state: ControlState,
+    pub connection: Option<ConnectionInfo>,
+    /// Increments with every `POST /start`; `0` before the first run.
+    pub run_id: u64,
+    pub current_pass: Option<CurrentPass>,
+    pub completed_passes: usize,
+    /// Events recorded for the current or last run (`/events?from=N` resumes from here).
+    pub events: usize,
+    pub has_report: bool,
+    /// Why the last connect or run failed.
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionInfo {
+    pub connector: Connector,
+    pub mode: BenchMode,
+    pub max_chunk_bytes: usize,
+    /// Read and write probe lines as printed by the CLI.
+    pub probes: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurrentPass {
+    pub op: BenchOp,
+    pub chunk_bytes: usize,
+}
+
+struct Session {
+    test: SpeedTest,
+    info: ConnectionInfo,
+}
+
+#[derive(Default)]
+struct ControlInner {
+    connecting: bool,
+    session: Option<Session>,
+    run_thread: Option<JoinHandle<()>>,
+    running: bool,
+    run_id: u64,
+    current_pass: Option<CurrentPass>,
+    completed_passes: usize,
+    events: Vec<StreamEvent>,
+    report: Option<BenchmarkReport>,
+    error: Option<String>,
+}
+
+impl ControlInner {
+    fn status(&self) -> ControlStatus {
+        let state = match (&self.session, self.connecting, self.running) {
+            (_, true, _) => ControlState::Connecting,
+            (Some(session), _, true) if session.test.is_cancelled() => ControlState::Stopping,
+            (_, _, true) => ControlState::Running,
+            (Some(_), _, false) => ControlState::Idle,
+            (None, _, false) => ControlState::Disconnected,
+        };
+        ControlStatus {
+            state,
+            connection: self.session.as_ref().map(|session| session.info.clone()),
+            run_id: self.run_id,
+            current_pass: self.current_pass,
+            completed_passes: self.completed_passes,
+            events: self.events.len(),
+            has_report: self.report.is_some(),
+            error: self.error.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    inner: Mutex<ControlInner>,
+    stop: AtomicBool,
+    /// `Authorization: Bearer` value every request must carry, when set.
+    token: Option<String>,
+}
+
+impl Shared {
+    fn push_event(&self, event: StreamEvent) {
+        self.inner.lock().events.push(event);
+    }
+
+    /// Cancel any run and wait for its thread, so the write probe is restored.
+    fn finish_run(&self) {
+        let run_thread = {
+            let mut inner = self.inner.lock();
+            if let Some(session) = &inner.session {
+                session.test.request_cancel();
+            }
+            inner.run_thread.take()
+        };
+        if let Some(handle) = run_thread {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Serves the control API on a background thread until `POST /shutdown` or drop.
+pub struct ControlServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ControlServer {
+    /// Bind the API; with a `token`, every request must send `Authorization: Bearer <token>`.
+    ///
+    /// A non-loopback `addr` is refused without a token.
+    pub fn bind(addr: SocketAddr, token: Option<String>) -> Result<Self> {
+        if token.as_deref().is_some_and(str::is_empty) {
+            bail!("control API token must not be empty");
+        }
+        if !addr.ip().is_loopback() && token.is_none() {
+            bail!("control API on non-loopback address {addr} needs a token");
+        }
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| anyhow::anyhow!("could not bind control API on {addr}: {e}"))?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            token,
+            ..Shared::default()
+        });
+        let handle = thread::Builder::new()
+            .name("control-server".to_string())
+            .spawn({
+                let shared = Arc::clone(&shared);
+                move || accept_loop(&listener, &shared)
+            })?;
+
+        Ok(Self {
+            addr,
+            shared,
+            handle: Some(handle),
+        })
+    }
+
+    /// Bound address (resolves port `0` to the assigned port).
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Block until a client sends `POST /shutdown`; a running benchmark is stopped first.
+    pub fn wait(mut self) {
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        self.shared.finish_run();
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        self.shared.finish_run();
+    }
+}
+
+fn accept_loop(listener: &TcpListener, shared: &Arc<Shared>) {
+    while !shared.stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            // `/connect` blocks and `/events` streams, so every client gets its own thread.
+            Ok((stream, _)) => {
+                let shared = Arc::clone(shared);
+                let _ = thread::Builder::new()
+                    .name("control-client".to_string())
+                    .spawn(move || {
+                        let _ = handle_connection(stream, &shared);
+                    });
+            }
+            // WouldBlock between connections; transient accept errors get the same back-off.
+            Err(_) => thread::sleep(ACCEPT_POLL),
+        }
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    /// `(name, value)` pairs in request order; names keep the client's casing.
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn json<T: Serialize>(status: &'static str, value: &T) -> Self {
+        let body = serde_json::to_string_pretty(value).unwrap_or_else(|e| e.to_string());
+        Self {
+            status,
+            body: body + "\n",
+        }
+    }
+
+    fn error(status: &'static str, message: impl std::fmt::Display) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message.to_string() }))
+    }
+
+    /// `500` for a failed connect, with the error's `kind` when it has a [`BenchErrorKind`].
+    fn connect_failed(error: &anyhow::Error) -> Self {
+        let mut body = serde_json::json!({ "error": error.to_string() });
+        if let Some(kind) = BenchErrorKind::of(error) {
+            body["kind"] = serde_json::json!(kind);
+        }
+        Self::json("500 Internal Server Error", &body)
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, shared: &Arc<Shared>) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let Some(request) = read_request(&mut stream)? else {
+        return write_response(
+            &mut stream,
+            &Response::error("400 Bad Request", "malformed request"),
+        );
+    };
+    if let Some(response) = reject(&request, shared) {
+        return write_response(&mut stream, &response);
+    }
+    if (request.method.as_str(), request.path.as_str()) == ("GET", "/events") {
+        return stream_events(&mut stream, shared, &request.query);
+    }
+    let response = route(&request, shared);
+    write_response(&mut stream, &response)
+}
+
+/// Checks every request passes before it is routed: no browser origin, the token when one is
+/// set, and a JSON content type on `POST`.
+fn reject(request: &Request, shared: &Shared) -> Option<Response> {
+    if request.header("origin").is_some() {
+        return Some(Response::error(
+            "403 Forbidden",
+            "requests from browsers (with an Origin header) are not accepted",
+        ));
+    }
+    if let Some(token) = &shared.token {
+        let sent = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim);
+        if !sent.is_some_and(|sent| tokens_match(sent, token)) {
+            return Some(Response::error(
+                "401 Unauthorized",
+                "missing or wrong Authorization: Bearer token",
+            ));
+        }
+    }
+    if request.method == "POST" {
+        let media_type = request
+            .header("content-type")
+            .and_then(|value| value.split(';').next())
+            .map(str::trim);
+        if !media_type.is_some_and(|media_type| media_type.eq_ignore_ascii_case("application/json"))
+        {
+            return Some(Response::error(
+                "415 Unsupported Media Type",
+                "POST requests need Content-Type: application/json",
+            ));
+        }
+    }
+    None
+}
+
+/// Compares every byte so the time taken does not reveal how much of the token matched.
+fn tokens_match(sent: &str, token: &str) -> bool {
+    sent.len() == token.len()
+        && sent
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn route(request: &Request, shared: &Arc<Shared>) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/status") => Response::json("200 OK", &shared.inner.lock().status()),
+        ("GET", "/report") => match &shared.inner.lock().report {
+            Some(report) => Response::json("200 OK", report),
+            None => Response::error("404 Not Found", "no finished run yet"),
+        },
+        ("POST", "/connect") => match parse_body(&request.body) {
+            Ok(connect) => handle_connect(shared, connect),
+            Err(response) => response,
+        },
+        ("POST", "/disconnect") => handle_disconnect(shared),
+        ("POST", "/start") => match parse_body(&request.body) {
+            Ok(start) => handle_start(shared, start),
+            Err(response) => response,
+        },
+        ("POST", "/stop") => handle_stop(shared),
+        ("POST", "/shutdown") => {
+            shared.stop.store(true, Ordering::Relaxed);
+            if let Some(session) = &shared.inner.lock().session {
+                session.test.request_cancel();
+            }
+            Response::json("200 OK", &serde_json::json!({ "shutdown": true }))
+        }
+        (
+            _,
+            "/status" | "/report" | "/events" | "/connect" | "/disconnect" | "/start" | "/stop"
+            | "/shutdown",
+        ) => Response::error("405 Method Not Allowed", "wrong method for this endpoint"),
+        _ => Response::error(
+            "404 Not Found",
+            "endpoints: GET /status, /events, /report; POST /connect, /disconnect, /start, /stop, /shutdown",
+        ),
+    }
+}
+
+/// An empty body means "all defaults".
+fn parse_body<T: Default + serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, Response> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(T::default());
+    }
+    serde_json::from_slice(body)
+        .map_err(|e| Response::error("400 Bad Request", format!("invalid JSON body: {e}")))
+}
+
+fn handle_connect(shared: &Arc<Shared>, connect: ConnectRequest) -> Response {
+    {
+        let mut inner = shared.inner.lock();
+        if inner.connecting || inner.running {
+            return Response::error("409 Conflict", "a connect or run is in progress");
+        }
+        inner.connecting = true;
+        inner.session = None;
+        inner.error = None;
+    }
+
+    let result = connect_session(&connect);
+    let mut inner = shared.inner.lock();
+    inner.connecting = false;
+    match result {
+        Ok(session) => {
+            inner.session = Some(session);
+            Response::json("200 OK", &inner.status())
+        }
+        Err(e) => {
+            inner.error = Some(e.to_string());
+            Response::connect_failed(&e)
+        }
+    }
+}
+
+fn connect_session(connect: &ConnectRequest) -> Result<Session> {
+    validate_chunk_sizes(&[connect.max_chunk_bytes])?;
+    let device = connect.device.trim();
+    if matches!(connect.connector, Connector::Pcileech) && device.is_empty() {
+        bail!("PCILeech requires a non-empty device string");
+    }
+    let write_policy = connect.target.write_policy();
+    write_policy.validate()?;
+    let test = SpeedTest::with_write_policy(
+        connect.connector,
+        device.to_string(),
+        connect.mode,
+        connect.max_chunk_bytes,
+        &write_policy,
+    )?;
+    let info = ConnectionInfo {
+        connector: connect.connector,
+        mode: connect.mode,
+        max_chunk_bytes: connect.max_chunk_bytes,
+        probes: test.probe_connect_detail_lines(),
+    };
+    Ok(Session { test, info })
+}
+
+fn handle_disconnect(shared: &Arc<Shared>) -> Response {
+    let mut inner = shared.inner.lock();
+    if inner.connecting || inner.running {
+        return Response::error("409 Conflict", "a connect or run is in progress");
+    }
+    inner.session = None;
+    Response::json("200 OK", &inner.status())
+}
+
+fn handle_start(shared: &Arc<Shared>, start: StartRequest) -> Response {
+    let mut inner = shared.inner.lock();
+    if inner.running {
+        return Response::error("409 Conflict", "a run is already in progress");
+    }
+    let Some(session) = &inner.session else {
+        return Response::error("409 Conflict", "not connected; POST /connect first");
+    };
+    let mode = start.mode.unwrap_or(session.info.mode);
+    if let Err(e) = validate_start(&session.info, mode, &start) {
+        return Response::error("400 Bad Request", e);
+    }
+
+    let test = session.test.clone();
+    let connector = session.info.connector;
+    test.reset_cancel();
+    if let Some(handle) = inner.run_thread.take() {
+        let _ = handle.join();
+    }
+    inner.running = true;
+    inner.run_id += 1;
+    inner.current_pass = None;
+    inner.completed_passes = 0;
+    inner.events.clear();
+    inner.report = None;
+    inner.error = None;
+
+    let run_shared = Arc::clone(shared);
+    let spawned = thread::Builder::new()
+        .name("control-run".to_string())
+        .spawn(move || run_benchmark(&run_shared, test, connector, mode, start));
+    match spawned {
+        Ok(handle) => {
+            inner.run_thread = Some(handle);
+            Response::json("202 Accepted", &inner.status())
+        }
+        Err(e) => {
+            inner.running = false;
+            Response::error("500 Internal Server Error", e)
+        }
+    }
+}
+
+fn validate_start(info: &ConnectionInfo, mode: BenchMode, start: &StartRequest) -> Result<()> {
+    if !(1..=60).contains(&start.duration) {
+        bail!("duration must be between 1 and 60 seconds");
+    }
+    validate_chunk_sizes(&start.sizes)?;
+    if let Some(op) = mode
+        .ops_for_size()
+        .iter()
+        .find(|op| !info.mode.ops_for_size().contains(op))
+    {
+        bail!(
+            "mode {} needs {} passes but the session connected in {} mode",
+            mode.label(),
+            op.label(),
+            info.mode.label()
+        );
+    }
+    let largest = max_chunk_bytes_in_list(&start.sizes);
+    if mode.needs_write_target() && largest > info.max_chunk_bytes {
+        bail!(
+            "write chunk {largest} B exceeds the connected max_chunk_bytes ({} B); reconnect with a larger value",
+            info.max_chunk_bytes
+        );
+    }
+    Ok(())
+}
+
+fn handle_stop(shared: &Arc<Shared>) -> Response {
+    let inner = shared.inner.lock();
+    match &inner.session {
+        Some(session) if inner.running => {
+            session.test.request_cancel();
+            Response::json("200 OK", &inner.status())
+        }
+        _ => Response::error("409 Conflict", "no run in progress"),
+    }
+}
+
+fn run_benchmark(
+    shared: &Arc<Shared>,
+    test: SpeedTest,
+    connector: Connector,
+    mode: BenchMode,
+    start: StartRequest,
+) {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            let mut inner = shared.inner.lock();
+            inner.running = false;
+            inner.error = Some(format!("could not start runtime: {e}"));
+            return;
+        }
+    };
+    let mut session = BenchmarkSession::new(connector, mode, start.sizes.clone(), start.duration)
+        .with_include_samples(start.include_samples)
+        .with_labels(start.labels.clone());
+    if let Some(note) = &start.note {
+        session = session.with_note(note);
+    }
+    shared.push_event(StreamEvent::run_start(
+        &test,
+        connector,
+        mode,
+        start.duration,
+        &start.sizes,
+    ));
+    let recorder = Arc::new(RunRecorder(Arc::clone(shared)));
+    // Earlier runs on this session handed the write region back to the target.
+    let result = if mode.needs_write_target() {
+        test.reverify_write_target()
+    } else {
+        Ok(())
+    };
+    let result = match result {
+        Ok(()) => runtime.block_on(session.run(test.clone(), recorder)),
+        Err(e) => Err(RunFailure {
+            report: Box::new(session.report(&test, Vec::new(), Vec::new()).with_error(&e)),
+            error: e,
+        }),
+    };
+
+    let mut inner = shared.inner.lock();
+    let run_error = match result {
+        Ok(report) => {
+            inner.report = Some(report);
+            None
+        }
+        Err(failure) => {
+            // Keep the passes finished before the failure available at `/report`.
+            inner.report = Some(*failure.report);
+            Some(failure.error)
+        }
+    };
+    inner.current_pass = None;
+    inner.running = false;
+    inner.events.push(StreamEvent::RunEnd {
+        error: run_error.as_ref().map(|e| e.to_string()),
+    });
+    if let Some(e) = run_error {
+        inner.error = Some(e.to_string());
+        if test.target_lost() {
+            inner.session = None;
+        }
+    }
+}
+
+/// Records a run's events for `/events` and its progress for `/status`.
+struct RunRecorder(Arc<Shared>);
+
+impl BenchObserver for RunRecorder {
+    fn pass_start(&self, _test: &SpeedTest, op: BenchOp, chunk_bytes: usize, step: &SessionStep) {
+        let mut inner = self.0.inner.lock();
+        inner.current_pass = Some(CurrentPass { op, chunk_bytes });
+        inner.events.push(StreamEvent::PassStart {
+            op,
+            chunk_bytes,
+            duration_secs: step.duration_secs,
+        });
+    }
+
+    fn sample(&self, sample: &BenchSample) {
+        self.0.push_event(StreamEvent::Sample(*sample));
+    }
+
+    fn warning(&self, message: &str) {
+        self.0.push_event(StreamEvent::Warning {
+            message: message.to_string(),
+        });
+    }
+
+    fn pass_end(&self, summary: &PassSummary) {
+        let mut inner = self.0.inner.lock();
+        inner.completed_passes += 1;
+        inner.events.push(StreamEvent::PassSummary(summary.clone()));
+    }
+
+    fn write_restored(&self, outcome: &Result<WriteRestoreOutcome>) {
+        if let Err(e) = outcome {
+            self.0.push_event(StreamEvent::Warning {
+                message: format!("failed to restore write probe bytes: {e}"),
+            });
+        }
+    }
+}
+
+/// NDJSON body of the run's events from `?from=N`, following the run until it ends.
+fn stream_events(stream: &mut TcpStream, shared: &Arc<Shared>, query: &str) -> io::Result<()> {
+    let mut next = query_param(query, "from")
+        .and_then(|from| from.parse::<usize>().ok())
+        .unwrap_or(0);
+    stream.set_write_timeout(None)?;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n"
+    )?;
+    stream.flush()?;
+
+    let run_id = shared.inner.lock().run_id;
+    loop {
+        let (lines, done) = {
+            let inner = shared.inner.lock();
+            let lines = inner
+                .events
+                .get(next..)
+                .unwrap_or_default()
+                .iter()
+                .map(StreamEvent::to_json_line)
+                .collect::<Vec<_>>();
+            let done = !inner.running || inner.run_id != run_id;
+            (lines, done)
+        };
+        next += lines.len();
+        for line in lines {
+            writeln!(stream, "{line}")?;
+        }
+        stream.flush()?;
+        if done || shared.stop.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        thread::sleep(EVENT_POLL);
+    }
+}
+
+/// Head plus a `Content-Length` body; `None` for a missing request line.
+fn read_request(stream: &mut TcpStream) -> io::Result<Option<Request>> {
+    let raw = read_request_head(stream)?;
+    let (head, rest) = raw.split_once("\r\n\r\n").unwrap_or((&raw, ""));
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Ok(None);
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let headers: Vec<(String, String)> = head
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    if content_length > MAX_REQUEST_BODY_BYTES {
+        return Ok(None);
+    }
+    let mut body = rest.as_bytes().to_vec();
+    let mut buf = [0u8; 1024];
+    while body.len() < content_length {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&buf[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok(Some(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        headers,
+        body,
+    }))
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(status code, body)` of one request, like a lab automation client would send it.
+    fn send(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+        send_with_headers(
+            addr,
+            method,
+            path,
+            "Content-Type: application/json\r\n",
+            body,
+        )
+    }
+
+    /// As [`send`], with `headers` (each ending in `\r\n`) instead of the JSON content type.
+    fn send_with_headers(
+        addr: SocketAddr,
+        method: &str,
+        path: &str,
+        headers: &str,
+        body: &str,
+    ) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\n{headers}Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let code = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (code, body.to_string())
+    }
+
+    #[test]
+    fn idle_server_reports_status_and_rejects_runs_without_a_session() {
+        let server = ControlServer::bind("127.0.0.1:0".parse().unwrap(), None).unwrap();
+        let addr = server.local_addr();
+
+        let (code, body) = send(addr, "GET", "/status", "");
+        assert_eq!(code, 200);
+        let status: ControlStatus = serde_json::from_str(&body).unwrap();
+        assert_eq!(status.state, ControlState::Disconnected);
+        assert_eq!(status.run_id, 0);
+
+        let (code, body) = send(addr, "POST", "/start", r#"{"sizes":[4096],"duration":1}"#);
+        assert_eq!(code, 409);
+        assert!(body.contains("POST /connect first"), "{body}");
+        assert_eq!(send(addr, "POST", "/stop", "").0, 409);
+        assert_eq!(send(addr, "GET", "/report", "").0, 404);
+        assert_eq!(send(addr, "GET", "/start", "").0, 405);
+        assert_eq!(send(addr, "GET", "/nope", "").0, 404);
+
+        let (code, body) = send(
+            addr,
+            "POST",
+            "/connect",
+            r#"{"connector":"native","speed":1}"#,
+        );
+        assert_eq!(code, 400);
+        assert!(body.contains("speed"), "{body}");
+
+        let (code, body) = send(addr, "GET", "/events?from=0", "");
+        assert_eq!(code, 200);
+        assert!(body.is_empty());
+
+        assert_eq!(send(addr, "POST", "/shutdown", "").0, 200);
+        server.wait();
+    }
+
+    #[test]
+    fn browser_requests_and_non_json_posts_are_refused() {
+        let server = ControlServer::bind("127.0.0.1:0".parse().unwrap(), None).unwrap();
+        let addr = server.local_addr();
+
+        let origin = "Origin: http://example.com\r\n";
+        assert_eq!(send_with_headers(addr, "GET", "/status", origin, "").0, 403);
+        let origin_json = "Origin: null\r\nContent-Type: application/json\r\n";
+        assert_eq!(
+            send_with_headers(addr, "POST", "/shutdown", origin_json, "").0,
+            403
+        );
+
+        let text = "Content-Type: text/plain\r\n";
+        assert_eq!(
+            send_with_headers(addr, "POST", "/connect", text, r#"{"connector":"native"}"#).0,
+            415
+        );
+        assert_eq!(send_with_headers(addr, "POST", "/shutdown", "", "").0, 415);
+        let charset = "content-type: Application/JSON; charset=utf-8\r\n";
+        assert_eq!(send_with_headers(addr, "POST", "/stop", charset, "").0, 409);
+
+        assert_eq!(send(addr, "POST", "/shutdown", "").0, 200);
+        server.wait();
+    }
+
+    #[test]
+    fn token_is_required_off_loopback_and_checked_on_every_request() {
+        let err = ControlServer::bind("0.0.0.0:0".parse().unwrap(), None)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("needs a token"), "{err}");
+        assert!(ControlServer::bind("127.0.0.1:0".parse().unwrap(), Some(String::new())).is_err());
+
+        let server =
+            ControlServer::bind("127.0.0.1:0".parse().unwrap(), Some("s3cret".to_string()))
+                .unwrap();
+        let addr = server.local_addr();
+
+        assert_eq!(send(addr, "GET", "/status", "").0, 401);
+        assert_eq!(send(addr, "GET", "/events", "").0, 401);
+        let wrong = "Authorization: Bearer s3cres\r\n";
+        assert_eq!(send_with_headers(addr, "GET", "/status", wrong, "").0, 401);
+        let right = "Authorization: Bearer s3cret\r\n";
+        assert_eq!(send_with_headers(addr, "GET", "/status", right, "").0, 200);
+
+        let shutdown = "Authorization: Bearer s3cret\r\nContent-Type: application/json\r\n";
+        assert_eq!(
+            send_with_headers(addr, "POST", "/shutdown", shutdown, "").0,
+            200
+        );
+        server.wait();
+    }
+
+    #[test]
+    fn start_requests_must_fit_the_connected_session() {
+        let info = ConnectionInfo {
+            connector: Connector::Native,
+            mode: BenchMode::Read,
+            max_chunk_bytes: 32768,
+            probes: Vec::new(),
+        };
+        let start: StartRequest = serde_json::from_str(r#"{"duration":5}"#).unwrap();
+        assert_eq!(start.sizes, DEFAULT_CHUNK_SIZES.to_vec());
+        assert!(validate_start(&info, BenchMode::Read, &start).is_ok());
+
+        let err = validate_start(&info, BenchMode::Both, &start).unwrap_err();
+        assert!(err.to_string().contains("connected in read mode"), "{err}");
+
+        let write_info = ConnectionInfo {
+            mode: BenchMode::Write,
+            ..info
+        };
+        let large = StartRequest {
+            sizes: vec![65536],
+            ..start.clone()
+        };
+        assert!(validate_start(&write_info, BenchMode::Write, &large).is_err());
+        let zero = StartRequest {
+            duration: 0,
+            ..start
+        };
+        assert!(validate_start(&write_info, BenchMode::Write, &zero).is_err());
+    }
+
+    #[test]
+    fn query_params_are_looked_up_by_name() {
+        assert_eq!(query_param("from=12&x=1", "from"), Some("12"));
+        assert_eq!(query_param("x=1", "from"), None);
+        assert_eq!(query_param("", "from"), None);
+    }
+}
@@ -0,0 +1,490 @@
+//! Connection doctor: run each connection stage on its own and report timing, outcome and hints.
+//!
+//! [`SpeedTest`](super::SpeedTest) setup stops at the first error. The doctor also shows which
+//! stages passed before it and how long each took. Save the [`DoctorReport`] as JSON to attach it
+//! to a support ticket.
+
+use super::error::BenchErrorKind;
+use super::initialization::{self, OS_PLUGIN};
+use super::report::unix_timestamp_secs;
+use super::write_target::{self, WriteTargetPolicy, format_page_type};
+use super::{Connector, ProbeTargets};
+use crate::bench_config::format_byte_count;
+use anyhow::Result;
+use memflow::{plugins::Inventory, prelude::v1::*};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, time::Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorStageKind {
+    Inventory,
+    Connector,
+    Os,
+    Process,
+    Module,
+    MemoryMap,
+    WriteTarget,
+}
+
+impl DoctorStageKind {
+    /// Every stage in run order.
+    pub const ALL: [DoctorStageKind; 7] = [
+        DoctorStageKind::Inventory,
+        DoctorStageKind::Connector,
+        DoctorStageKind::Os,
+        DoctorStageKind::Process,
+        DoctorStageKind::Module,
+        DoctorStageKind::MemoryMap,
+        DoctorStageKind::WriteTarget,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DoctorStageKind::Inventory => "inventory scan",
+            DoctorStageKind::Connector => "connector open",
+            DoctorStageKind::Os => "OS plugin init",
+            DoctorStageKind::Process => "process lookup",
+            DoctorStageKind::Module => "module lookup",
+            DoctorStageKind::MemoryMap => "memory map",
+            DoctorStageKind::WriteTarget => "write target",
+        }
+    }
+
+    /// Fixes to try when the stage fails with an error that has no [`BenchErrorKind`].
+    fn hints(self) -> &'static [&'static str] {
+        match self {
+            DoctorStageKind::Inventory => BenchErrorKind::PluginNotFound.hints(),
+            DoctorStageKind::Connector => BenchErrorKind::DeviceNotConnected.hints(),
+            DoctorStageKind::Os => &[
+                "Check that the target runs a Windows version the win32 plugin supports",
+                "A device that returns bad reads also fails here; compare with a known-good target",
+            ],
+            DoctorStageKind::Process => BenchErrorKind::ProcessNotFound.hints(),
+            DoctorStageKind::Module => BenchErrorKind::ModuleNotFound.hints(),
+            DoctorStageKind::MemoryMap => &[
+                "The OS plugin could not walk the process page tables; check the Windows version",
+                "Retry while the target is idle",
+            ],
+            DoctorStageKind::WriteTarget => BenchErrorKind::NoWritableRegion.hints(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Ok,
+    Failed,
+    /// Not run: an earlier stage failed or the connector does not use it.
+    Skipped,
+}
+
+impl StageStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            StageStatus::Ok => "ok",
+            StageStatus::Failed => "failed",
+            StageStatus::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DoctorStage {
+    pub stage: DoctorStageKind,
+    pub status: StageStatus,
+    pub elapsed_ms: f64,
+    pub detail: String,
+    /// Category of a typed failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<BenchErrorKind>,
+    /// Suggested fixes; only for failed stages.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<String>,
+}
+
+/// What the doctor connects to; mirrors the benchmark's connect settings.
+#[derive(Clone, Debug)]
+pub struct DoctorSettings {
+    pub connector: Connector,
+    /// PCILeech device string; ignored by other connectors.
+    pub device: String,
+    /// Largest chunk the write region must hold.
+    pub max_chunk_bytes: usize,
+    pub write_policy: WriteTargetPolicy,
+    /// Write and read back a canary in the chosen region, then restore it.
+    pub verify_write: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DoctorReport {
+    pub version: String,
+    pub generated_unix_secs: u64,
+    pub connector: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub device: String,
+    pub max_chunk_bytes: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_addr: Option<String>,
+    pub verify_write: bool,
+    pub stages: Vec<DoctorStage>,
+}
+
+impl DoctorReport {
+    /// `true` when no stage failed.
+    pub fn passed(&self) -> bool {
+        self.first_failure().is_none()
+    }
+
+    pub fn first_failure(&self) -> Option<&DoctorStage> {
+        self.stages
+            .iter()
+            .find(|stage| stage.status == StageStatus::Failed)
+    }
+
+    pub fn save_json(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let body = serde_json::to_string_pretty(self)? + "\n";
+        fs::write(path, body)
+            .map_err(|e| anyhow::anyhow!("could not write doctor report {}: {e}", path.display()))
+    }
+}
+
+/// Run every stage in order; `on_stage` sees each result as soon as it is known.
+///
+/// Stages after a failure are reported as skipped. The doctor never writes to the target unless
+/// [`DoctorSettings::verify_write`] is set.
+pub fn run_doctor(settings: &DoctorSettings, on_stage: impl FnMut(&DoctorStage)) -> DoctorReport {
+    let mut run = StageRun {
+        stages: Vec::new(),
+        on_stage,
+    };
+    if let Some(problem) = settings_problem(settings) {
+        run.fail_early(problem);
+    } else {
+        let _ = run_stages(&mut run, settings);
+    }
+    run.skip_remaining();
+
+    DoctorReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        generated_unix_secs: unix_timestamp_secs(),
+        connector: settings.connector.to_string(),
+        device: match settings.connector {
+            Connector::Pcileech => settings.device.trim().to_string(),
+            _ => String::new(),
+        },
+        max_chunk_bytes: settings.max_chunk_bytes,
+        write_addr: settings.write_policy.address.map(ProbeTargets::format_va),
+        verify_write: settings.verify_write,
+        stages: run.stages,
+    }
+}
+
+fn settings_problem(settings: &DoctorSettings) -> Option<anyhow::Error> {
+    if matches!(settings.connector, Connector::Pcileech) && settings.device.trim().is_empty() {
+        return Some(anyhow::anyhow!(
+            "PCILeech requires a non-empty device string"
+        ));
+    }
+    settings.write_policy.validate().err()
+}
+
+/// `None` once a stage failed; the caller then marks the rest as skipped.
+fn run_stages<F: FnMut(&DoctorStage)>(
+    run: &mut StageRun<F>,
+    settings: &DoctorSettings,
+) -> Option<()> {
+    let connector = settings.connector;
+    let os = if matches!(connector, Connector::Native) {
+        run.skip(
+            DoctorStageKind::Inventory,
+            "native connector does not load plugins",
+        );
+        run.skip(
+            DoctorStageKind::Connector,
+            "native connector reads the local host",
+        );
+        run.stage(DoctorStageKind::Os, || {
+            let os = initialization::initialize_native_os()?;
+            let detail = format!("native OS ({})", os.info().arch);
+            Ok((os, detail))
+        })?
+    } else {
+        let mut inventory = run.stage(DoctorStageKind::Inventory, || {
+            let inventory = Inventory::scan();
+            let detail = format!(
+                "connectors: {}; OS plugins: {}",
+                list_or_none(&inventory.available_connectors()),
+                list_or_none(&inventory.available_os())
+            );
+            initialization::ensure_plugins_available(&inventory, connector)?;
+            Ok((inventory, detail))
+        })?;
+        let connector_instance = run.stage(DoctorStageKind::Connector, || {
+            let device = settings.device.trim();
+            let instance = initialization::open_connector(&mut inventory, connector, device)?;
+            let detail = match connector {
+                Connector::Pcileech => format!("{connector} opened with device={device}"),
+                _ => format!("{connector} opened"),
+            };
+            Ok((instance, detail))
+        })?;
+        run.stage(DoctorStageKind::Os, || {
+            let os = initialization::initialize_plugin_os(
+                &mut inventory,
+                connector,
+                connector_instance,
+            )?;
+            let info = os.info();
+            let detail = format!(
+                "{OS_PLUGIN} kernel at {} ({})",
+                ProbeTargets::format_va(info.base),
+                info.arch
+            );
+            Ok((os, detail))
+        })?
+    };
+
+    let mut process = run.stage(DoctorStageKind::Process, || {
+        let process = initialization::find_target_process(os)?;
+        let info = process.info();
+        let detail = format!("{} pid {}", info.name, info.pid);
+        Ok((process, detail))
+    })?;
+    let read_addr = run.stage(DoctorStageKind::Module, || {
+        let module = initialization::find_read_module(&mut process)?;
+        let detail = format!(
+            "{} at {} ({})",
+            module.name,
+            ProbeTargets::format_va(module.base),
+            format_byte_count(usize::try_from(module.size).unwrap_or(usize::MAX))
+        );
+        Ok((module.base, detail))
+    })?;
+    run.stage(DoctorStageKind::MemoryMap, || {
+        let map = write_target::full_memory_map(&mut process);
+        if map.is_empty() {
+            anyhow::bail!("memory map of the target process is empty");
+        }
+        let writable = map
+            .iter()
+            .filter(|range| write_target::is_writable_candidate(range.2))
+            .count();
+        let mapped = map.iter().map(|range| range.1).sum::<umem>();
+        let detail = format!(
+            "{} ranges, {writable} writable, {} mapped",
+            map.len(),
+            format_byte_count(usize::try_from(mapped).unwrap_or(usize::MAX))
+        );
+        Ok(((), detail))
+    })?;
+    run.stage(DoctorStageKind::WriteTarget, || {
+        let policy = &settings.write_policy;
+        let max_chunk_bytes = settings.max_chunk_bytes;
+        let detail = if settings.verify_write {
+            let target =
+                write_target::resolve_safe_write_target(&mut process, read_addr, max_chunk_bytes, policy)?;
+            format!(
+                "{} region at {}; canary of {} verified and original bytes restored",
+                format_byte_count(usize::try_from(target.region_bytes).unwrap_or(usize::MAX)),
+                ProbeTargets::format_va(target.base),
+                format_byte_count(target.verified_bytes)
+            )
+        } else {
+            let region =
+                write_target::find_write_region(&mut process, read_addr, max_chunk_bytes, policy)?;
+            let page_type = write_target::full_memory_map(&mut process)
+                .iter()
+                .find(|range| {
+                    range.0 <= region.base && region.base.to_umem() < range.0.to_umem() + range.1
+                })
+                .map(|range| format_page_type(range.2))
+                .unwrap_or_else(|| "unknown".to_string());
+            format!(
+                "{} region at {} ({page_type}); nothing written (use --verify-write to test writes)",
+                format_byte_count(usize::try_from(region.size).unwrap_or(usize::MAX)),
+                ProbeTargets::format_va(region.base)
+            )
+        };
+        Ok(((), detail))
+    })?;
+    Some(())
+}
+
+fn list_or_none(names: &[String]) -> String {
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+struct StageRun<F: FnMut(&DoctorStage)> {
+    stages: Vec<DoctorStage>,
+    on_stage: F,
+}
+
+impl<F: FnMut(&DoctorStage)> StageRun<F> {
+    /// Time `f`; `None` when it failed.
+    fn stage<T>(
+        &mut self,
+        stage: DoctorStageKind,
+        f: impl FnOnce() -> Result<(T, String)>,
+    ) -> Option<T> {
+        let start = Instant::now();
+        let result = f();
+        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+        match result {
+            Ok((value, detail)) => {
+                self.push(DoctorStage {
+                    stage,
+                    status: StageStatus::Ok,
+                    elapsed_ms,
+                    detail,
+                    error_kind: None,
+                    hints: Vec::new(),
+                });
+                Some(value)
+            }
+            Err(e) => {
+                self.push(failed_stage(stage, elapsed_ms, &e));
+                None
+            }
+        }
+    }
+
+    fn skip(&mut self, stage: DoctorStageKind, reason: &str) {
+        self.push(DoctorStage {
+            stage,
+            status: StageStatus::Skipped,
+            elapsed_ms: 0.0,
+            detail: reason.to_string(),
+            error_kind: None,
+            hints: Vec::new(),
+        });
+    }
+
+    /// Settings rejected before connecting: the first stage carries the error.
+    fn fail_early(&mut self, error: anyhow::Error) {
+        let mut stage = failed_stage(DoctorStageKind::Inventory, 0.0, &error);
+        stage.hints = vec!["Fix the settings above; nothing was opened yet".to_string()];
+        self.push(stage);
+    }
+
+    fn skip_remaining(&mut self) {
+        let Some(failed) = self
+            .stages
+            .iter()
+            .find(|stage| stage.status == StageStatus::Failed)
+            .map(|stage| stage.stage)
+        else {
+            return;
+        };
+        for stage in DoctorStageKind::ALL {
+            if self.stages.iter().all(|done| done.stage != stage) {
+                self.skip(stage, &format!("not run: {} failed", failed.label()));
+            }
+        }
+    }
+
+    fn push(&mut self, stage: DoctorStage) {
+        (self.on_stage)(&stage);
+        self.stages.push(stage);
+    }
+}
+
+fn failed_stage(stage: DoctorStageKind, elapsed_ms: f64, error: &anyhow::Error) -> DoctorStage {
+    let error_kind = BenchErrorKind::of(error);
+    let hints = error_kind.map_or_else(|| stage.hints(), BenchErrorKind::hints);
+    DoctorStage {
+        stage,
+        status: StageStatus::Failed,
+        elapsed_ms,
+        detail: format!("{error:#}"),
+        error_kind,
+        hints: hints.iter().map(|hint| hint.to_string()).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::BenchError;
+
+    fn settings(connector: Connector, device: &str) -> DoctorSettings {
+        DoctorSettings {
+            connector,
+            device: device.to_string(),
+            max_chunk_bytes: 4096,
+            write_policy: WriteTargetPolicy::default(),
+            verify_write: false,
+        }
+    }
+
+    #[test]
+    fn bad_settings_fail_the_first_stage_and_skip_the_rest() {
+        let mut seen = Vec::new();
+        let report = run_doctor(&settings(Connector::Pcileech, " "), |stage| {
+            seen.push((stage.stage, stage.status))
+        });
+
+        assert!(!report.passed());
+        assert_eq!(report.stages.len(), DoctorStageKind::ALL.len());
+        assert_eq!(seen.len(), DoctorStageKind::ALL.len());
+        let failed = report.first_failure().unwrap();
+        assert_eq!(failed.stage, DoctorStageKind::Inventory);
+        assert!(failed.detail.contains("device"), "{}", failed.detail);
+        assert!(!failed.hints.is_empty());
+        assert!(
+            report.stages[1..]
+                .iter()
+                .all(|stage| stage.status == StageStatus::Skipped
+                    && stage.detail == "not run: inventory scan failed")
+        );
+        assert!(report.device.is_empty());
+    }
+
+    #[test]
+    fn failed_stages_take_hints_from_the_error_kind() {
+        let typed = anyhow::Error::new(BenchError::new(
+            BenchErrorKind::ProcessNotFound,
+            "process explorer.exe not found",
+        ));
+        let stage = failed_stage(DoctorStageKind::Os, 12.5, &typed);
+        assert_eq!(stage.error_kind, Some(BenchErrorKind::ProcessNotFound));
+        assert_eq!(
+            stage.hints.len(),
+            BenchErrorKind::ProcessNotFound.hints().len()
+        );
+
+        let untyped = failed_stage(DoctorStageKind::MemoryMap, 1.0, &anyhow::anyhow!("empty"));
+        assert_eq!(untyped.error_kind, None);
+        assert_eq!(untyped.hints[0], DoctorStageKind::MemoryMap.hints()[0]);
+
+        let json = serde_json::to_string(&stage).unwrap();
+        assert!(json.contains("\"stage\":\"os\""), "{json}");
+        assert!(
+            json.contains("\"error_kind\":\"process_not_found\""),
+            "{json}"
+        );
+    }
+
+    #[test]
+    fn reports_round_trip_through_json_files() {
+        let report = run_doctor(&settings(Connector::Pcileech, ""), |_| {});
+        let dir = std::env::temp_dir().join(format!("dma-speedtest-doctor-{}", std::process::id()));
+        let path = dir.join("doctor.json");
+        report.save_json(&path).unwrap();
+        let loaded: DoctorReport =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(loaded.stages, report.stages);
+        assert_eq!(loaded.connector, "pcileech");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,162 @@
+//! Host, software and target fingerprint captured at connect time so archived reports can be told apart.
+
+use super::connect_timings::ConnectTimings;
+use super::connector::Connector;
+use super::probe_targets::ProbeTargets;
+use memflow::prelude::v1::*;
+use serde::{Deserialize, Serialize};
+
+/// Where and against what a run was measured.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunEnvironment {
+    pub hostname: String,
+    pub host_os: String,
+    pub cpu_model: String,
+    pub logical_cpus: usize,
+    pub memflow_version: String,
+    /// Version of a connector linked into this build; `None` for plugins loaded at runtime.
+    pub connector_version: Option<String>,
+    /// PCILeech `device` argument (e.g. `FPGA`); `None` for other connectors.
+    pub device: Option<String>,
+    pub kernel_base: String,
+    pub kernel_bytes: u64,
+    pub kernel_arch: String,
+    pub target_process: String,
+    pub target_pid: u32,
+    pub target_path: String,
+    /// Size of the probed module image; changes with the target build.
+    pub target_module_bytes: u64,
+    /// How long each connection setup phase took; empty in reports from older builds.
+    #[serde(default, skip_serializing_if = "ConnectTimings::is_empty")]
+    pub connect_timings: ConnectTimings,
+}
+
+impl RunEnvironment {
+    pub(super) fn collect(
+        connector: Connector,
+        pcileech_device: &str,
+        os: &OsInfo,
+        process: &ProcessInfo,
+        module: &ModuleInfo,
+    ) -> Self {
+        Self {
+            hostname: hostname(),
+            host_os: host_os(),
+            cpu_model: cpu_model(),
+            logical_cpus: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(0),
+            memflow_version: locked_version(option_env!("DMA_SPEEDTEST_MEMFLOW_VERSION")),
+            connector_version: matches!(connector, Connector::Native)
+                .then(|| locked_version(option_env!("DMA_SPEEDTEST_MEMFLOW_NATIVE_VERSION"))),
+            device: matches!(connector, Connector::Pcileech)
+                .then(|| pcileech_device.trim().to_string()),
+            kernel_base: ProbeTargets::format_va(os.base),
+            kernel_bytes: os.size,
+            kernel_arch: format!("{:?}", os.arch),
+            target_process: process.name.to_string(),
+            target_pid: process.pid,
+            target_path: process.path.to_string(),
+            target_module_bytes: module.size,
+            connect_timings: ConnectTimings::default(),
+        }
+    }
+
+    /// Columns appended to every CSV report row, in [`Self::csv_values`] order.
+    pub(super) const CSV_COLUMNS: [&'static str; 14] = [
+        "hostname",
+        "host_os",
+        "cpu_model",
+        "logical_cpus",
+        "memflow_version",
+        "connector_version",
+        "device",
+        "kernel_base",
+        "kernel_bytes",
+        "kernel_arch",
+        "target_process",
+        "target_pid",
+        "target_path",
+        "target_module_bytes",
+    ];
+
+    pub(super) fn csv_values(&self) -> [String; 14] {
+        [
+            self.hostname.clone(),
+            self.host_os.clone(),
+            self.cpu_model.clone(),
+            self.logical_cpus.to_string(),
+            self.memflow_version.clone(),
+            self.connector_version.clone().unwrap_or_default(),
+            self.device.clone().unwrap_or_default(),
+            self.kernel_base.clone(),
+            self.kernel_bytes.to_string(),
+            self.kernel_arch.clone(),
+            self.target_process.clone(),
+            self.target_pid.to_string(),
+            self.target_path.clone(),
+            self.target_module_bytes.to_string(),
+        ]
+    }
+}
+
+/// Version baked in by `build.rs` from `Cargo.lock`.
+fn locked_version(version: Option<&str>) -> String {
+    version.unwrap_or("unknown").to_string()
+}
+
+fn hostname() -> String {
+    ["COMPUTERNAME", "HOSTNAME"]
+        .into_iter()
+        .find_map(|var| std::env::var(var).ok())
+        .or_else(|| read_trimmed("/proc/sys/kernel/hostname"))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn host_os() -> String {
+    let os = format!("{} {}", std::env::consts::OS, std::env::consts::ARCH);
+    match read_trimmed("/proc/sys/kernel/osrelease") {
+        Some(release) => format!("{os} ({release})"),
+        None => os,
+    }
+}
+
+fn cpu_model() -> String {
+    std::env::var("PROCESSOR_IDENTIFIER")
+        .ok()
+        .or_else(|| {
+            std::fs::read_to_string("/proc/cpuinfo")
+                .ok()
+                .and_then(|cpuinfo| cpu_model_from_cpuinfo(&cpuinfo))
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn cpu_model_from_cpuinfo(cpuinfo: &str) -> Option<String> {
+    cpuinfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "model name").then(|| value.trim().to_string())
+    })
+}
+
+fn read_trimmed(path: &str) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|text| text.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cpu_model_from_cpuinfo() {
+        let cpuinfo = "processor\t: 0\nvendor_id\t: GenuineIntel\nmodel name\t: Intel(R) Core(TM) i7\nflags\t\t: fpu\n";
+        assert_eq!(
+            cpu_model_from_cpuinfo(cpuinfo).as_deref(),
+            Some("Intel(R) Core(TM) i7")
+        );
+        assert_eq!(cpu_model_from_cpuinfo("processor\t: 0\n"), None);
+    }
+}
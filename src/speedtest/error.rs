@@ -0,0 +1,198 @@
+//! Typed connection and write probe failures, so the GUI, CLI and reports can react per category.
+//!
+//! Errors still travel as `anyhow::Error`; recover the category with [`BenchErrorKind::of`].
+
+use super::liveness::TargetLost;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// First exit code used for [`BenchErrorKind`]s; untyped errors exit with `1`.
+const EXIT_CODE_BASE: u8 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BenchErrorKind {
+    /// The connector or OS plugin is not installed where memflow looks for plugins.
+    PluginNotFound,
+    /// The connector loaded but could not open the device or target.
+    DeviceNotConnected,
+    ProcessNotFound,
+    ModuleNotFound,
+    /// No region passes the write target policy.
+    NoWritableRegion,
+    /// The write probe canary did not read back intact.
+    WriteVerificationMismatch,
+    /// The target process exited or the DMA device stopped answering mid-run.
+    TargetLost,
+    /// The target process wrote to or remapped the write probe region, so no further writes go there.
+    WriteTargetCompromised,
+    /// A write chunk is larger than the write probe region resolved at connect.
+    WriteChunkTooLarge,
+}
+
+impl BenchErrorKind {
+    pub const ALL: [BenchErrorKind; 9] = [
+        BenchErrorKind::PluginNotFound,
+        BenchErrorKind::DeviceNotConnected,
+        BenchErrorKind::ProcessNotFound,
+        BenchErrorKind::ModuleNotFound,
+        BenchErrorKind::NoWritableRegion,
+        BenchErrorKind::WriteVerificationMismatch,
+        BenchErrorKind::TargetLost,
+        BenchErrorKind::WriteTargetCompromised,
+        BenchErrorKind::WriteChunkTooLarge,
+    ];
+
+    /// Category of `error`, also through added context; a [`TargetLost`] maps to [`Self::TargetLost`].
+    pub fn of(error: &anyhow::Error) -> Option<Self> {
+        BenchError::from_error(error)
+            .map(|e| e.kind)
+            .or_else(|| TargetLost::from_error(error).map(|_| BenchErrorKind::TargetLost))
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BenchErrorKind::PluginNotFound => "plugin not found",
+            BenchErrorKind::DeviceNotConnected => "device not connected",
+            BenchErrorKind::ProcessNotFound => "process not found",
+            BenchErrorKind::ModuleNotFound => "module not found",
+            BenchErrorKind::NoWritableRegion => "no writable region",
+            BenchErrorKind::WriteVerificationMismatch => "write verification mismatch",
+            BenchErrorKind::TargetLost => "target lost",
+            BenchErrorKind::WriteTargetCompromised => "write target compromised",
+            BenchErrorKind::WriteChunkTooLarge => "write chunk too large",
+        }
+    }
+
+    /// Suggested fixes, most likely first.
+    pub fn hints(self) -> &'static [&'static str] {
+        match self {
+            BenchErrorKind::PluginNotFound => &[
+                "Install the connector and win32 OS plugins with memflowup",
+                "Or place the plugin libraries next to the executable",
+            ],
+            BenchErrorKind::DeviceNotConnected => &[
+                "Ensure the FPGA device is properly connected",
+                "Check if the PCILeech driver is installed (FTDI)",
+                "Run as Administrator",
+            ],
+            BenchErrorKind::ProcessNotFound => &[
+                "Log in to a desktop session on the target so explorer.exe is running",
+                "Check that the OS plugin matches the target's Windows version",
+            ],
+            BenchErrorKind::ModuleNotFound => &[
+                "Wait until the target has finished booting and retry",
+                "Check that the OS plugin matches the target's Windows version",
+            ],
+            BenchErrorKind::NoWritableRegion => &[
+                "Drop the largest chunk size or relax the write target policy",
+                "List the candidates with --list-write-candidates, then pick one with --write-addr",
+            ],
+            BenchErrorKind::WriteVerificationMismatch => &[
+                "The DMA write path does not return written data; check the device supports writes",
+                "Try another region with --write-addr, or benchmark reads only",
+            ],
+            BenchErrorKind::TargetLost => &[
+                "Check that the target is still running and the FPGA device is connected",
+                "Allow reconnects with --reconnect-attempts to resume the remaining passes",
+            ],
+            BenchErrorKind::WriteTargetCompromised => &[
+                "Reconnect so a new write region is selected and verified",
+                "Pick a region the target does not use with --write-addr, or benchmark reads only",
+            ],
+            BenchErrorKind::WriteChunkTooLarge => &[
+                "Drop the write chunk sizes larger than the write probe region",
+                "Reconnect so the write region is sized for the largest write chunk",
+            ],
+        }
+    }
+
+    /// Process exit code for CLI runs that fail with this kind.
+    pub fn exit_code(self) -> u8 {
+        EXIT_CODE_BASE
+            + match self {
+                BenchErrorKind::PluginNotFound => 0,
+                BenchErrorKind::DeviceNotConnected => 1,
+                BenchErrorKind::ProcessNotFound => 2,
+                BenchErrorKind::ModuleNotFound => 3,
+                BenchErrorKind::NoWritableRegion => 4,
+                BenchErrorKind::WriteVerificationMismatch => 5,
+                BenchErrorKind::TargetLost => 6,
+                BenchErrorKind::WriteTargetCompromised => 7,
+                BenchErrorKind::WriteChunkTooLarge => 8,
+            }
+    }
+}
+
+/// Typed benchmark error; recover it with [`Self::from_error`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchError {
+    pub kind: BenchErrorKind,
+    pub detail: String,
+}
+
+impl BenchError {
+    pub fn new(kind: BenchErrorKind, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            detail: detail.into(),
+        }
+    }
+
+    /// The typed error behind `error`, also through added context.
+    pub fn from_error(error: &anyhow::Error) -> Option<&Self> {
+        error.downcast_ref::<Self>()
+    }
+
+    /// [`BenchErrorKind::exit_code`] for typed errors, `1` otherwise.
+    pub fn exit_code_for(error: &anyhow::Error) -> u8 {
+        BenchErrorKind::of(error).map_or(1, BenchErrorKind::exit_code)
+    }
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.detail)
+    }
+}
+
+impl std::error::Error for BenchError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_errors_survive_context_and_pick_exit_codes() {
+        let error = anyhow::Error::new(BenchError::new(
+            BenchErrorKind::ProcessNotFound,
+            "process explorer.exe not found",
+        ))
+        .context("reconnect attempts exhausted");
+        let typed = BenchError::from_error(&error).unwrap();
+        assert_eq!(typed.kind, BenchErrorKind::ProcessNotFound);
+        assert_eq!(typed.to_string(), "process explorer.exe not found");
+        assert_eq!(BenchError::exit_code_for(&error), 12);
+        assert_eq!(BenchError::exit_code_for(&anyhow::anyhow!("other")), 1);
+
+        let lost = anyhow::Error::new(TargetLost {
+            kind: crate::speedtest::TargetLossKind::DeviceLost,
+            detail: "ntdll.dll header unreadable".into(),
+        })
+        .context("pass 3 of 8");
+        assert_eq!(BenchErrorKind::of(&lost), Some(BenchErrorKind::TargetLost));
+        assert_eq!(BenchError::exit_code_for(&lost), 16);
+        assert_eq!(BenchErrorKind::of(&anyhow::anyhow!("other")), None);
+
+        let mut codes = BenchErrorKind::ALL.map(BenchErrorKind::exit_code).to_vec();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), BenchErrorKind::ALL.len());
+        assert!(codes.iter().all(|&code| code >= EXIT_CODE_BASE));
+        assert!(
+            BenchErrorKind::ALL
+                .iter()
+                .all(|kind| !kind.hints().is_empty())
+        );
+    }
+}
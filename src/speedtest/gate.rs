@@ -0,0 +1,366 @@
+//! Pass/fail gating of a finished run against absolute limits and an optional baseline report.
+
+use super::compare::{CompareMetric, CompareThresholds, compare_reports};
+use super::{BenchOp, BenchmarkReport, PassSummary};
+use crate::bench_config::format_chunk_size;
+use serde::{Deserialize, Serialize};
+
+/// A limit for one chunk size, or for every size when `chunk_bytes` is `None`.
+/// Serialized in the `--min-throughput` syntax (`VALUE` or `SIZE_BYTES=VALUE`).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SizeLimit {
+    pub chunk_bytes: Option<usize>,
+    pub value: f64,
+}
+
+impl SizeLimit {
+    /// Parse `VALUE` (all sizes) or `SIZE_BYTES=VALUE`.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let trimmed = input.trim();
+        let (chunk_bytes, value) = match trimmed.split_once('=') {
+            Some((size, value)) => {
+                let size = size
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid chunk size in limit {input:?}"))?;
+                (Some(size), value)
+            }
+            None => (None, trimmed),
+        };
+        let value = value
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| format!("invalid limit value in {input:?}"))?;
+        if !value.is_finite() || value < 0.0 {
+            return Err(format!("limit {input:?} must be a non-negative number"));
+        }
+        Ok(Self { chunk_bytes, value })
+    }
+}
+
+impl TryFrom<String> for SizeLimit {
+    type Error = String;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        Self::parse(&input)
+    }
+}
+
+impl From<SizeLimit> for String {
+    fn from(limit: SizeLimit) -> Self {
+        match limit.chunk_bytes {
+            Some(chunk_bytes) => format!("{chunk_bytes}={}", limit.value),
+            None => limit.value.to_string(),
+        }
+    }
+}
+
+/// The most specific limit for `chunk_bytes`: a size-specific entry wins over a global one.
+fn limit_for(limits: &[SizeLimit], chunk_bytes: usize) -> Option<f64> {
+    limits
+        .iter()
+        .rev()
+        .find(|limit| limit.chunk_bytes == Some(chunk_bytes))
+        .or_else(|| {
+            limits
+                .iter()
+                .rev()
+                .find(|limit| limit.chunk_bytes.is_none())
+        })
+        .map(|limit| limit.value)
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GateCriteria {
+    /// Minimum average MiB/s.
+    pub min_throughput: Vec<SizeLimit>,
+    /// Maximum average latency in μs.
+    pub max_latency: Vec<SizeLimit>,
+    pub baseline: Option<BenchmarkReport>,
+    pub baseline_thresholds: CompareThresholds,
+}
+
+impl GateCriteria {
+    pub fn is_empty(&self) -> bool {
+        self.min_throughput.is_empty() && self.max_latency.is_empty() && self.baseline.is_none()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GateCheckKind {
+    MinThroughput,
+    MaxLatency,
+    BaselineThroughput,
+    BaselineLatency,
+    /// A baseline pass that this run did not produce.
+    BaselineMissing,
+}
+
+impl GateCheckKind {
+    /// Baseline checks measure percent change rather than an absolute value.
+    pub fn is_relative(self) -> bool {
+        matches!(self, Self::BaselineThroughput | Self::BaselineLatency)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GateCheck {
+    pub op: BenchOp,
+    pub chunk_bytes: usize,
+    /// Scenario step index of the checked pass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<usize>,
+    pub kind: GateCheckKind,
+    /// Short description, e.g. `avg MiB/s >= 100` or `μs vs baseline <= +10%`.
+    pub check: String,
+    /// Measured value, or percent change for relative checks.
+    pub actual: f64,
+    pub passed: bool,
+}
+
+impl GateCheck {
+    /// `actual` as shown in verdict tables: percent change for relative checks.
+    pub fn format_actual(&self) -> String {
+        if self.kind == GateCheckKind::BaselineMissing {
+            "missing".to_string()
+        } else if self.kind.is_relative() {
+            format!("{:+.1}%", self.actual)
+        } else {
+            format!("{:.2}", self.actual)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GateVerdict {
+    pub passed: bool,
+    pub checks: Vec<GateCheck>,
+    /// Baseline passes with no counterpart in this run; each also fails a
+    /// [`GateCheckKind::BaselineMissing`] check.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unmatched_baseline_passes: Vec<String>,
+}
+
+impl GateVerdict {
+    /// Checks evaluated for one pass.
+    pub fn checks_for<'a>(&'a self, pass: &'a PassSummary) -> impl Iterator<Item = &'a GateCheck> {
+        self.checks.iter().filter(move |check| {
+            check.op == pass.op && check.chunk_bytes == pass.chunk_bytes && check.step == pass.step
+        })
+    }
+}
+
+/// Check every pass summary against `criteria`. A pass without samples fails its absolute
+/// checks, and a baseline pass missing from the run fails the gate.
+pub fn evaluate_gate(report: &BenchmarkReport, criteria: &GateCriteria) -> GateVerdict {
+    let mut checks = Vec::new();
+
+    for pass in &report.passes {
+        let no_samples = if pass.samples == 0 {
+            " (no samples)"
+        } else {
+            ""
+        };
+        if let Some(min) = limit_for(&criteria.min_throughput, pass.chunk_bytes) {
+            checks.push(absolute_check(
+                pass,
+                GateCheckKind::MinThroughput,
+                format!("avg MiB/s >= {min}{no_samples}"),
+                pass.avg_mib_s,
+                pass.samples > 0 && pass.avg_mib_s >= min,
+            ));
+        }
+        if let Some(max) = limit_for(&criteria.max_latency, pass.chunk_bytes) {
+            checks.push(absolute_check(
+                pass,
+                GateCheckKind::MaxLatency,
+                format!("avg μs <= {max}{no_samples}"),
+                pass.avg_latency_us,
+                pass.samples > 0 && pass.avg_latency_us <= max,
+            ));
+        }
+    }
+
+    let mut unmatched_baseline_passes = Vec::new();
+    if let Some(baseline) = &criteria.baseline {
+        let comparison = compare_reports(baseline, report, criteria.baseline_thresholds);
+        for pass in &comparison.passes {
+            for &(metric, delta) in &pass.metrics {
+                let (kind, check) = match metric {
+                    CompareMetric::Throughput => (
+                        GateCheckKind::BaselineThroughput,
+                        format!(
+                            "MiB/s vs baseline >= -{}%",
+                            criteria.baseline_thresholds.max_throughput_drop_pct
+                        ),
+                    ),
+                    CompareMetric::Latency => (
+                        GateCheckKind::BaselineLatency,
+                        format!(
+                            "μs vs baseline <= +{}%",
+                            criteria.baseline_thresholds.max_latency_rise_pct
+                        ),
+                    ),
+                    CompareMetric::OpsPerSec => continue,
+                };
+                checks.push(GateCheck {
+                    op: pass.op,
+                    chunk_bytes: pass.chunk_bytes,
+                    step: pass.step,
+                    kind,
+                    check,
+                    actual: delta.percent_change().unwrap_or(0.0),
+                    passed: !pass.regressions.contains(&metric),
+                });
+            }
+        }
+        for missing in baseline.passes.iter().filter(|base| {
+            !comparison.passes.iter().any(|pass| {
+                pass.op == base.op && pass.chunk_bytes == base.chunk_bytes && pass.step == base.step
+            })
+        }) {
+            checks.push(absolute_check(
+                missing,
+                GateCheckKind::BaselineMissing,
+                "pass present in run".to_string(),
+                0.0,
+                false,
+            ));
+        }
+        unmatched_baseline_passes = comparison
+            .missing_in_candidate
+            .iter()
+            .map(|(op, chunk_bytes)| format!("{} {}", op.label(), format_chunk_size(*chunk_bytes)))
+            .collect();
+    }
+
+    GateVerdict {
+        passed: checks.iter().all(|check| check.passed),
+        checks,
+        unmatched_baseline_passes,
+    }
+}
+
+fn absolute_check(
+    pass: &PassSummary,
+    kind: GateCheckKind,
+    check: String,
+    actual: f64,
+    passed: bool,
+) -> GateCheck {
+    GateCheck {
+        op: pass.op,
+        chunk_bytes: pass.chunk_bytes,
+        step: pass.step,
+        kind,
+        check,
+        actual,
+        passed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::BenchMode;
+
+    fn pass(chunk_bytes: usize, mib_s: f64, latency_us: f64) -> PassSummary {
+        PassSummary {
+            min_latency_us: latency_us,
+            avg_latency_us: latency_us,
+            max_latency_us: latency_us,
+            ..PassSummary::test_read(chunk_bytes, mib_s)
+        }
+    }
+
+    fn report(passes: Vec<PassSummary>) -> BenchmarkReport {
+        BenchmarkReport::test_report(BenchMode::Read, &[4096, 8192], passes)
+    }
+
+    #[test]
+    fn parses_global_and_size_specific_limits() {
+        assert_eq!(
+            SizeLimit::parse("150.5").unwrap(),
+            SizeLimit {
+                chunk_bytes: None,
+                value: 150.5
+            }
+        );
+        assert_eq!(
+            SizeLimit::parse("4096=80").unwrap(),
+            SizeLimit {
+                chunk_bytes: Some(4096),
+                value: 80.0
+            }
+        );
+        assert!(SizeLimit::parse("4k=80").is_err());
+        assert!(SizeLimit::parse("-1").is_err());
+    }
+
+    #[test]
+    fn size_specific_limit_overrides_global_limit() {
+        let run = report(vec![pass(4096, 90.0, 20.0), pass(8192, 90.0, 20.0)]);
+        let criteria = GateCriteria {
+            min_throughput: vec![
+                SizeLimit::parse("100").unwrap(),
+                SizeLimit::parse("4096=80").unwrap(),
+            ],
+            ..GateCriteria::default()
+        };
+
+        let verdict = evaluate_gate(&run, &criteria);
+
+        assert!(!verdict.passed);
+        assert_eq!(verdict.checks.len(), 2);
+        assert!(verdict.checks[0].passed);
+        assert!(!verdict.checks[1].passed);
+        assert_eq!(verdict.checks[1].check, "avg MiB/s >= 100");
+    }
+
+    #[test]
+    fn baseline_regressions_fail_the_gate() {
+        let run = report(vec![pass(4096, 90.0, 10.0)]);
+        let criteria = GateCriteria {
+            baseline: Some(report(vec![
+                pass(4096, 100.0, 10.0),
+                pass(8192, 100.0, 10.0),
+            ])),
+            ..GateCriteria::default()
+        };
+
+        let verdict = evaluate_gate(&run, &criteria);
+
+        assert!(!verdict.passed);
+        assert_eq!(verdict.checks.len(), 3);
+        assert_eq!(verdict.checks[0].kind, GateCheckKind::BaselineThroughput);
+        assert_eq!(verdict.checks[0].actual, -10.0);
+        assert!(verdict.checks[1].passed);
+        assert_eq!(verdict.checks[2].kind, GateCheckKind::BaselineMissing);
+        assert_eq!(verdict.checks[2].chunk_bytes, 8192);
+        assert!(!verdict.checks[2].passed);
+        assert_eq!(verdict.checks[2].format_actual(), "missing");
+        assert_eq!(verdict.unmatched_baseline_passes, vec!["read 8 KiB"]);
+    }
+
+    #[test]
+    fn passes_without_samples_fail_absolute_checks() {
+        let empty = PassSummary {
+            samples: 0,
+            ..pass(4096, 0.0, 0.0)
+        };
+        let criteria = GateCriteria {
+            max_latency: vec![SizeLimit::parse("50").unwrap()],
+            min_throughput: vec![SizeLimit::parse("0").unwrap()],
+            ..GateCriteria::default()
+        };
+
+        let verdict = evaluate_gate(&report(vec![empty]), &criteria);
+
+        assert!(!verdict.passed);
+        assert_eq!(verdict.checks.len(), 2);
+        assert!(verdict.checks.iter().all(|check| !check.passed));
+        assert_eq!(verdict.checks[1].check, "avg μs <= 50 (no samples)");
+    }
+}
@@ -0,0 +1,229 @@
+//! Opt-in local run history: an append-only JSON-lines file with one finished report per line.
+
+use super::compare::CompareMetric;
+use super::{BenchOp, BenchmarkReport};
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Where `--history` and the GUI record runs unless another file is given.
+pub const DEFAULT_HISTORY_PATH: &str = "reports/history.jsonl";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Sequential id; never reused because the file is only appended to.
+    pub id: u64,
+    pub report: BenchmarkReport,
+}
+
+/// One `(op, chunk size)` metric value from a recorded run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrendPoint {
+    pub run_id: u64,
+    pub generated_unix_secs: u64,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunHistory {
+    path: PathBuf,
+}
+
+impl RunHistory {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All recorded runs in file order; a missing file is an empty history.
+    pub fn load(&self) -> Result<Vec<HistoryEntry>> {
+        let body = match fs::read_to_string(&self.path) {
+            Ok(body) => body,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => bail!("could not read history {}: {e}", self.path.display()),
+        };
+        body.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    anyhow::anyhow!(
+                        "{} line {} is not a history entry: {e}",
+                        self.path.display(),
+                        idx + 1
+                    )
+                })
+            })
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Result<HistoryEntry> {
+        match self.load()?.into_iter().find(|entry| entry.id == id) {
+            Some(entry) => Ok(entry),
+            None => bail!("run {id} is not in {}", self.path.display()),
+        }
+    }
+
+    /// Record a finished run and return its id. Damaged lines are skipped when picking the id,
+    /// so one bad line does not stop recording; [`Self::load`] still reports them.
+    pub fn append(&self, report: &BenchmarkReport) -> Result<u64> {
+        let body = match fs::read_to_string(&self.path) {
+            Ok(body) => body,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => bail!("could not read history {}: {e}", self.path.display()),
+        };
+        let id = body
+            .lines()
+            .filter_map(|line| serde_json::from_str::<EntryId>(line).ok())
+            .map(|entry| entry.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let line = serde_json::to_string(&HistoryEntry {
+            id,
+            report: report.clone(),
+        })?;
+
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| anyhow::anyhow!("could not open history {}: {e}", self.path.display()))?;
+        // A truncated last line must not swallow the new entry.
+        if !body.is_empty() && !body.ends_with('\n') {
+            writeln!(file)?;
+        }
+        writeln!(file, "{line}")?;
+        Ok(id)
+    }
+}
+
+/// Just the id of a history line, for numbering without parsing whole reports.
+#[derive(Deserialize)]
+struct EntryId {
+    id: u64,
+}
+
+/// `metric` for one `(op, chunk_bytes)` pass across runs, oldest first.
+///
+/// Runs without that pass, runs that ended with an error and passes cut short by a target loss
+/// are skipped, so partial figures do not skew the trend.
+pub fn metric_trend(
+    entries: &[HistoryEntry],
+    op: BenchOp,
+    chunk_bytes: usize,
+    metric: CompareMetric,
+) -> Vec<TrendPoint> {
+    let mut points = entries
+        .iter()
+        .filter(|entry| entry.report.error.is_none())
+        .filter_map(|entry| {
+            let pass = entry.report.passes.iter().find(|pass| {
+                pass.op == op && pass.chunk_bytes == chunk_bytes && !pass.interrupted
+            })?;
+            Some(TrendPoint {
+                run_id: entry.id,
+                generated_unix_secs: entry.report.generated_unix_secs,
+                value: metric.value(pass),
+            })
+        })
+        .collect::<Vec<_>>();
+    points.sort_by_key(|point| (point.generated_unix_secs, point.run_id));
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::{BenchMode, PassSummary};
+
+    fn report(avg_mib_s: f64, chunk_bytes: usize) -> BenchmarkReport {
+        BenchmarkReport::test_report(
+            BenchMode::Read,
+            &[chunk_bytes],
+            vec![PassSummary::test_read(chunk_bytes, avg_mib_s)],
+        )
+    }
+
+    #[test]
+    fn appends_runs_and_reports_metric_trend() {
+        let dir =
+            std::env::temp_dir().join(format!("dma-speedtest-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let history = RunHistory::new(dir.join("nested").join("history.jsonl"));
+        assert!(history.load().unwrap().is_empty());
+
+        assert_eq!(history.append(&report(100.0, 4096)).unwrap(), 1);
+        assert_eq!(history.append(&report(50.0, 8192)).unwrap(), 2);
+        assert_eq!(history.append(&report(90.0, 4096)).unwrap(), 3);
+
+        let entries = history.load().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(history.get(2).unwrap().report.passes[0].chunk_bytes, 8192);
+        assert!(history.get(9).is_err());
+
+        let trend = metric_trend(&entries, BenchOp::Read, 4096, CompareMetric::Throughput);
+        assert_eq!(
+            trend
+                .iter()
+                .map(|point| (point.run_id, point.value))
+                .collect::<Vec<_>>(),
+            vec![(1, 100.0), (3, 90.0)]
+        );
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(history.path())
+            .unwrap();
+        write!(file, "{{\"id\":7,\"report\":").unwrap();
+        drop(file);
+        assert_eq!(history.append(&report(80.0, 4096)).unwrap(), 4);
+        let body = fs::read_to_string(history.path()).unwrap();
+        assert!(body.lines().last().unwrap().starts_with("{\"id\":4,"));
+        let err = history.load().unwrap_err().to_string();
+        assert!(err.contains("line 4 is not a history entry"));
+
+        fs::write(history.path(), "not json\n").unwrap();
+        let err = history.load().unwrap_err().to_string();
+        assert!(err.contains("line 1 is not a history entry"));
+        assert_eq!(history.append(&report(80.0, 4096)).unwrap(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn trends_skip_failed_runs_and_interrupted_passes() {
+        let mut interrupted = report(40.0, 4096);
+        interrupted.passes[0].interrupted = true;
+        let entries = [
+            report(100.0, 4096),
+            report(10.0, 4096).with_error(&anyhow::anyhow!("device lost")),
+            interrupted,
+            report(90.0, 4096),
+        ]
+        .into_iter()
+        .zip(1..)
+        .map(|(report, id)| HistoryEntry { id, report })
+        .collect::<Vec<_>>();
+
+        let trend = metric_trend(&entries, BenchOp::Read, 4096, CompareMetric::Throughput);
+        assert_eq!(
+            trend
+                .iter()
+                .map(|point| (point.run_id, point.value))
+                .collect::<Vec<_>>(),
+            vec![(1, 100.0), (4, 90.0)]
+        );
+    }
+}
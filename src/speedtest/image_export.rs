@@ -0,0 +1,214 @@
+//! PNG and SVG export of GUI plots and result tables; PNGs are rasterized from the same SVG.
+
+use super::report::{escape_xml, unix_timestamp_millis};
+use super::svg_chart::{ChartSeries, LineChart};
+use anyhow::Result;
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Default export width in pixels; height follows the image's aspect ratio.
+pub const DEFAULT_IMAGE_WIDTH: u32 = 1280;
+/// Smallest and largest accepted export widths.
+pub const IMAGE_WIDTH_RANGE: std::ops::RangeInclusive<u32> = 320..=7680;
+
+const TABLE_WIDTH: f64 = 560.0;
+const TABLE_ROW_HEIGHT: f64 = 22.0;
+const TABLE_TITLE_HEIGHT: f64 = 32.0;
+const TABLE_PADDING: f64 = 12.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+        }
+    }
+}
+
+/// One plotted line: `(x, y)` points in plot units and an `[r, g, b]` color.
+pub struct ImageSeries {
+    pub label: String,
+    pub rgb: [u8; 3],
+    pub points: Vec<(f64, f64)>,
+    /// Drawn dashed and faded, like GUI reference overlays.
+    pub dashed: bool,
+}
+
+/// Line chart of `series` over elapsed seconds.
+pub fn plot_svg(title: &str, y_label: &str, series: Vec<ImageSeries>, width_px: u32) -> String {
+    LineChart {
+        title,
+        x_label: "Elapsed (s)",
+        y_label,
+        x_ticks: Vec::new(),
+        series: series
+            .into_iter()
+            .map(|series| ChartSeries {
+                label: series.label,
+                color: hex_color(series.rgb),
+                points: series.points,
+                dashed: series.dashed,
+            })
+            .collect(),
+    }
+    .render_sized(width_px)
+}
+
+/// Striped table with a bold header row; every row should have `headers.len()` cells.
+pub fn table_svg(title: &str, headers: &[&str], rows: &[Vec<String>], width_px: u32) -> String {
+    let height = TABLE_TITLE_HEIGHT + TABLE_ROW_HEIGHT * (rows.len() + 1) as f64 + TABLE_PADDING;
+    let height_px = (f64::from(width_px) * height / TABLE_WIDTH).round();
+    let column_width = (TABLE_WIDTH - 2.0 * TABLE_PADDING) / headers.len().max(1) as f64;
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {TABLE_WIDTH} {height}" width="{width_px}" height="{height_px}" font-family="sans-serif" font-size="12"><rect width="{TABLE_WIDTH}" height="{height}" fill="#ffffff"/><text x="{TABLE_PADDING}" y="22" font-size="14" font-weight="bold">{}</text>"##,
+        escape_xml(title)
+    );
+
+    let header = headers
+        .iter()
+        .map(|cell| cell.to_string())
+        .collect::<Vec<_>>();
+    for (idx, cells) in std::iter::once(&header).chain(rows).enumerate() {
+        let top = TABLE_TITLE_HEIGHT + TABLE_ROW_HEIGHT * idx as f64;
+        let fill = match idx {
+            0 => "#ecf0f1",
+            _ if idx % 2 == 0 => "#f7f9f9",
+            _ => "#ffffff",
+        };
+        let _ = write!(
+            svg,
+            r##"<rect x="{TABLE_PADDING}" y="{top}" width="{}" height="{TABLE_ROW_HEIGHT}" fill="{fill}"/>"##,
+            TABLE_WIDTH - 2.0 * TABLE_PADDING
+        );
+        let weight = if idx == 0 {
+            r#" font-weight="bold""#
+        } else {
+            ""
+        };
+        for (column, cell) in cells.iter().enumerate() {
+            let _ = write!(
+                svg,
+                r##"<text x="{:.1}" y="{:.1}" fill="#2c3e50"{weight}>{}</text>"##,
+                TABLE_PADDING + 6.0 + column_width * column as f64,
+                top + 15.0,
+                escape_xml(cell)
+            );
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// `reports/dma-speedtest-<name>-<unix millis>.<ext>`.
+pub fn default_image_path(name: &str, format: ImageFormat) -> PathBuf {
+    PathBuf::from("reports").join(format!(
+        "dma-speedtest-{name}-{}.{}",
+        unix_timestamp_millis(),
+        format.extension()
+    ))
+}
+
+/// Write `svg` as-is, or rasterize it at its own pixel size for PNG.
+pub fn write_image(svg: &str, format: ImageFormat, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    let bytes = match format {
+        ImageFormat::Svg => svg.as_bytes().to_vec(),
+        ImageFormat::Png => rasterize_png(svg)?,
+    };
+    fs::write(path, bytes)
+        .map_err(|e| anyhow::anyhow!("could not write image {}: {e}", path.display()))
+}
+
+fn rasterize_png(svg: &str) -> Result<Vec<u8>> {
+    let mut options = resvg::usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = resvg::usvg::Tree::from_str(svg, &options)
+        .map_err(|e| anyhow::anyhow!("could not parse chart SVG: {e}"))?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| anyhow::anyhow!("image size {}x{} is empty", size.width(), size.height()))?;
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::default(),
+        &mut pixmap.as_mut(),
+    );
+    pixmap
+        .encode_png()
+        .map_err(|e| anyhow::anyhow!("could not encode PNG: {e}"))
+}
+
+fn hex_color([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_plot_and_table_at_requested_width_and_rasterizes_png() {
+        let svg = plot_svg(
+            "Throughput (MiB/s)",
+            "MiB/s",
+            vec![
+                ImageSeries {
+                    label: "read 4 KiB".to_string(),
+                    rgb: [76, 175, 80],
+                    points: vec![(0.1, 10.0), (0.2, 12.0)],
+                    dashed: false,
+                },
+                ImageSeries {
+                    label: "read 4 KiB (reference)".to_string(),
+                    rgb: [76, 175, 80],
+                    points: vec![(0.0, 11.0), (1.0, 11.0)],
+                    dashed: true,
+                },
+            ],
+            1280,
+        );
+        assert!(svg.contains(r#"width="1280" height="600""#));
+        assert!(svg.contains(r##"stroke="#4caf50""##));
+        assert_eq!(svg.matches("stroke-dasharray").count(), 1);
+
+        let table = table_svg(
+            "Results (MiB/s)",
+            &["Op", "Size", "Min", "Avg", "Max"],
+            &[vec![
+                "read".to_string(),
+                "4 KiB".to_string(),
+                "10.0".to_string(),
+                "11.0".to_string(),
+                "<12.0>".to_string(),
+            ]],
+            560,
+        );
+        assert!(table.contains(r#"width="560" height="88""#));
+        assert!(table.contains(">&lt;12.0&gt;<"));
+
+        let dir = std::env::temp_dir().join(format!("dma-speedtest-image-{}", std::process::id()));
+        let path = dir.join("plot.png");
+        write_image(&svg, ImageFormat::Png, &path).unwrap();
+        let png = fs::read(&path).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        // IHDR width and height, big-endian.
+        assert_eq!(&png[16..24], &[0, 0, 5, 0, 0, 0, 2, 88]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
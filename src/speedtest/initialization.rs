@@ -1,10 +1,17 @@
 use super::bench::BenchMode;
+use super::connect_timings::{ConnectStep, ConnectTimings};
 use super::connector::Connector;
+use super::environment::RunEnvironment;
+use super::error::{BenchError, BenchErrorKind};
+use super::observer::{BenchObserver, ConnectPhase};
 use super::probe_targets::{TARGET_PROCESS, TARGET_READ_MODULE};
-use super::write_target::{self, MIN_WRITE_REGION_BYTES};
+use super::write_target::{self, WriteCandidate, WriteTargetPolicy};
 use anyhow::Result;
 use memflow::{plugins::Inventory, prelude::v1::*};
 
+/// memflow OS plugin used with every plugin-based connector.
+pub(super) const OS_PLUGIN: &str = "win32";
+
 pub struct SpeedTestInit {
     pub process: IntoProcessInstanceArcBox<'static>,
     pub read_addr: Address,
@@ -12,6 +19,7 @@ pub struct SpeedTestInit {
     pub write_region_bytes: Option<umem>,
     pub write_verified_bytes: Option<usize>,
     pub write_restore_bytes: Option<Vec<u8>>,
+    pub environment: RunEnvironment,
 }
 
 pub(super) fn initialize_speedtest(
@@ -19,26 +27,72 @@ pub(super) fn initialize_speedtest(
     pcileech_device: String,
     mode: BenchMode,
     max_chunk_bytes: usize,
+    write_policy: &WriteTargetPolicy,
+    observer: &dyn BenchObserver,
 ) -> Result<SpeedTestInit> {
-    let os = initialize_os(connector, &pcileech_device)?;
-    let mut process = find_target_process(os)?;
-    let read_addr = find_module_address(&mut process)?;
+    let mut timings = ConnectTimings::default();
+    let mut init = connect_target(
+        connector,
+        pcileech_device,
+        mode,
+        max_chunk_bytes,
+        write_policy,
+        observer,
+        &mut timings,
+    )
+    .inspect_err(|_| observer.connect_failed(&timings))?;
+    init.environment.connect_timings = timings;
+    Ok(init)
+}
 
-    let min_write_bytes = MIN_WRITE_REGION_BYTES.max(max_chunk_bytes);
+fn connect_target(
+    connector: Connector,
+    pcileech_device: String,
+    mode: BenchMode,
+    max_chunk_bytes: usize,
+    write_policy: &WriteTargetPolicy,
+    observer: &dyn BenchObserver,
+    timings: &mut ConnectTimings,
+) -> Result<SpeedTestInit> {
+    observer.connect_phase(ConnectPhase::Connector);
+    let os = initialize_os(connector, &pcileech_device, timings)?;
+    let os_info = os.info().clone();
+    observer.connect_phase(ConnectPhase::Process);
+    let mut process = timings.time(ConnectStep::ProcessLookup, || find_target_process(os))?;
+    observer.connect_phase(ConnectPhase::Module);
+    let read_module = timings.time(ConnectStep::ModuleLookup, || find_read_module(&mut process))?;
+    let read_addr = read_module.base;
+    let environment = RunEnvironment::collect(
+        connector,
+        &pcileech_device,
+        &os_info,
+        process.info(),
+        &read_module,
+    );
 
-    let (write_addr, write_region_bytes, write_verified_bytes, write_restore_bytes) =
-        if mode.needs_write_target() {
-            let target =
-                write_target::resolve_safe_write_target(&mut process, read_addr, min_write_bytes)?;
-            (
-                Some(target.base),
-                Some(target.region_bytes),
-                Some(target.verified_bytes),
-                Some(target.restore_bytes),
+    let (write_addr, write_region_bytes, write_verified_bytes, write_restore_bytes) = if mode
+        .needs_write_target()
+    {
+        observer.connect_phase(ConnectPhase::WriteTarget);
+        let region = timings.time(ConnectStep::WriteRegionSearch, || {
+            write_target::find_write_region(&mut process, read_addr, max_chunk_bytes, write_policy)
+        })?;
+        let target = timings.time(ConnectStep::CanaryVerification, || {
+            write_target::verify_write_region(
+                &mut process,
+                region,
+                write_policy.effective_min_bytes(max_chunk_bytes),
             )
-        } else {
-            (None, None, None, None)
-        };
+        })?;
+        (
+            Some(target.base),
+            Some(target.region_bytes),
+            Some(target.verified_bytes),
+            Some(target.restore_bytes),
+        )
+    } else {
+        (None, None, None, None)
+    };
 
     Ok(SpeedTestInit {
         process,
@@ -47,68 +101,137 @@ pub(super) fn initialize_speedtest(
         write_region_bytes,
         write_verified_bytes,
         write_restore_bytes,
+        environment,
     })
 }
 
-fn initialize_os(connector: Connector, pcileech_device: &str) -> Result<OsInstanceArcBox<'static>> {
-    let mut inventory = Inventory::scan();
+/// Connect and list write candidates without writing to the target (dry run).
+pub(super) fn preview_write_candidates(
+    connector: Connector,
+    pcileech_device: &str,
+    max_chunk_bytes: usize,
+    write_policy: &WriteTargetPolicy,
+) -> Result<Vec<WriteCandidate>> {
+    write_policy.validate()?;
+    let os = initialize_os(connector, pcileech_device, &mut ConnectTimings::default())?;
+    let mut process = find_target_process(os)?;
+    let read_addr = find_read_module(&mut process)?.base;
+    write_target::preview_write_candidates(&mut process, read_addr, max_chunk_bytes, write_policy)
+}
 
-    match connector {
-        Connector::Pcileech => initialize_pcileech(&mut inventory, pcileech_device),
-        Connector::Native => Ok(memflow_native::create_os(
-            &Default::default(), // os_cfg
-            Default::default(),  // process_cfg
-        )?),
-        // Not tested
-        Connector::Kvm | Connector::Qemu => initialize_vm_connector(&mut inventory, &connector),
+fn initialize_os(
+    connector: Connector,
+    pcileech_device: &str,
+    timings: &mut ConnectTimings,
+) -> Result<OsInstanceArcBox<'static>> {
+    if matches!(connector, Connector::Native) {
+        return timings.time(ConnectStep::OsInit, initialize_native_os);
     }
+    let mut inventory = timings.time(ConnectStep::InventoryScan, Inventory::scan);
+    ensure_plugins_available(&inventory, connector)?;
+    let connector_instance = timings.time(ConnectStep::ConnectorBuild, || {
+        open_connector(&mut inventory, connector, pcileech_device)
+    })?;
+    timings.time(ConnectStep::OsInit, || {
+        initialize_plugin_os(&mut inventory, connector, connector_instance)
+    })
 }
 
-fn initialize_pcileech(
-    inventory: &mut Inventory,
-    device: &str,
-) -> Result<OsInstanceArcBox<'static>> {
-    let args = Args::new().insert("device", device);
+pub(super) fn initialize_native_os() -> Result<OsInstanceArcBox<'static>> {
+    memflow_native::create_os(
+        &Default::default(), // os_cfg
+        Default::default(),  // process_cfg
+    )
+    .map_err(|e| {
+        BenchError::new(
+            BenchErrorKind::DeviceNotConnected,
+            format!("native OS init failed: {e}"),
+        )
+        .into()
+    })
+}
 
+/// Fail with [`BenchErrorKind::PluginNotFound`] before building when a plugin is missing.
+pub(super) fn ensure_plugins_available(inventory: &Inventory, connector: Connector) -> Result<()> {
+    let name = connector.to_string();
+    if !inventory.available_connectors().contains(&name) {
+        return Err(BenchError::new(
+            BenchErrorKind::PluginNotFound,
+            format!("connector plugin `{name}` not found in the memflow plugin paths"),
+        )
+        .into());
+    }
+    if !inventory.available_os().iter().any(|os| os == OS_PLUGIN) {
+        return Err(BenchError::new(
+            BenchErrorKind::PluginNotFound,
+            format!("OS plugin `{OS_PLUGIN}` not found in the memflow plugin paths"),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Open a plugin connector (everything but [`Connector::Native`]).
+pub(super) fn open_connector(
+    inventory: &mut Inventory,
+    connector: Connector,
+    pcileech_device: &str,
+) -> Result<ConnectorInstanceArcBox<'static>> {
+    let args = match connector {
+        Connector::Pcileech => Args::new().insert("device", pcileech_device),
+        // Not tested
+        _ => Args::new()
+            .insert("retries", "1")
+            .insert("retry_interval", "0"),
+    };
     let connector_args = ConnectorArgs::new(None, args, None);
 
     inventory
-        .builder()
-        .connector("pcileech")
-        .args(connector_args)
-        .os("win32")
-        .build()
-        .map_err(|e| anyhow::anyhow!(
-            "PCILeech connector error: {e}\n\nCommon fixes:\n  1. Ensure FPGA device is properly connected\n  2. Check if PCILeech driver is installed (FTDI)\n  3. Run as Administrator"
-        ))
+        .instantiate_connector(&connector.to_string(), None, Some(&connector_args))
+        .map_err(|e| {
+            let detail = match connector {
+                Connector::Pcileech => format!("PCILeech connector error: {e}"),
+                _ => format!("{connector} connector error: {e}"),
+            };
+            BenchError::new(BenchErrorKind::DeviceNotConnected, detail).into()
+        })
+}
+
+/// Start the Windows OS plugin on an open connector.
+pub(super) fn initialize_plugin_os(
+    inventory: &mut Inventory,
+    connector: Connector,
+    connector_instance: ConnectorInstanceArcBox<'static>,
+) -> Result<OsInstanceArcBox<'static>> {
+    inventory
+        .instantiate_os(OS_PLUGIN, Some(connector_instance), None)
+        .map_err(|e| {
+            BenchError::new(
+                BenchErrorKind::DeviceNotConnected,
+                format!("{OS_PLUGIN} OS plugin init over {connector} failed: {e}"),
+            )
+            .into()
+        })
 }
 
-fn find_target_process(
+pub(super) fn find_target_process(
     os: OsInstanceArcBox<'static>,
 ) -> Result<IntoProcessInstanceArcBox<'static>> {
-    let process = os.into_process_by_name(TARGET_PROCESS)?;
+    let process = os.into_process_by_name(TARGET_PROCESS).map_err(|e| {
+        BenchError::new(
+            BenchErrorKind::ProcessNotFound,
+            format!("process {TARGET_PROCESS} not found: {e}"),
+        )
+    })?;
     Ok(process)
 }
 
-fn find_module_address(process: &mut IntoProcessInstanceArcBox<'_>) -> Result<Address> {
-    let addr = process.module_by_name(TARGET_READ_MODULE)?.base;
-    Ok(addr)
-}
-
-// Not tested
-fn initialize_vm_connector(
-    inventory: &mut Inventory,
-    connector: &Connector,
-) -> Result<OsInstanceArcBox<'static>> {
-    let args = Args::new()
-        .insert("retries", "1")
-        .insert("retry_interval", "0");
-    let connector_args = ConnectorArgs::new(None, args, None);
-
-    Ok(inventory
-        .builder()
-        .connector(&connector.to_string())
-        .args(connector_args)
-        .os("win32")
-        .build()?)
+pub(super) fn find_read_module(process: &mut IntoProcessInstanceArcBox<'_>) -> Result<ModuleInfo> {
+    let module = process.module_by_name(TARGET_READ_MODULE).map_err(|e| {
+        BenchError::new(
+            BenchErrorKind::ModuleNotFound,
+            format!("module {TARGET_READ_MODULE} not found in {TARGET_PROCESS}: {e}"),
+        )
+    })?;
+    Ok(module)
 }
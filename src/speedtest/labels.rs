@@ -0,0 +1,84 @@
+//! `key=value` run labels attached to reports, report file names, comparisons and history.
+
+use std::collections::BTreeMap;
+
+pub type RunLabels = BTreeMap<String, String>;
+
+/// Longest label fragment used in a default report file name.
+const MAX_FILE_NAME_SLUG: usize = 64;
+
+/// Parse one `KEY=VALUE` label; keys are trimmed and must be non-empty.
+pub fn parse_label(input: &str) -> Result<(String, String), String> {
+    let Some((key, value)) = input.split_once('=') else {
+        return Err(format!("label {input:?} must be KEY=VALUE"));
+    };
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(format!("label {input:?} has an empty key"));
+    }
+    Ok((key.to_string(), value.trim().to_string()))
+}
+
+/// Comma-separated `KEY=VALUE` list (GUI input); blank entries are skipped.
+pub fn parse_labels_csv(input: &str) -> Result<RunLabels, String> {
+    input
+        .split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(parse_label)
+        .collect()
+}
+
+/// `key=value, key=value` for tables and console output.
+pub fn format_labels(labels: &RunLabels) -> String {
+    labels
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// File-name-safe `key-value_key-value` fragment; empty when there are no labels.
+pub(super) fn labels_file_slug(labels: &RunLabels) -> String {
+    let slug = labels
+        .iter()
+        .map(|(key, value)| {
+            if value.is_empty() {
+                key.clone()
+            } else {
+                format!("{key}-{value}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("_")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_FILE_NAME_SLUG)
+        .collect::<String>();
+    slug.trim_matches(['_', '.']).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_labels() {
+        assert_eq!(
+            parse_label(" card = A1 ").unwrap(),
+            ("card".to_string(), "A1".to_string())
+        );
+        assert!(parse_label("card").is_err());
+        assert!(parse_label("=A1").is_err());
+
+        let labels = parse_labels_csv("slot=2, card=A1 rev/b,").unwrap();
+        assert_eq!(format_labels(&labels), "card=A1 rev/b, slot=2");
+        assert_eq!(labels_file_slug(&labels), "card-A1_rev_b_slot-2");
+        assert_eq!(labels_file_slug(&RunLabels::new()), "");
+    }
+}
@@ -0,0 +1,164 @@
+//! Tell transient DMA failures apart from the target process exiting or the device going away.
+//!
+//! Individual ops already retry with backoff. Only a sustained streak of skipped ops triggers a
+//! liveness probe: the process state and a read of the probe module header.
+
+use super::mem_io::{self, IoAttempt};
+use super::probe_targets::{ProbeTargets, TARGET_PROCESS, TARGET_READ_MODULE};
+use memflow::prelude::v1::*;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Consecutive skipped ops before the worker probes liveness.
+pub const LOSS_STREAK_OPS: u32 = 16;
+
+/// Minimum duration of a failure streak before the worker probes liveness.
+pub const LOSS_STREAK_MIN_DURATION: Duration = Duration::from_millis(500);
+
+/// Why the benchmark target became unreachable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetLossKind {
+    /// The target process exited (or was restarted under a new PID).
+    ProcessExited,
+    /// The DMA device stopped answering reads of known-good memory.
+    DeviceLost,
+}
+
+impl TargetLossKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ProcessExited => "target process exited",
+            Self::DeviceLost => "DMA device lost",
+        }
+    }
+}
+
+/// Typed error for a lost target; recover it with `error.downcast_ref::<TargetLost>()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TargetLost {
+    pub kind: TargetLossKind,
+    pub detail: String,
+}
+
+impl TargetLost {
+    pub fn from_error(error: &anyhow::Error) -> Option<&Self> {
+        error.downcast_ref::<Self>()
+    }
+}
+
+impl fmt::Display for TargetLost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.kind.label(), self.detail)
+    }
+}
+
+impl std::error::Error for TargetLost {}
+
+/// Tracks consecutive skipped ops within one pass.
+#[derive(Debug, Default)]
+pub struct FailureStreak {
+    count: u32,
+    since: Option<Instant>,
+}
+
+impl FailureStreak {
+    /// Record a skipped op; `true` when the streak is long enough to probe liveness.
+    pub fn record_failure(&mut self) -> bool {
+        self.record_failure_at(Instant::now())
+    }
+
+    fn record_failure_at(&mut self, now: Instant) -> bool {
+        self.count += 1;
+        let since = *self.since.get_or_insert(now);
+        self.count >= LOSS_STREAK_OPS && now.duration_since(since) >= LOSS_STREAK_MIN_DURATION
+    }
+
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.since = None;
+    }
+}
+
+/// Probe whether the target is still reachable after a failure streak.
+pub fn probe_target(
+    process: &mut IntoProcessInstanceArcBox<'_>,
+    read_addr: Address,
+) -> std::result::Result<(), TargetLost> {
+    let state = process.state();
+    let mut header = [0u8; 2];
+    let header_readable =
+        mem_io::read_raw_into_with_retry(process, read_addr, &mut header) == IoAttempt::Ok;
+
+    match classify_loss(&state, header_readable) {
+        None => Ok(()),
+        Some(TargetLossKind::ProcessExited) => Err(TargetLost {
+            kind: TargetLossKind::ProcessExited,
+            detail: match state {
+                ProcessState::Dead(code) => format!("{TARGET_PROCESS} exit code {code}"),
+                _ => format!("{TARGET_PROCESS} is no longer running"),
+            },
+        }),
+        Some(TargetLossKind::DeviceLost) => Err(TargetLost {
+            kind: TargetLossKind::DeviceLost,
+            detail: format!(
+                "{TARGET_READ_MODULE} header at {} unreadable while process state is {state:?}",
+                ProbeTargets::format_va(read_addr)
+            ),
+        }),
+    }
+}
+
+/// `None` means the failures look transient and the pass should continue.
+fn classify_loss(state: &ProcessState, header_readable: bool) -> Option<TargetLossKind> {
+    match state {
+        ProcessState::Dead(_) => Some(TargetLossKind::ProcessExited),
+        _ if header_readable => None,
+        _ => Some(TargetLossKind::DeviceLost),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streak_needs_both_count_and_duration() {
+        let start = Instant::now();
+        let mut streak = FailureStreak::default();
+        for _ in 0..LOSS_STREAK_OPS - 1 {
+            assert!(!streak.record_failure_at(start));
+        }
+        assert!(!streak.record_failure_at(start));
+        assert!(streak.record_failure_at(start + LOSS_STREAK_MIN_DURATION));
+
+        streak.reset();
+        assert!(!streak.record_failure_at(start + LOSS_STREAK_MIN_DURATION * 4));
+    }
+
+    #[test]
+    fn classify_prefers_process_exit_over_readable_header() {
+        assert_eq!(
+            classify_loss(&ProcessState::Dead(0), true),
+            Some(TargetLossKind::ProcessExited)
+        );
+        assert_eq!(classify_loss(&ProcessState::Alive, true), None);
+        assert_eq!(
+            classify_loss(&ProcessState::Unknown, false),
+            Some(TargetLossKind::DeviceLost)
+        );
+    }
+
+    #[test]
+    fn target_lost_survives_anyhow_round_trip() {
+        let error = anyhow::Error::from(TargetLost {
+            kind: TargetLossKind::DeviceLost,
+            detail: "test".to_string(),
+        });
+
+        let lost = TargetLost::from_error(&error).expect("downcast");
+        assert_eq!(lost.kind, TargetLossKind::DeviceLost);
+        assert_eq!(error.to_string(), "DMA device lost (test)");
+    }
+}
@@ -0,0 +1,497 @@
+//! Prometheus text exposition of live and per-pass benchmark metrics.
+//!
+//! [`BenchMetrics`] is fed from the samples a [`BenchObserver`](super::BenchObserver) receives,
+//! and from each finished [`PassSummary`]. It can be scraped over HTTP (see
+//! [`MetricsServer`](super::MetricsServer)) or mirrored to a node-exporter textfile.
+//!
+//! The update calls return the first textfile write failure so the caller can warn once; later
+//! failures are dropped until a write succeeds again.
+
+use super::{BenchMode, BenchOp, BenchSample, Connector, PassSummary};
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use std::{
+    fmt::Write as _,
+    fs,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Content type for the Prometheus text exposition format.
+pub const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Minimum spacing between textfile rewrites while a pass is running.
+const TEXTFILE_INTERVAL: Duration = Duration::from_secs(1);
+
+type PassValue = fn(&PassSummary) -> f64;
+
+/// Shared metrics state; clones update the same registry.
+#[derive(Clone)]
+pub struct BenchMetrics {
+    state: Arc<Mutex<MetricsState>>,
+}
+
+struct MetricsState {
+    connector: String,
+    mode: &'static str,
+    running: bool,
+    live: Option<LivePass>,
+    passes: Vec<PassSummary>,
+    textfile: Option<Textfile>,
+}
+
+struct LivePass {
+    op: BenchOp,
+    chunk_bytes: usize,
+    step: Option<usize>,
+    latest: BenchSample,
+    ops: u64,
+    skipped_ops: u64,
+}
+
+struct Textfile {
+    path: PathBuf,
+    last_write: Option<Instant>,
+    warned: bool,
+}
+
+impl BenchMetrics {
+    pub fn new(connector: Connector, mode: BenchMode) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MetricsState {
+                connector: connector.to_string(),
+                mode: mode.label(),
+                running: true,
+                live: None,
+                passes: Vec::new(),
+                textfile: None,
+            })),
+        }
+    }
+
+    /// Also write the exposition to `path` (node-exporter textfile collector; use a `.prom` name).
+    pub fn with_textfile(self, path: PathBuf) -> Self {
+        self.state.lock().textfile = Some(Textfile {
+            path,
+            last_write: None,
+            warned: false,
+        });
+        self
+    }
+
+    /// Update the live gauges from one interval sample.
+    pub fn record_sample(&self, sample: &BenchSample) -> Result<()> {
+        let mut state = self.state.lock();
+        match &mut state.live {
+            Some(live)
+                if live.op == sample.op
+                    && live.chunk_bytes == sample.chunk_bytes
+                    && live.step == sample.step =>
+            {
+                live.latest = *sample;
+                live.ops = live.ops.saturating_add(sample.ops);
+                live.skipped_ops = live.skipped_ops.saturating_add(sample.skipped_ops);
+            }
+            live => {
+                *live = Some(LivePass {
+                    op: sample.op,
+                    chunk_bytes: sample.chunk_bytes,
+                    step: sample.step,
+                    latest: *sample,
+                    ops: sample.ops,
+                    skipped_ops: sample.skipped_ops,
+                });
+            }
+        }
+        state.write_textfile(false)
+    }
+
+    /// Publish a finished pass and clear the live gauges.
+    pub fn finish_pass(&self, summary: &PassSummary) -> Result<()> {
+        let mut state = self.state.lock();
+        state.live = None;
+        state.passes.retain(|pass| {
+            pass.op != summary.op
+                || pass.chunk_bytes != summary.chunk_bytes
+                || pass.step != summary.step
+        });
+        state.passes.push(summary.clone());
+        state.write_textfile(true)
+    }
+
+    /// Mark the run as finished; per-pass gauges stay available.
+    pub fn finish_run(&self) -> Result<()> {
+        let mut state = self.state.lock();
+        state.running = false;
+        state.live = None;
+        state.write_textfile(true)
+    }
+
+    pub fn render(&self) -> String {
+        self.state.lock().render()
+    }
+}
+
+impl MetricsState {
+    fn render(&self) -> String {
+        let mut out = String::new();
+        family(
+            &mut out,
+            "dma_speedtest_info",
+            "Benchmark run metadata.",
+            "gauge",
+            [(
+                labels(&[
+                    ("version", env!("CARGO_PKG_VERSION")),
+                    ("connector", &self.connector),
+                    ("mode", self.mode),
+                ]),
+                1.0,
+            )],
+        );
+        family(
+            &mut out,
+            "dma_speedtest_running",
+            "1 while a benchmark run is in progress.",
+            "gauge",
+            [(String::new(), f64::from(u8::from(self.running)))],
+        );
+        family(
+            &mut out,
+            "dma_speedtest_passes_completed",
+            "Passes finished so far.",
+            "gauge",
+            [(String::new(), self.passes.len() as f64)],
+        );
+
+        if let Some(live) = &self.live {
+            let key = pass_labels(live.op, live.chunk_bytes, live.step, None);
+            let sample = &live.latest;
+            for (name, help, kind, value) in [
+                (
+                    "dma_speedtest_current_throughput_mib_s",
+                    "Throughput of the latest interval of the active pass, in MiB/s.",
+                    "gauge",
+                    sample.throughput_mib_s,
+                ),
+                (
+                    "dma_speedtest_current_ops_per_second",
+                    "Ops per second in the latest interval of the active pass.",
+                    "gauge",
+                    sample.ops_per_sec as f64,
+                ),
+                (
+                    "dma_speedtest_current_latency_microseconds",
+                    "Average op latency in the latest interval of the active pass.",
+                    "gauge",
+                    sample.latency_us,
+                ),
+                (
+                    "dma_speedtest_current_elapsed_seconds",
+                    "Time spent in the active pass.",
+                    "gauge",
+                    sample.elapsed_secs,
+                ),
+                (
+                    "dma_speedtest_current_ops_total",
+                    "Completed ops in the active pass.",
+                    "counter",
+                    live.ops as f64,
+                ),
+                (
+                    "dma_speedtest_current_skipped_ops_total",
+                    "Ops skipped after exhausting retries in the active pass.",
+                    "counter",
+                    live.skipped_ops as f64,
+                ),
+            ] {
+                family(&mut out, name, help, kind, [(key.clone(), value)]);
+            }
+        }
+
+        if self.passes.is_empty() {
+            return out;
+        }
+        for (name, help, stats) in [
+            (
+                "dma_speedtest_pass_throughput_mib_s",
+                "Per-pass throughput in MiB/s (weighted average, min and max interval).",
+                pass_stat_rows(&self.passes, |p| (p.min_mib_s, p.avg_mib_s, p.max_mib_s)),
+            ),
+            (
+                "dma_speedtest_pass_ops_per_second",
+                "Per-pass ops per second (weighted average, min and max interval).",
+                pass_stat_rows(&self.passes, |p| (p.min_ops_s, p.avg_ops_s, p.max_ops_s)),
+            ),
+            (
+                "dma_speedtest_pass_latency_microseconds",
+                "Per-pass op latency (ops-weighted average, min and max interval).",
+                pass_stat_rows(&self.passes, |p| {
+                    (p.min_latency_us, p.avg_latency_us, p.max_latency_us)
+                }),
+            ),
+        ] {
+            family(&mut out, name, help, "gauge", stats);
+        }
+        let counts: [(&str, &str, PassValue); 4] = [
+            (
+                "dma_speedtest_pass_samples",
+                "Interval samples recorded per pass.",
+                |p| p.samples as f64,
+            ),
+            ("dma_speedtest_pass_ops", "Completed ops per pass.", |p| {
+                p.total_ops as f64
+            }),
+            (
+                "dma_speedtest_pass_skipped_ops",
+                "Ops skipped after exhausting retries per pass.",
+                |p| p.skipped_ops as f64,
+            ),
+            (
+                "dma_speedtest_pass_measured_seconds",
+                "Measured time per pass.",
+                |p| p.measured_secs,
+            ),
+        ];
+        for (name, help, value) in counts {
+            family(
+                &mut out,
+                name,
+                help,
+                "gauge",
+                self.passes.iter().map(|pass| {
+                    (
+                        pass_labels(pass.op, pass.chunk_bytes, pass.step, None),
+                        value(pass),
+                    )
+                }),
+            );
+        }
+        out
+    }
+
+    fn write_textfile(&mut self, force: bool) -> Result<()> {
+        let Some(textfile) = &self.textfile else {
+            return Ok(());
+        };
+        if !force
+            && textfile
+                .last_write
+                .is_some_and(|at| at.elapsed() < TEXTFILE_INTERVAL)
+        {
+            return Ok(());
+        }
+
+        let body = self.render();
+        let Some(textfile) = &mut self.textfile else {
+            return Ok(());
+        };
+        textfile.last_write = Some(Instant::now());
+        // Write then rename so the collector never reads a half-written file.
+        let tmp = textfile.path.with_extension("prom.tmp");
+        let result = fs::write(&tmp, body).and_then(|()| fs::rename(&tmp, &textfile.path));
+        let warn = result.is_err() && !textfile.warned;
+        textfile.warned = result.is_err();
+        if warn {
+            result.with_context(|| {
+                format!(
+                    "could not write metrics textfile {}",
+                    textfile.path.display()
+                )
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn pass_stat_rows(
+    passes: &[PassSummary],
+    values: impl Fn(&PassSummary) -> (f64, f64, f64),
+) -> Vec<(String, f64)> {
+    passes
+        .iter()
+        .flat_map(|pass| {
+            let (min, avg, max) = values(pass);
+            [("min", min), ("avg", avg), ("max", max)].map(|(stat, value)| {
+                (
+                    pass_labels(pass.op, pass.chunk_bytes, pass.step, Some(stat)),
+                    value,
+                )
+            })
+        })
+        .collect()
+}
+
+/// `op` and `chunk_bytes`, plus the scenario `step` index so repeated sizes in different steps
+/// stay separate series.
+fn pass_labels(op: BenchOp, chunk_bytes: usize, step: Option<usize>, stat: Option<&str>) -> String {
+    let chunk_bytes = chunk_bytes.to_string();
+    let step = step.map(|step| step.to_string());
+    let mut pairs = vec![("op", op.label()), ("chunk_bytes", chunk_bytes.as_str())];
+    if let Some(step) = &step {
+        pairs.push(("step", step));
+    }
+    if let Some(stat) = stat {
+        pairs.push(("stat", stat));
+    }
+    labels(&pairs)
+}
+
+fn labels(pairs: &[(&str, &str)]) -> String {
+    let body = pairs
+        .iter()
+        .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{{{body}}}")
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn family(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    kind: &str,
+    rows: impl IntoIterator<Item = (String, f64)>,
+) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    for (labels, value) in rows {
+        let _ = writeln!(out, "{name}{labels} {value}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(ops: u64, skipped_ops: u64) -> BenchSample {
+        BenchSample {
+            op: BenchOp::Read,
+            throughput_mib_s: 20.5,
+            ops_per_sec: 5000,
+            elapsed_secs: 0.2,
+            interval_secs: 0.1,
+            ops,
+            chunk_bytes: 4096,
+            latency_us: 2.0,
+            skipped_ops,
+            step: None,
+        }
+    }
+
+    fn summary() -> PassSummary {
+        PassSummary {
+            min_mib_s: 10.0,
+            max_mib_s: 30.0,
+            skipped_ops: 2,
+            ..PassSummary::test_read(4096, 20.0)
+        }
+    }
+
+    #[test]
+    fn live_gauges_track_the_active_pass_until_it_finishes() {
+        let metrics = BenchMetrics::new(Connector::Native, BenchMode::Read);
+        metrics.record_sample(&sample(500, 1)).unwrap();
+        metrics.record_sample(&sample(400, 2)).unwrap();
+
+        let text = metrics.render();
+        assert!(text.contains("dma_speedtest_running 1\n"));
+        assert!(text.contains(
+            "dma_speedtest_current_throughput_mib_s{op=\"read\",chunk_bytes=\"4096\"} 20.5\n"
+        ));
+        assert!(
+            text.contains(
+                "dma_speedtest_current_ops_total{op=\"read\",chunk_bytes=\"4096\"} 900\n"
+            )
+        );
+        assert!(text.contains(
+            "dma_speedtest_current_skipped_ops_total{op=\"read\",chunk_bytes=\"4096\"} 3\n"
+        ));
+
+        metrics.finish_pass(&summary()).unwrap();
+        metrics.finish_run().unwrap();
+
+        let text = metrics.render();
+        assert!(text.contains("dma_speedtest_running 0\n"));
+        assert!(!text.contains("dma_speedtest_current_"));
+        assert!(text.contains("dma_speedtest_passes_completed 1\n"));
+        assert!(text.contains(
+            "dma_speedtest_pass_throughput_mib_s{op=\"read\",chunk_bytes=\"4096\",stat=\"avg\"} 20\n"
+        ));
+        assert!(
+            text.contains("dma_speedtest_pass_skipped_ops{op=\"read\",chunk_bytes=\"4096\"} 2\n")
+        );
+    }
+
+    #[test]
+    fn scenario_steps_keep_separate_pass_series() {
+        let metrics = BenchMetrics::new(Connector::Native, BenchMode::Read);
+        for step in [0, 1] {
+            metrics
+                .finish_pass(&PassSummary {
+                    step: Some(step),
+                    ..summary()
+                })
+                .unwrap();
+        }
+
+        let text = metrics.render();
+        assert!(text.contains("dma_speedtest_passes_completed 2\n"));
+        assert!(text.contains(
+            "dma_speedtest_pass_skipped_ops{op=\"read\",chunk_bytes=\"4096\",step=\"1\"} 2\n"
+        ));
+        assert!(text.contains(
+            "dma_speedtest_pass_throughput_mib_s{op=\"read\",chunk_bytes=\"4096\",step=\"0\",stat=\"avg\"} 20\n"
+        ));
+    }
+
+    #[test]
+    fn textfile_mirrors_the_exposition() {
+        let dir =
+            std::env::temp_dir().join(format!("dma-speedtest-metrics-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("dma_speedtest.prom");
+
+        let metrics =
+            BenchMetrics::new(Connector::Native, BenchMode::Read).with_textfile(path.clone());
+        metrics.finish_pass(&summary()).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert_eq!(written, metrics.render());
+        assert!(!path.with_extension("prom.tmp").exists());
+
+        fs::remove_file(path).unwrap();
+        fs::remove_dir(dir).unwrap();
+    }
+
+    #[test]
+    fn textfile_write_failures_are_returned_once() {
+        let path = std::env::temp_dir()
+            .join(format!(
+                "dma-speedtest-metrics-missing-{}",
+                std::process::id()
+            ))
+            .join("dma_speedtest.prom");
+        let metrics = BenchMetrics::new(Connector::Native, BenchMode::Read).with_textfile(path);
+
+        let err = metrics.finish_pass(&summary()).unwrap_err();
+        assert!(format!("{err:#}").contains("could not write metrics textfile"));
+        assert!(metrics.finish_run().is_ok());
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(
+            labels(&[("device", "fpga\"a\\b\n")]),
+            "{device=\"fpga\\\"a\\\\b\\n\"}"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! Minimal blocking HTTP endpoint serving [`BenchMetrics`] at `/metrics`.
+
+use super::metrics::{BenchMetrics, METRICS_CONTENT_TYPE};
+use anyhow::Result;
+use std::{
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_REQUEST_HEAD_BYTES: usize = 8 * 1024;
+
+/// Serves metrics on a background thread until dropped.
+pub struct MetricsServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MetricsServer {
+    pub fn bind(addr: SocketAddr, metrics: BenchMetrics) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| anyhow::anyhow!("could not bind metrics endpoint on {addr}: {e}"))?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = thread::Builder::new()
+            .name("metrics-server".to_string())
+            .spawn({
+                let stop = Arc::clone(&stop);
+                move || accept_loop(&listener, &metrics, &stop)
+            })?;
+
+        Ok(Self {
+            addr,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// Bound address (resolves port `0` to the assigned port).
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn accept_loop(listener: &TcpListener, metrics: &BenchMetrics, stop: &AtomicBool) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            // One scrape at a time is plenty for a local collector.
+            Ok((stream, _)) => {
+                let _ = handle_connection(stream, metrics);
+            }
+            // WouldBlock between connections; transient accept errors get the same back-off.
+            Err(_) => thread::sleep(ACCEPT_POLL),
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, metrics: &BenchMetrics) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let head = read_request_head(&mut stream)?;
+    let (status, content_type, body) = match request_target(&head) {
+        Some(("GET", "/metrics")) => ("200 OK", METRICS_CONTENT_TYPE, metrics.render()),
+        Some(("GET", _)) => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "metrics are served at /metrics\n".to_string(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "only GET is supported\n".to_string(),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+pub(super) fn read_request_head(stream: &mut TcpStream) -> io::Result<String> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD_BYTES {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+/// `(method, path)` from the request line, ignoring any query string.
+fn request_target(head: &str) -> Option<(&str, &str)> {
+    let mut parts = head.lines().next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    Some((method, target.split('?').next().unwrap_or(target)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::{BenchMode, Connector};
+
+    fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_metrics_to_a_local_scraper() {
+        let metrics = BenchMetrics::new(Connector::Native, BenchMode::Read);
+        let server = MetricsServer::bind("127.0.0.1:0".parse().unwrap(), metrics).unwrap();
+
+        let response = get(server.local_addr(), "/metrics?format=text");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(response.contains("\r\n\r\n# HELP dma_speedtest_info "));
+        assert!(response.contains("dma_speedtest_running 1\n"));
+
+        assert!(get(server.local_addr(), "/").starts_with("HTTP/1.1 404"));
+    }
+
+    #[test]
+    fn request_target_strips_query() {
+        assert_eq!(
+            request_target("GET /metrics?x=1 HTTP/1.1\r\n"),
+            Some(("GET", "/metrics"))
+        );
+        assert_eq!(request_target(""), None);
+    }
+}
@@ -1,23 +1,85 @@
+mod agent;
 mod bench;
+mod compare;
+mod connect_timings;
 mod connector;
+mod control_server;
+mod doctor;
+mod environment;
+mod error;
+mod gate;
+mod history;
+mod image_export;
 mod initialization;
+mod labels;
+mod liveness;
 mod mem_io;
+mod metrics;
+mod metrics_server;
+mod observer;
 mod probe_targets;
+mod profile;
 mod report;
+mod report_html;
+mod report_junit;
+mod report_markdown;
+mod scenario;
+mod session;
 mod stats;
+mod stream;
+mod svg_chart;
+mod watchdog;
 mod worker;
 mod write_target;
 
+pub use agent::{AgentServer, AgentStopHandle, AgentViewer};
 pub use bench::{BenchMode, BenchOp, BenchStats};
+pub use compare::{
+    CompareMetric, CompareThresholds, MetricDelta, PassComparison, ReportComparison,
+    compare_reports,
+};
+pub use connect_timings::{ConnectStep, ConnectTiming, ConnectTimings};
 pub use connector::Connector;
+pub use control_server::{
+    ConnectRequest, ConnectionInfo, ControlServer, ControlState, ControlStatus, CurrentPass,
+    DEFAULT_CONTROL_ADDR, StartRequest,
+};
+pub use doctor::{
+    DoctorReport, DoctorSettings, DoctorStage, DoctorStageKind, StageStatus, run_doctor,
+};
+pub use environment::RunEnvironment;
+pub use error::{BenchError, BenchErrorKind};
+pub use gate::{GateCheck, GateCheckKind, GateCriteria, GateVerdict, SizeLimit, evaluate_gate};
+pub use history::{DEFAULT_HISTORY_PATH, HistoryEntry, RunHistory, TrendPoint, metric_trend};
+pub use image_export::{
+    DEFAULT_IMAGE_WIDTH, IMAGE_WIDTH_RANGE, ImageFormat, ImageSeries, default_image_path, plot_svg,
+    table_svg, write_image,
+};
+pub use labels::{RunLabels, format_labels, parse_label, parse_labels_csv};
+pub use liveness::{TargetLossKind, TargetLost};
+pub use metrics::{BenchMetrics, METRICS_CONTENT_TYPE};
+pub use metrics_server::MetricsServer;
+pub use observer::{BenchObserver, ConnectPhase, NoopObserver};
 pub use probe_targets::{ProbeTargets, WRITE_MUTATION_WARNING};
+pub use profile::{BenchProfile, ProfileOutput, ProfileTarget, ProfileThresholds};
 pub use report::{
-    BenchmarkReport, ReportFormat, default_report_path, infer_report_format, resolve_report_format,
+    BenchmarkReport, REPORT_SCHEMA_VERSION, ReportError, ReportFormat, ReportStep,
+    default_report_path, infer_report_format, load_report_from_path, resolve_report_format,
     write_report_to_path,
 };
+pub use scenario::{Scenario, ScenarioStep};
+pub use session::{BenchmarkSession, RunFailure, SessionStep};
+#[allow(deprecated)]
+pub use stats::drain_stats_channel;
 pub use stats::{
-    BenchSample, PassAggregator, PassSummary, drain_stats_channel, format_console_log_line,
-    format_live_sample_line, live_sample_columns,
+    BenchSample, PassAggregator, PassSummary, SummaryGroup, format_console_log_line,
+    format_live_sample_line, live_sample_columns, summary_groups,
+};
+pub use stream::StreamEvent;
+#[allow(deprecated)]
+pub use worker::{BenchPassStartFn, BenchWarnFn};
+pub use worker::{RECONNECT_DELAY, SpeedTest, WriteRestoreOutcome};
+pub use write_target::{
+    MIN_WRITE_REGION_BYTES, SafeWriteRegion, VaRange, WriteCandidate, WritePageFlag,
+    WriteTargetPolicy, format_page_type, select_write_candidate, select_write_region,
 };
-pub use worker::{BenchPassStartFn, BenchWarnFn, SpeedTest};
-pub use write_target::MIN_WRITE_REGION_BYTES;
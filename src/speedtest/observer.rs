@@ -0,0 +1,73 @@
+//! [`BenchObserver`]: one subscription point for everything a benchmark reports while it runs.
+//!
+//! The CLI printer, the GUI console, the control API recorder and exporters all implement the
+//! same trait; a [`BenchmarkSession`](super::BenchmarkSession) calls it from connect to restore.
+
+use super::connect_timings::ConnectTimings;
+use super::probe_targets::{TARGET_PROCESS, TARGET_READ_MODULE};
+use super::{
+    BenchOp, BenchSample, PassSummary, SessionStep, SpeedTest, TargetLost, WriteRestoreOutcome,
+};
+use anyhow::Result;
+
+/// Connection setup stages, reported in order as each one starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectPhase {
+    /// Plugin inventory scan and connector / OS plugin creation.
+    Connector,
+    Process,
+    Module,
+    /// Write probe search and canary verification; only for modes that write.
+    WriteTarget,
+}
+
+impl ConnectPhase {
+    pub fn label(self) -> String {
+        match self {
+            ConnectPhase::Connector => "opening connector and OS plugin".to_string(),
+            ConnectPhase::Process => format!("finding process {TARGET_PROCESS}"),
+            ConnectPhase::Module => format!("finding module {TARGET_READ_MODULE}"),
+            ConnectPhase::WriteTarget => "resolving write probe".to_string(),
+        }
+    }
+}
+
+/// Benchmark lifecycle callbacks; every method defaults to doing nothing.
+///
+/// Callbacks run on the benchmark's thread or runtime and should return quickly.
+pub trait BenchObserver: Send + Sync {
+    /// A connection stage is starting (initial connect and every reconnect).
+    fn connect_phase(&self, _phase: ConnectPhase) {}
+    /// A connect failed; `timings` covers the phases that ran, the failing one included.
+    ///
+    /// The error itself follows through [`Self::error`] or [`Self::reconnect_failed`].
+    fn connect_failed(&self, _timings: &ConnectTimings) {}
+    /// A scenario step begins (single-step sessions skip this).
+    fn step_start(&self, _index: usize, _step: &SessionStep) {}
+    fn pass_start(
+        &self,
+        _test: &SpeedTest,
+        _op: BenchOp,
+        _chunk_bytes: usize,
+        _step: &SessionStep,
+    ) {
+    }
+    fn sample(&self, _sample: &BenchSample) {}
+    /// Retry and skipped-op warnings from the worker.
+    fn warning(&self, _message: &str) {}
+    /// A pass finished, including one cut short by an error or cancel.
+    fn pass_end(&self, _summary: &PassSummary) {}
+    /// The connect or run failed with the error about to be returned.
+    fn error(&self, _error: &anyhow::Error) {}
+    /// The target was lost and a reconnect attempt is about to start.
+    fn reconnecting(&self, _lost: &TargetLost) {}
+    fn reconnect_failed(&self, _error: &anyhow::Error) {}
+    fn reconnected(&self, _test: &SpeedTest) {}
+    /// Outcome of restoring the write probe's original bytes; only called for write runs.
+    fn write_restored(&self, _outcome: &Result<WriteRestoreOutcome>) {}
+}
+
+/// Observer that ignores every event.
+pub struct NoopObserver;
+
+impl BenchObserver for NoopObserver {}
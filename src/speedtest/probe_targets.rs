@@ -1,4 +1,4 @@
-//! Human-readable descriptions of fixed read/write probe targets (not user-configurable).
+//! Human-readable descriptions of the read/write probe targets.
 
 use crate::bench_config::format_byte_count;
 use memflow::prelude::v1::*;
@@ -14,6 +14,8 @@ pub struct ProbeTargets {
     pub read_addr: Address,
     pub write_addr: Option<Address>,
     pub write_region_bytes: Option<umem>,
+    /// Write probe address came from `--write-addr` instead of auto-selection.
+    pub write_explicit: bool,
 }
 
 impl ProbeTargets {
@@ -26,6 +28,20 @@ impl ProbeTargets {
             read_addr,
             write_addr,
             write_region_bytes,
+            write_explicit: false,
+        }
+    }
+
+    pub fn with_explicit_write(mut self, explicit: bool) -> Self {
+        self.write_explicit = explicit;
+        self
+    }
+
+    fn write_selection_label(&self) -> &'static str {
+        if self.write_explicit {
+            "user-specified"
+        } else {
+            "auto-selected"
         }
     }
 
@@ -63,7 +79,8 @@ impl ProbeTargets {
                 .map(Self::format_va)
                 .unwrap_or_else(|| "?".to_string());
             lines.push(format!(
-                "Write probe: auto-selected private writable region @ {}",
+                "Write probe: {} private writable region @ {}",
+                self.write_selection_label(),
                 Self::format_va(addr)
             ));
             lines.push(format!("  Warning: {WRITE_MUTATION_WARNING}"));
@@ -105,10 +122,11 @@ impl ProbeTargets {
         let addr = self.write_addr?;
         let region = self.write_region_bytes?;
         Some(format!(
-            "DMA write {chunk} -> {addr} (inside {region} auto-selected writable probe region); {WRITE_PAYLOAD_DESC}",
+            "DMA write {chunk} -> {addr} (inside {region} {selection} writable probe region); {WRITE_PAYLOAD_DESC}",
             chunk = format_byte_count(chunk_bytes),
             addr = Self::format_va(addr),
             region = format_byte_count(region as usize),
+            selection = self.write_selection_label(),
         ))
     }
 
@@ -144,6 +162,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn explicit_write_target_is_labelled_user_specified() {
+        let targets = ProbeTargets::new(
+            Address::from(0x1000_u64),
+            Some(Address::from(0x2000_u64)),
+            Some(64 * 1024),
+        )
+        .with_explicit_write(true);
+
+        assert!(
+            targets
+                .connect_detail_lines()
+                .iter()
+                .any(|line| line.contains("user-specified private writable region"))
+        );
+        assert!(
+            targets
+                .format_write_pass(4096)
+                .unwrap()
+                .contains("user-specified")
+        );
+    }
+
     #[test]
     fn write_connect_details_warn_about_mutating_memory() {
         let targets = ProbeTargets::new(
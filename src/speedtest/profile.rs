@@ -0,0 +1,254 @@
+//! Declarative benchmark profiles: TOML or JSON files with the settings of a run.
+//!
+//! Every field is optional so a profile can pin only what matters; the CLI fills the rest from
+//! flags and defaults, and flags given on the command line win over profile fields.
+
+use super::{BenchMode, Connector, ReportFormat, SizeLimit, WritePageFlag, WriteTargetPolicy};
+use anyhow::{Result, bail};
+use memflow::prelude::v1::Address;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BenchProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connector: Option<Connector>,
+    /// PCILeech device string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<BenchMode>,
+    /// Chunk sizes in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sizes: Option<Vec<usize>>,
+    /// Seconds per chunk size.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+    #[serde(skip_serializing_if = "ProfileTarget::is_empty")]
+    pub target: ProfileTarget,
+    #[serde(skip_serializing_if = "ProfileThresholds::is_empty")]
+    pub thresholds: ProfileThresholds,
+    #[serde(skip_serializing_if = "ProfileOutput::is_empty")]
+    pub output: ProfileOutput,
+}
+
+/// Write probe selection, as the `--write-*` flags.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfileTarget {
+    /// Hex string such as `"0x1f0000"`.
+    #[serde(skip_serializing_if = "Option::is_none", with = "hex_address")]
+    pub write_addr: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_region_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_min_region: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_max_region: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_prefer: Option<Vec<WritePageFlag>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_avoid: Option<Vec<WritePageFlag>>,
+}
+
+impl ProfileTarget {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn write_policy(&self) -> WriteTargetPolicy {
+        WriteTargetPolicy {
+            address: self.write_addr.map(Address::from),
+            region_bytes: self.write_region_bytes,
+            min_region_bytes: self.write_min_region,
+            max_region_bytes: self.write_max_region,
+            prefer: self.write_prefer.clone().unwrap_or_default(),
+            avoid: self.write_avoid.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// QA gate limits, as `--baseline`, `--min-throughput` and friends.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfileThresholds {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_throughput_drop: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_latency_rise: Option<f64>,
+    /// `VALUE` or `SIZE_BYTES=VALUE` entries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_throughput: Option<Vec<SizeLimit>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_latency: Option<Vec<SizeLimit>>,
+}
+
+impl ProfileThresholds {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Report output, as `--output`, `--output-format` and `--include-samples`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfileOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ReportFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_samples: Option<bool>,
+}
+
+impl ProfileOutput {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileFormat {
+    Toml,
+    Json,
+}
+
+impl ProfileFormat {
+    /// `.toml` or `.json`, case-insensitive.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("toml") => Ok(ProfileFormat::Toml),
+            Some("json") => Ok(ProfileFormat::Json),
+            _ => bail!("{} must end in .toml or .json", path.display()),
+        }
+    }
+}
+
+/// Read a TOML or JSON settings file; `what` names it in errors ("profile", "scenario").
+pub(super) fn load_settings_file<T: DeserializeOwned>(path: &Path, what: &str) -> Result<T> {
+    let format = ProfileFormat::from_path(path)?;
+    let body = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("could not read {what} {}: {e}", path.display()))?;
+    let settings = match format {
+        ProfileFormat::Toml => toml::from_str(&body).map_err(|e| e.to_string()),
+        ProfileFormat::Json => serde_json::from_str(&body).map_err(|e| e.to_string()),
+    };
+    settings.map_err(|e| anyhow::anyhow!("invalid {what} {}: {e}", path.display()))
+}
+
+impl BenchProfile {
+    pub fn load(path: &Path) -> Result<Self> {
+        load_settings_file(path, "profile")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let body = match ProfileFormat::from_path(path)? {
+            ProfileFormat::Toml => toml::to_string_pretty(self)?,
+            ProfileFormat::Json => serde_json::to_string_pretty(self)? + "\n",
+        };
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, body)
+            .map_err(|e| anyhow::anyhow!("could not write profile {}: {e}", path.display()))
+    }
+}
+
+/// `Option<u64>` as a `0x`-prefixed hex string.
+mod hex_address {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(address) => serializer.serialize_str(&format!("{address:#x}")),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u64>, D::Error> {
+        let Some(text) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let trimmed = text.trim();
+        let digits = trimmed
+            .strip_prefix("0x")
+            .or_else(|| trimmed.strip_prefix("0X"))
+            .unwrap_or(trimmed);
+        u64::from_str_radix(digits, 16)
+            .map(Some)
+            .map_err(|_| serde::de::Error::custom(format!("invalid hex address {text:?}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_profile() -> BenchProfile {
+        BenchProfile {
+            connector: Some(Connector::Native),
+            mode: Some(BenchMode::Both),
+            sizes: Some(vec![4096, 65536]),
+            duration: Some(30),
+            target: ProfileTarget {
+                write_addr: Some(0x1f_0000),
+                write_prefer: Some(vec![WritePageFlag::Noexec]),
+                ..ProfileTarget::default()
+            },
+            thresholds: ProfileThresholds {
+                max_latency_rise: Some(15.0),
+                min_throughput: Some(vec![
+                    SizeLimit::parse("100").unwrap(),
+                    SizeLimit::parse("4096=60").unwrap(),
+                ]),
+                ..ProfileThresholds::default()
+            },
+            output: ProfileOutput {
+                format: Some(ReportFormat::Junit),
+                ..ProfileOutput::default()
+            },
+            ..BenchProfile::default()
+        }
+    }
+
+    #[test]
+    fn round_trips_toml_and_json_profiles() {
+        let dir =
+            std::env::temp_dir().join(format!("dma-speedtest-profile-{}", std::process::id()));
+        let profile = sample_profile();
+
+        let toml_path = dir.join("bench.toml");
+        profile.save(&toml_path).unwrap();
+        let body = fs::read_to_string(&toml_path).unwrap();
+        assert!(body.contains("connector = \"native\""));
+        assert!(body.contains("write_addr = \"0x1f0000\""));
+        assert!(body.contains("min_throughput = [\n    \"100\",\n    \"4096=60\",\n]"));
+        assert!(!body.contains("device"));
+        assert!(!body.contains("[output]\npath"));
+        assert_eq!(BenchProfile::load(&toml_path).unwrap(), profile);
+
+        let json_path = dir.join("bench.JSON");
+        profile.save(&json_path).unwrap();
+        assert_eq!(BenchProfile::load(&json_path).unwrap(), profile);
+
+        fs::write(&toml_path, "mode = \"read\"\nspeed = 3\n").unwrap();
+        let err = BenchProfile::load(&toml_path).unwrap_err().to_string();
+        assert!(err.contains("speed"), "{err}");
+        assert!(BenchProfile::load(&dir.join("bench.yaml")).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,17 +1,31 @@
-use super::{BenchMode, Connector, PassSummary, ProbeTargets};
+use super::error::BenchErrorKind;
+use super::gate::GateVerdict;
+use super::labels::{RunLabels, format_labels, labels_file_slug};
+use super::stats::SummaryGroup;
+use super::{
+    BenchMode, BenchSample, Connector, PassSummary, ProbeTargets, RunEnvironment, report_html,
+    report_junit, report_markdown,
+};
+use crate::bench_config::{format_byte_count, format_chunk_size};
 use anyhow::{Result, bail};
 use clap::ValueEnum;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::{
     fs,
     path::{Path, PathBuf},
     time::{SystemTime, UNIX_EPOCH},
 };
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ReportFormat {
     Csv,
     Json,
+    Markdown,
+    /// Single file with inline SVG charts; no external assets.
+    Html,
+    /// JUnit XML for CI: one testcase per pass.
+    Junit,
 }
 
 impl ReportFormat {
@@ -19,23 +33,90 @@ impl ReportFormat {
         match self {
             ReportFormat::Csv => "csv",
             ReportFormat::Json => "json",
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+            ReportFormat::Junit => "xml",
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Bumped when the report layout changes incompatibly.
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BenchmarkReport {
+    /// Reports written before the field existed share the version 1 layout.
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub version: String,
     pub connector: String,
+    /// For scenario runs: every mode used, the longest step and all sizes.
     pub mode: String,
     pub duration_secs: u64,
     pub sizes: Vec<usize>,
+    /// Scenario steps in run order; passes and samples refer to them by index.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<ReportStep>,
     pub generated_unix_secs: u64,
+    /// `key=value` tags from `--label` or the GUI; empty when none were given.
+    #[serde(default, skip_serializing_if = "RunLabels::is_empty")]
+    pub labels: RunLabels,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
     pub probes: ReportProbeTargets,
+    /// Host, software and target fingerprint; absent in reports from older builds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<RunEnvironment>,
     pub passes: Vec<PassSummary>,
+    /// Per-interval time series; only present when raw samples were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub samples: Option<Vec<BenchSample>>,
+    /// Pass/fail gate result; only present when limits or a baseline were given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<GateVerdict>,
+    /// Why the run stopped early; the passes are the ones finished before the failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ReportError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportError {
+    /// Category of a [`BenchError`]; absent for other failures.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<BenchErrorKind>,
+    pub message: String,
+}
+
+impl ReportError {
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        Self {
+            kind: BenchErrorKind::of(error),
+            message: format!("{error:#}"),
+        }
+    }
+}
+
+/// One step of a scenario run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportStep {
+    pub name: String,
+    pub mode: String,
+    pub duration_secs: u64,
+    pub sizes: Vec<usize>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl ReportStep {
+    pub fn new(name: &str, mode: BenchMode, duration_secs: u64, sizes: &[usize]) -> Self {
+        Self {
+            name: name.to_string(),
+            mode: mode.label().to_string(),
+            duration_secs,
+            sizes: sizes.to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReportProbeTargets {
     pub read_addr: String,
     pub write_addr: Option<String>,
@@ -52,12 +133,16 @@ impl BenchmarkReport {
         passes: Vec<PassSummary>,
     ) -> Self {
         Self {
+            schema_version: REPORT_SCHEMA_VERSION,
             version: env!("CARGO_PKG_VERSION").to_string(),
             connector: connector.to_string(),
-            mode: bench_mode_label(mode).to_string(),
+            mode: mode.label().to_string(),
             duration_secs,
             sizes: sizes.to_vec(),
+            steps: Vec::new(),
             generated_unix_secs: unix_timestamp_secs(),
+            labels: RunLabels::new(),
+            note: None,
             probes: ReportProbeTargets {
                 read_addr: ProbeTargets::format_va(probes.read_addr),
                 write_addr: probes.write_addr.map(ProbeTargets::format_va),
@@ -65,9 +150,279 @@ impl BenchmarkReport {
                     .write_region_bytes
                     .map(|bytes| usize::try_from(bytes).unwrap_or(usize::MAX)),
             },
+            environment: None,
             passes,
+            samples: None,
+            verdict: None,
+            error: None,
         }
     }
+
+    pub fn with_labels(mut self, labels: RunLabels) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Free-form note; blank text is dropped.
+    pub fn with_note(mut self, note: &str) -> Self {
+        let note = note.trim();
+        self.note = (!note.is_empty()).then(|| note.to_string());
+        self
+    }
+
+    pub fn with_steps(mut self, steps: Vec<ReportStep>) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Name of the scenario step a pass or sample belongs to.
+    pub fn step_name(&self, step: Option<usize>) -> Option<&str> {
+        step.and_then(|index| self.steps.get(index))
+            .map(|step| step.name.as_str())
+    }
+
+    /// Passes grouped by scenario step, in step order; one unnamed section for plain runs.
+    pub fn step_sections(&self) -> Vec<(Option<&ReportStep>, Vec<PassSummary>)> {
+        if self.steps.is_empty() {
+            return vec![(None, self.passes.clone())];
+        }
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                let passes = self
+                    .passes
+                    .iter()
+                    .filter(|pass| pass.step == Some(index))
+                    .cloned()
+                    .collect();
+                (Some(step), passes)
+            })
+            .collect()
+    }
+
+    pub fn with_environment(mut self, environment: RunEnvironment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    pub fn with_verdict(mut self, verdict: GateVerdict) -> Self {
+        self.verdict = Some(verdict);
+        self
+    }
+
+    /// Record the error that ended the run.
+    pub fn with_error(mut self, error: &anyhow::Error) -> Self {
+        self.error = Some(ReportError::from_error(error));
+        self
+    }
+
+    /// Attach the raw per-interval samples (JSON body, companion CSV for CSV reports).
+    pub fn with_samples(mut self, samples: Vec<BenchSample>) -> Self {
+        self.samples = Some(samples);
+        self
+    }
+
+    /// Where the long-format samples CSV goes next to a CSV report, if one is written.
+    pub fn samples_companion_path(&self, format: ReportFormat, path: &Path) -> Option<PathBuf> {
+        if format != ReportFormat::Csv || self.samples.is_none() {
+            return None;
+        }
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "report".to_string());
+        Some(path.with_file_name(format!("{stem}-samples.csv")))
+    }
+}
+
+/// Run metadata rows shared by the Markdown and HTML reports.
+pub(super) fn overview_rows(report: &BenchmarkReport) -> Vec<(&'static str, String)> {
+    let mut rows = vec![
+        ("Version", report.version.clone()),
+        ("Connector", report.connector.clone()),
+        ("Mode", report.mode.clone()),
+    ];
+    if report.steps.is_empty() {
+        rows.push(("Duration per pass", format!("{} s", report.duration_secs)));
+        rows.push(("Chunk sizes", format_chunk_sizes(&report.sizes)));
+    } else {
+        rows.push(("Scenario steps", report.steps.len().to_string()));
+    }
+    rows.push((
+        "Generated (unix secs)",
+        report.generated_unix_secs.to_string(),
+    ));
+    if !report.labels.is_empty() {
+        rows.push(("Labels", format_labels(&report.labels)));
+    }
+    if let Some(note) = &report.note {
+        rows.push(("Note", note.clone()));
+    }
+    if let Some(environment) = &report.environment
+        && !environment.connect_timings.is_empty()
+    {
+        rows.push((
+            "Connect timings",
+            format!(
+                "{} (total {})",
+                environment.connect_timings.summary(),
+                environment.connect_timings.format_total()
+            ),
+        ));
+    }
+    if let Some(error) = &report.error {
+        let message = match error.kind {
+            Some(kind) => format!("{}: {}", kind.label(), error.message),
+            None => error.message.clone(),
+        };
+        rows.push(("Error", message));
+    }
+    rows
+}
+
+fn format_chunk_sizes(sizes: &[usize]) -> String {
+    sizes
+        .iter()
+        .map(|&size| format_chunk_size(size))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Header and cells for the scenario steps table shared by the Markdown and HTML reports.
+pub(super) fn steps_table(report: &BenchmarkReport) -> (Vec<String>, Vec<Vec<String>>) {
+    let header = ["#", "Step", "Mode", "Duration", "Chunk sizes"]
+        .map(str::to_string)
+        .to_vec();
+    let rows = report
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            vec![
+                (index + 1).to_string(),
+                step.name.clone(),
+                step.mode.clone(),
+                format!("{} s", step.duration_secs),
+                format_chunk_sizes(&step.sizes),
+            ]
+        })
+        .collect();
+    (header, rows)
+}
+
+/// Probe target rows shared by the Markdown and HTML reports.
+pub(super) fn probe_rows(report: &BenchmarkReport) -> Vec<(&'static str, String)> {
+    let probes = &report.probes;
+    vec![
+        ("Read address", probes.read_addr.clone()),
+        (
+            "Write address",
+            probes
+                .write_addr
+                .clone()
+                .unwrap_or_else(|| "none (read-only run)".to_string()),
+        ),
+        (
+            "Write region",
+            probes
+                .write_region_bytes
+                .map(format_byte_count)
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+    ]
+}
+
+/// Header and cells for one summary group, matching the CLI summary table columns.
+pub(super) fn summary_table(group: &SummaryGroup<'_>) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut header = Vec::new();
+    if group.op.is_none() {
+        header.push("Op".to_string());
+    }
+    let ops_heading = match group.op {
+        Some(op) => format!("Avg {}", op.ops_per_sec_label()),
+        None => "Avg ops/s".to_string(),
+    };
+    header.extend([
+        "Size".to_string(),
+        "Avg MiB/s".to_string(),
+        ops_heading,
+        "Avg μs".to_string(),
+        "Samples".to_string(),
+    ]);
+
+    let rows = group
+        .rows
+        .iter()
+        .map(|pass| {
+            let mut row = Vec::new();
+            if group.op.is_none() {
+                row.push(pass.op.label().to_string());
+            }
+            row.extend([
+                format_chunk_size(pass.chunk_bytes),
+                format!("{:.2}", pass.avg_mib_s),
+                format!("{:.0}", pass.avg_ops_s),
+                format!("{:.1}", pass.avg_latency_us),
+                pass.samples.to_string(),
+            ]);
+            row
+        })
+        .collect();
+
+    (header, rows)
+}
+
+/// Header and cells for the gate verdict table; scenario reports get a leading step column.
+pub(super) fn verdict_table(
+    report: &BenchmarkReport,
+    verdict: &GateVerdict,
+) -> (Vec<String>, Vec<Vec<String>>) {
+    let with_steps = !report.steps.is_empty();
+    let mut header = Vec::new();
+    if with_steps {
+        header.push("Step".to_string());
+    }
+    header.extend(["Op", "Size", "Check", "Actual", "Result"].map(str::to_string));
+    let rows = verdict
+        .checks
+        .iter()
+        .map(|check| {
+            let mut row = Vec::new();
+            if with_steps {
+                row.push(report.step_name(check.step).unwrap_or("-").to_string());
+            }
+            row.extend([
+                check.op.label().to_string(),
+                format_chunk_size(check.chunk_bytes),
+                check.check.clone(),
+                check.format_actual(),
+                if check.passed { "PASS" } else { "FAIL" }.to_string(),
+            ]);
+            row
+        })
+        .collect();
+    (header, rows)
+}
+
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+/// Load a JSON report written by this tool (CSV reports are not loadable).
+pub fn load_report_from_path(path: &Path) -> Result<BenchmarkReport> {
+    let body = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("could not read report {}: {e}", path.display()))?;
+    let report: BenchmarkReport = serde_json::from_str(&body)
+        .map_err(|e| anyhow::anyhow!("{} is not a JSON benchmark report: {e}", path.display()))?;
+    if report.schema_version > REPORT_SCHEMA_VERSION {
+        bail!(
+            "{} uses report schema {} but this build reads up to {REPORT_SCHEMA_VERSION}",
+            path.display(),
+            report.schema_version
+        );
+    }
+    Ok(report)
 }
 
 pub fn infer_report_format(path: &Path) -> Result<ReportFormat> {
@@ -79,7 +434,12 @@ pub fn infer_report_format(path: &Path) -> Result<ReportFormat> {
     {
         Some("csv") => Ok(ReportFormat::Csv),
         Some("json") => Ok(ReportFormat::Json),
-        _ => bail!("could not infer report format from output path; use --output-format csv|json"),
+        Some("md" | "markdown") => Ok(ReportFormat::Markdown),
+        Some("html" | "htm") => Ok(ReportFormat::Html),
+        Some("xml") => Ok(ReportFormat::Junit),
+        _ => bail!(
+            "could not infer report format from output path; use --output-format csv|json|markdown|html|junit"
+        ),
     }
 }
 
@@ -90,9 +450,16 @@ pub fn resolve_report_format(format: Option<ReportFormat>, path: &Path) -> Resul
     }
 }
 
-pub fn default_report_path(format: ReportFormat) -> PathBuf {
+/// `reports/dma-speedtest-[<labels>-]<unix millis>.<ext>`.
+pub fn default_report_path(format: ReportFormat, labels: &RunLabels) -> PathBuf {
+    let slug = labels_file_slug(labels);
+    let prefix = if slug.is_empty() {
+        "dma-speedtest".to_string()
+    } else {
+        format!("dma-speedtest-{slug}")
+    };
     PathBuf::from("reports").join(format!(
-        "dma-speedtest-{}.{}",
+        "{prefix}-{}.{}",
         unix_timestamp_millis(),
         format.extension()
     ))
@@ -112,16 +479,37 @@ pub fn write_report_to_path(
     let body = match format {
         ReportFormat::Csv => report_to_csv(report),
         ReportFormat::Json => serde_json::to_string_pretty(report)?,
+        ReportFormat::Markdown => report_markdown::render(report),
+        ReportFormat::Html => report_html::render(report),
+        ReportFormat::Junit => report_junit::render(report),
     };
     fs::write(path, body)?;
+
+    if let (Some(samples_path), Some(samples)) = (
+        report.samples_companion_path(format, path),
+        report.samples.as_deref(),
+    ) {
+        fs::write(samples_path, samples_to_csv(report, samples))?;
+    }
     Ok(())
 }
 
+/// Wide format: one row per pass with a fixed column set; unset fields are empty cells.
 fn report_to_csv(report: &BenchmarkReport) -> String {
     let mut out = String::new();
     out.push_str(
-        "version,connector,mode,duration_secs,generated_unix_secs,read_addr,write_addr,write_region_bytes,op,chunk_bytes,samples,total_ops,measured_secs,min_mib_s,avg_mib_s,max_mib_s,min_ops_s,avg_ops_s,max_ops_s,min_latency_us,avg_latency_us,max_latency_us\n",
+        "version,connector,mode,duration_secs,generated_unix_secs,read_addr,write_addr,write_region_bytes,op,chunk_bytes,samples,total_ops,measured_secs,min_mib_s,avg_mib_s,max_mib_s,min_ops_s,avg_ops_s,max_ops_s,min_latency_us,avg_latency_us,max_latency_us,step,labels,note",
     );
+    for name in RunEnvironment::CSV_COLUMNS {
+        out.push(',');
+        out.push_str(name);
+    }
+    out.push_str(",gate,skipped_ops,interrupted\n");
+    let environment = report
+        .environment
+        .as_ref()
+        .map(RunEnvironment::csv_values)
+        .unwrap_or_default();
 
     for pass in &report.passes {
         let columns = [
@@ -152,19 +540,95 @@ fn report_to_csv(report: &BenchmarkReport) -> String {
             format!("{:.6}", pass.avg_latency_us),
             format!("{:.6}", pass.max_latency_us),
         ];
-        out.push_str(
-            &columns
-                .into_iter()
-                .map(csv_escape)
-                .collect::<Vec<_>>()
-                .join(","),
+        let mut row = columns.into_iter().map(csv_escape).collect::<Vec<_>>();
+        row.push(csv_escape(
+            report.step_name(pass.step).unwrap_or_default().to_string(),
+        ));
+        row.push(csv_escape(format_labels(&report.labels)));
+        row.push(csv_escape(report.note.clone().unwrap_or_default()));
+        row.extend(environment.iter().cloned().map(csv_escape));
+        row.push(
+            report
+                .verdict
+                .as_ref()
+                .map(|verdict| pass_gate_label(verdict, pass))
+                .unwrap_or_default()
+                .to_string(),
         );
+        row.push(pass.skipped_ops.to_string());
+        row.push(pass.interrupted.to_string());
+        out.push_str(&row.join(","));
         out.push('\n');
     }
 
     out
 }
 
+/// `fail` if any gate check for this pass failed, empty when the pass had no checks.
+fn pass_gate_label(verdict: &GateVerdict, pass: &PassSummary) -> &'static str {
+    let mut checks = verdict.checks_for(pass).peekable();
+    if checks.peek().is_none() {
+        ""
+    } else if checks.all(|check| check.passed) {
+        "pass"
+    } else {
+        "fail"
+    }
+}
+
+/// Long format: one row per interval sample.
+fn samples_to_csv(report: &BenchmarkReport, samples: &[BenchSample]) -> String {
+    let with_steps = !report.steps.is_empty();
+    let mut out = String::new();
+    out.push_str(
+        "op,chunk_bytes,elapsed_secs,interval_secs,ops,throughput_mib_s,ops_per_sec,latency_us,skipped_ops",
+    );
+    out.push_str(if with_steps { ",step\n" } else { "\n" });
+
+    for sample in samples {
+        out.push_str(&format!(
+            "{},{},{:.6},{:.6},{},{:.6},{},{:.6},{}",
+            sample.op.label(),
+            sample.chunk_bytes,
+            sample.elapsed_secs,
+            sample.interval_secs,
+            sample.ops,
+            sample.throughput_mib_s,
+            sample.ops_per_sec,
+            sample.latency_us,
+            sample.skipped_ops,
+        ));
+        if with_steps {
+            out.push(',');
+            out.push_str(&csv_escape(
+                report
+                    .step_name(sample.step)
+                    .unwrap_or_default()
+                    .to_string(),
+            ));
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Escape text for XML/HTML content and attribute values.
+pub(super) fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 fn csv_escape(value: String) -> String {
     if value.contains(|c| [',', '"', '\n', '\r'].contains(&c)) {
         format!("\"{}\"", value.replace('"', "\"\""))
@@ -173,50 +637,47 @@ fn csv_escape(value: String) -> String {
     }
 }
 
-fn bench_mode_label(mode: BenchMode) -> &'static str {
-    match mode {
-        BenchMode::Read => "read",
-        BenchMode::Write => "write",
-        BenchMode::Both => "both",
-    }
-}
-
-fn unix_timestamp_secs() -> u64 {
+pub(super) fn unix_timestamp_secs() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|duration| duration.as_secs())
         .unwrap_or(0)
 }
 
-fn unix_timestamp_millis() -> u128 {
+pub(super) fn unix_timestamp_millis() -> u128 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|duration| duration.as_millis())
         .unwrap_or(0)
 }
 
+#[cfg(test)]
+impl BenchmarkReport {
+    /// One-second native run with only a read probe, for report and gate tests.
+    pub(crate) fn test_report(mode: BenchMode, sizes: &[usize], passes: Vec<PassSummary>) -> Self {
+        Self::new(
+            Connector::Native,
+            mode,
+            1,
+            sizes,
+            ProbeTargets::new(memflow::prelude::v1::Address::from(0x1000_u64), None, None),
+            passes,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::speedtest::BenchOp;
+    use crate::speedtest::gate::{GateCheck, GateCheckKind};
+    use crate::speedtest::{BenchError, BenchOp};
     use memflow::prelude::v1::*;
 
     fn summary() -> PassSummary {
         PassSummary {
-            op: BenchOp::Read,
-            chunk_bytes: 4096,
             min_mib_s: 10.0,
-            avg_mib_s: 20.0,
             max_mib_s: 30.0,
-            min_ops_s: 100.0,
-            avg_ops_s: 200.0,
-            max_ops_s: 300.0,
-            min_latency_us: 1.0,
-            avg_latency_us: 2.0,
-            max_latency_us: 3.0,
-            samples: 4,
-            total_ops: 1000,
-            measured_secs: 5.0,
+            ..PassSummary::test_read(4096, 20.0)
         }
     }
 
@@ -230,40 +691,301 @@ mod tests {
             infer_report_format(Path::new("out.JSON")).unwrap(),
             ReportFormat::Json
         );
+        assert_eq!(
+            infer_report_format(Path::new("out.md")).unwrap(),
+            ReportFormat::Markdown
+        );
+        assert_eq!(
+            infer_report_format(Path::new("out.htm")).unwrap(),
+            ReportFormat::Html
+        );
+        assert_eq!(
+            infer_report_format(Path::new("junit.xml")).unwrap(),
+            ReportFormat::Junit
+        );
         assert!(infer_report_format(Path::new("out.txt")).is_err());
     }
 
     #[test]
     fn serializes_report_as_json_and_csv() {
+        let report = BenchmarkReport::test_report(BenchMode::Read, &[4096], vec![summary()]);
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("\"connector\":\"native\""));
+        assert!(json.contains("\"avg_mib_s\":20.0"));
+
+        let csv = report_to_csv(&report);
+        assert!(csv.contains("version,connector,mode"));
+        assert!(csv.contains(",native,read,"));
+        assert!(csv.contains(",20.000000,"));
+    }
+
+    fn sample(elapsed_secs: f64) -> BenchSample {
+        BenchSample {
+            op: BenchOp::Read,
+            throughput_mib_s: 20.0,
+            ops_per_sec: 5000,
+            elapsed_secs,
+            interval_secs: 0.1,
+            ops: 500,
+            chunk_bytes: 4096,
+            latency_us: 2.0,
+            skipped_ops: 0,
+            step: None,
+        }
+    }
+
+    #[test]
+    fn raw_samples_are_opt_in_for_json_and_companion_csv() {
+        let report = BenchmarkReport::test_report(BenchMode::Read, &[4096], vec![summary()]);
+        let path = Path::new("reports/run.csv");
+        assert!(
+            !serde_json::to_string(&report)
+                .unwrap()
+                .contains("\"samples\":[")
+        );
+        assert_eq!(report.samples_companion_path(ReportFormat::Csv, path), None);
+
+        let report = report.with_samples(vec![sample(0.1), sample(0.2)]);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("\"samples\":[{\"op\":\"read\""));
+        assert_eq!(
+            report.samples_companion_path(ReportFormat::Csv, path),
+            Some(PathBuf::from("reports/run-samples.csv"))
+        );
+        assert_eq!(
+            report.samples_companion_path(ReportFormat::Json, path),
+            None
+        );
+
+        let csv = samples_to_csv(&report, report.samples.as_deref().unwrap());
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.contains("\nread,4096,0.200000,0.100000,500,20.000000,5000,2.000000,0\n"));
+    }
+
+    #[test]
+    fn csv_gate_column_is_empty_without_verdict() {
+        let report = BenchmarkReport::test_report(BenchMode::Read, &[4096], vec![summary()]);
+        let csv = report_to_csv(&report);
+        assert!(
+            csv.lines()
+                .next()
+                .unwrap()
+                .ends_with(",gate,skipped_ops,interrupted")
+        );
+        assert!(csv.lines().nth(1).unwrap().ends_with(",,0,false"));
+
+        let report = report.with_verdict(GateVerdict {
+            passed: false,
+            checks: vec![GateCheck {
+                op: BenchOp::Read,
+                chunk_bytes: 4096,
+                step: None,
+                kind: GateCheckKind::MinThroughput,
+                check: "avg MiB/s >= 100".to_string(),
+                actual: 20.0,
+                passed: false,
+            }],
+            unmatched_baseline_passes: Vec::new(),
+        });
+        let csv = report_to_csv(&report);
+        assert!(
+            csv.lines()
+                .next()
+                .unwrap()
+                .ends_with(",target_module_bytes,gate,skipped_ops,interrupted")
+        );
+        assert!(csv.lines().nth(1).unwrap().ends_with(",fail,0,false"));
+        assert!(
+            serde_json::to_string(&report)
+                .unwrap()
+                .contains("\"verdict\":{\"passed\":false")
+        );
+    }
+
+    #[test]
+    fn environment_is_serialized_into_json_and_csv() {
         let report = BenchmarkReport::new(
-            Connector::Native,
+            Connector::Pcileech,
             BenchMode::Read,
             1,
             &[4096],
             ProbeTargets::new(Address::from(0x1000_u64), None, None),
             vec![summary()],
         );
+        assert!(
+            !serde_json::to_string(&report)
+                .unwrap()
+                .contains("environment")
+        );
+        let csv = report_to_csv(&report);
+        assert!(csv.contains(",hostname,"));
+        assert!(
+            csv.lines()
+                .nth(1)
+                .unwrap()
+                .ends_with(",,,,,,,,,,,,,,,,0,false")
+        );
 
+        let report = report.with_environment(RunEnvironment {
+            hostname: "bench-01".to_string(),
+            cpu_model: "Intel(R) Core(TM) i7, 8 cores".to_string(),
+            device: Some("FPGA".to_string()),
+            target_pid: 4242,
+            ..RunEnvironment::default()
+        });
         let json = serde_json::to_string(&report).unwrap();
-        assert!(json.contains("\"connector\":\"native\""));
-        assert!(json.contains("\"avg_mib_s\":20.0"));
+        assert!(json.contains("\"environment\":{\"hostname\":\"bench-01\""));
+        let loaded: BenchmarkReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.environment, report.environment);
 
         let csv = report_to_csv(&report);
-        assert!(csv.contains("version,connector,mode"));
-        assert!(csv.contains(",native,read,"));
-        assert!(csv.contains(",20.000000,"));
+        let mut lines = csv.lines();
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .ends_with(",max_latency_us,step,labels,note,hostname,host_os,cpu_model,logical_cpus,memflow_version,connector_version,device,kernel_base,kernel_bytes,kernel_arch,target_process,target_pid,target_path,target_module_bytes,gate,skipped_ops,interrupted")
+        );
+        let row = lines.next().unwrap();
+        assert!(row.contains(",bench-01,,\"Intel(R) Core(TM) i7, 8 cores\",0,,,FPGA,"));
+        assert!(row.contains(",4242,"));
+        assert!(!json.contains("connect_timings"));
+        assert!(
+            overview_rows(&report)
+                .iter()
+                .all(|(name, _)| *name != "Connect timings")
+        );
     }
 
     #[test]
-    fn writes_report_to_nested_output_path() {
+    fn connect_timings_reach_json_and_overview() {
+        use crate::speedtest::{ConnectStep, ConnectTiming, ConnectTimings};
+
         let report = BenchmarkReport::new(
-            Connector::Native,
+            Connector::Pcileech,
             BenchMode::Read,
             1,
             &[4096],
             ProbeTargets::new(Address::from(0x1000_u64), None, None),
             vec![summary()],
+        )
+        .with_environment(RunEnvironment {
+            connect_timings: ConnectTimings(vec![
+                ConnectTiming {
+                    step: ConnectStep::InventoryScan,
+                    elapsed_us: 4_000,
+                },
+                ConnectTiming {
+                    step: ConnectStep::OsInit,
+                    elapsed_us: 1_500_000,
+                },
+            ]),
+            ..RunEnvironment::default()
+        });
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(
+            json.contains(r#""connect_timings":[{"step":"inventory_scan","elapsed_us":4000},"#),
+            "{json}"
         );
+        let loaded: BenchmarkReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.environment, report.environment);
+        assert!(overview_rows(&report).contains(&(
+            "Connect timings",
+            "inventory scan 4.0 ms, OS init 1.50 s (total 1.50 s)".to_string()
+        )));
+    }
+
+    #[test]
+    fn run_errors_keep_their_category_in_json_and_overview() {
+        let error = anyhow::Error::new(BenchError::new(
+            BenchErrorKind::ProcessNotFound,
+            "process explorer.exe not found",
+        ))
+        .context("reconnect attempts exhausted");
+        let report = BenchmarkReport::test_report(BenchMode::Read, &[4096], vec![summary()]);
+        assert!(!serde_json::to_string(&report).unwrap().contains("error"));
+
+        let report = report.with_error(&error);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(
+            json.contains("\"error\":{\"kind\":\"process_not_found\""),
+            "{json}"
+        );
+        let loaded: BenchmarkReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.error, report.error);
+        assert!(
+            overview_rows(&report).contains(&(
+                "Error",
+                "process not found: reconnect attempts exhausted: process explorer.exe not found"
+                    .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn labels_and_note_reach_json_csv_and_file_name() {
+        let report = BenchmarkReport::test_report(BenchMode::Read, &[4096], vec![summary()]);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(!json.contains("labels") && !json.contains("note"));
+        assert!(report_to_csv(&report).contains(",3.000000,,,,"));
+        let plain = default_report_path(ReportFormat::Csv, &report.labels);
+        assert!(
+            plain
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("dma-speedtest-1")
+        );
+
+        let labels = [
+            ("card".to_string(), "A1".to_string()),
+            ("slot".to_string(), "2".to_string()),
+        ]
+        .into_iter()
+        .collect::<RunLabels>();
+        let report = report
+            .with_labels(labels)
+            .with_note(" after firmware update ");
+        let loaded: BenchmarkReport =
+            serde_json::from_str(&serde_json::to_string(&report).unwrap()).unwrap();
+        assert_eq!(loaded.labels, report.labels);
+        assert_eq!(loaded.note.as_deref(), Some("after firmware update"));
+
+        let csv = report_to_csv(&report);
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().contains(",step,labels,note,"));
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .contains(",,\"card=A1, slot=2\",after firmware update,")
+        );
+
+        let path = default_report_path(ReportFormat::Json, &report.labels);
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with("dma-speedtest-card-A1_slot-2-"));
+        assert!(name.ends_with(".json"));
+    }
+
+    #[test]
+    fn reports_without_schema_version_load_as_version_one() {
+        let mut value = serde_json::to_value(BenchmarkReport::test_report(
+            BenchMode::Read,
+            &[4096],
+            vec![summary()],
+        ))
+        .unwrap();
+        value.as_object_mut().unwrap().remove("schema_version");
+
+        let report: BenchmarkReport = serde_json::from_value(value).unwrap();
+        assert_eq!(report.schema_version, 1);
+        assert!(report.samples.is_none());
+    }
+
+    #[test]
+    fn writes_report_to_nested_output_path() {
+        let report = BenchmarkReport::test_report(BenchMode::Read, &[4096], vec![summary()]);
         let dir = std::env::temp_dir().join(format!(
             "dma-speedtest-report-test-{}",
             unix_timestamp_millis()
@@ -275,6 +997,11 @@ mod tests {
         let written = std::fs::read_to_string(&path).unwrap();
         assert!(written.contains("\"connector\": \"native\""));
 
+        let loaded = load_report_from_path(&path).unwrap();
+        assert_eq!(loaded.schema_version, REPORT_SCHEMA_VERSION);
+        assert_eq!(loaded.passes.len(), 1);
+        assert_eq!(loaded.passes[0].avg_mib_s, 20.0);
+
         std::fs::remove_file(path).unwrap();
         std::fs::remove_dir(dir.join("nested")).unwrap();
         std::fs::remove_dir(dir).unwrap();
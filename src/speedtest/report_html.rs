@@ -0,0 +1,303 @@
+//! Self-contained HTML report with inline SVG charts, suitable for attaching to bug reports.
+
+use super::report::{
+    escape_xml, overview_rows, probe_rows, steps_table, summary_table, verdict_table,
+};
+use super::stats::summary_groups;
+use super::svg_chart::{ChartSeries, LineChart, PALETTE};
+use super::{BenchOp, BenchSample, BenchmarkReport, PassSummary};
+use crate::bench_config::format_chunk_size;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:24px;color:#2c3e50}\
+table{border-collapse:collapse;margin:8px 0 16px}\
+th,td{border:1px solid #bdc3c7;padding:4px 10px;text-align:left}\
+th{background:#ecf0f1}\
+.charts{display:flex;flex-wrap:wrap;gap:16px}\
+.pass{color:#27ae60;font-weight:bold}.fail{color:#c0392b;font-weight:bold}\
+.note{color:#7f8c8d}";
+
+pub(super) fn render(report: &BenchmarkReport) -> String {
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str(&format!(
+        "<title>DMA speed test report ({} / {})</title>\n<style>{STYLE}</style>\n</head>\n<body>\n",
+        escape_xml(&report.connector),
+        escape_xml(&report.mode)
+    ));
+    out.push_str("<h1>DMA speed test report</h1>\n");
+    push_key_value_table(&mut out, &overview_rows(report));
+
+    out.push_str("<h2>Probe targets</h2>\n");
+    push_key_value_table(&mut out, &probe_rows(report));
+
+    if !report.steps.is_empty() {
+        out.push_str("<h2>Scenario steps</h2>\n");
+        let (header, rows) = steps_table(report);
+        push_table(&mut out, &header, &rows);
+    }
+
+    out.push_str("<h2>Summary (weighted averages)</h2>\n");
+    if report.passes.is_empty() {
+        out.push_str("<p class=\"note\">No passes recorded.</p>\n");
+    }
+    for (step, passes) in report.step_sections() {
+        let group_tag = match step {
+            Some(step) => {
+                out.push_str(&format!("<h3>{}</h3>\n", escape_xml(&step.name)));
+                if passes.is_empty() {
+                    out.push_str("<p class=\"note\">No passes recorded.</p>\n");
+                    continue;
+                }
+                "h4"
+            }
+            None => "h3",
+        };
+        for group in summary_groups(&passes) {
+            if let Some(title) = group.title {
+                out.push_str(&format!(
+                    "<{group_tag}>{}</{group_tag}>\n",
+                    escape_xml(title.trim_end_matches(':'))
+                ));
+            }
+            let (header, rows) = summary_table(&group);
+            push_table(&mut out, &header, &rows);
+        }
+    }
+
+    if let Some(verdict) = &report.verdict {
+        out.push_str("<h2>Gate verdict</h2>\n");
+        let (header, rows) = verdict_table(report, verdict);
+        push_table(&mut out, &header, &rows);
+        let (label, class) = if verdict.passed {
+            ("PASS", "pass")
+        } else {
+            ("FAIL", "fail")
+        };
+        out.push_str(&format!("<p class=\"{class}\">Overall: {label}</p>\n"));
+    }
+
+    out.push_str("<h2>By chunk size</h2>\n<div class=\"charts\">\n");
+    out.push_str(&chunk_size_chart(report, "Throughput", "MiB/s", |p| p.avg_mib_s).render());
+    out.push('\n');
+    out.push_str(&chunk_size_chart(report, "Latency", "μs", |p| p.avg_latency_us).render());
+    out.push_str("\n</div>\n");
+
+    out.push_str("<h2>Per-pass time series</h2>\n");
+    match report.samples.as_deref() {
+        Some(samples) if !samples.is_empty() => {
+            out.push_str("<div class=\"charts\">\n");
+            out.push_str(
+                &time_series_chart(report, samples, "Throughput over time", "MiB/s", |s| {
+                    s.throughput_mib_s
+                })
+                .render(),
+            );
+            out.push('\n');
+            out.push_str(
+                &time_series_chart(report, samples, "Latency over time", "μs", |s| {
+                    s.latency_us
+                })
+                .render(),
+            );
+            out.push_str("\n</div>\n");
+        }
+        _ => out.push_str(
+            "<p class=\"note\">Raw samples were not recorded for this run; re-run with --include-samples (or tick Raw samples in the GUI) to chart each pass over time.</p>\n",
+        ),
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// One series per op (per scenario step and op), x on a log2 scale so power-of-two sizes are
+/// evenly spaced.
+fn chunk_size_chart<'a>(
+    report: &BenchmarkReport,
+    title: &'a str,
+    y_label: &'a str,
+    value: impl Fn(&PassSummary) -> f64,
+) -> LineChart<'a> {
+    let mut sizes = report
+        .passes
+        .iter()
+        .map(|pass| pass.chunk_bytes)
+        .collect::<Vec<_>>();
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    let ops = [BenchOp::Read, BenchOp::Write];
+    let value = &value;
+    let series = report
+        .step_sections()
+        .into_iter()
+        .enumerate()
+        .flat_map(|(section, (step, passes))| {
+            ops.into_iter().enumerate().filter_map(move |(idx, op)| {
+                let mut points = passes
+                    .iter()
+                    .filter(|pass| pass.op == op)
+                    .map(|pass| (log2_size(pass.chunk_bytes), value(pass)))
+                    .collect::<Vec<_>>();
+                points.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let label = match step {
+                    Some(step) => format!("{}: {}", step.name, op.label()),
+                    None => op.label().to_string(),
+                };
+                (!points.is_empty()).then(|| ChartSeries {
+                    label,
+                    color: PALETTE[(section * ops.len() + idx) % PALETTE.len()].to_string(),
+                    points,
+                    dashed: false,
+                })
+            })
+        })
+        .collect();
+
+    LineChart {
+        title,
+        x_label: "Chunk size",
+        y_label,
+        x_ticks: sizes
+            .into_iter()
+            .map(|size| (log2_size(size), format_chunk_size(size)))
+            .collect(),
+        series,
+    }
+}
+
+/// One series per `(op, chunk size)` pass (and scenario step), in run order.
+fn time_series_chart<'a>(
+    report: &BenchmarkReport,
+    samples: &[BenchSample],
+    title: &'a str,
+    y_label: &'a str,
+    value: impl Fn(&BenchSample) -> f64,
+) -> LineChart<'a> {
+    let mut passes: Vec<(Option<usize>, BenchOp, usize)> = Vec::new();
+    let mut series: Vec<ChartSeries> = Vec::new();
+    for sample in samples {
+        let key = (sample.step, sample.op, sample.chunk_bytes);
+        let point = (sample.elapsed_secs, value(sample));
+        match passes.iter().position(|pass| *pass == key) {
+            Some(idx) => series[idx].points.push(point),
+            None => {
+                let pass_label = format!("{} {}", sample.op.label(), format_chunk_size(key.2));
+                let label = match report.step_name(sample.step) {
+                    Some(step) => format!("{step}: {pass_label}"),
+                    None => pass_label,
+                };
+                series.push(ChartSeries {
+                    label,
+                    color: PALETTE[passes.len() % PALETTE.len()].to_string(),
+                    points: vec![point],
+                    dashed: false,
+                });
+                passes.push(key);
+            }
+        }
+    }
+
+    LineChart {
+        title,
+        x_label: "Elapsed (s)",
+        y_label,
+        x_ticks: Vec::new(),
+        series,
+    }
+}
+
+fn log2_size(chunk_bytes: usize) -> f64 {
+    (chunk_bytes.max(1) as f64).log2()
+}
+
+fn push_key_value_table(out: &mut String, rows: &[(&str, String)]) {
+    out.push_str("<table>\n");
+    for (key, value) in rows {
+        out.push_str(&format!(
+            "<tr><th>{}</th><td>{}</td></tr>\n",
+            escape_xml(key),
+            escape_xml(value)
+        ));
+    }
+    out.push_str("</table>\n");
+}
+
+fn push_table(out: &mut String, header: &[String], rows: &[Vec<String>]) {
+    out.push_str("<table>\n<tr>");
+    for cell in header {
+        out.push_str(&format!("<th>{}</th>", escape_xml(cell)));
+    }
+    out.push_str("</tr>\n");
+    for row in rows {
+        out.push_str("<tr>");
+        for cell in row {
+            out.push_str(&format!("<td>{}</td>", escape_xml(cell)));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::BenchMode;
+
+    fn pass(chunk_bytes: usize, mib_s: f64) -> PassSummary {
+        PassSummary::test_read(chunk_bytes, mib_s)
+    }
+
+    fn sample(chunk_bytes: usize, elapsed_secs: f64) -> BenchSample {
+        BenchSample {
+            op: BenchOp::Read,
+            throughput_mib_s: 20.0,
+            ops_per_sec: 5000,
+            elapsed_secs,
+            interval_secs: 0.1,
+            ops: 500,
+            chunk_bytes,
+            latency_us: 2.0,
+            skipped_ops: 0,
+            step: None,
+        }
+    }
+
+    fn report() -> BenchmarkReport {
+        BenchmarkReport::test_report(
+            BenchMode::Read,
+            &[4096, 65536],
+            vec![pass(4096, 20.0), pass(65536, 80.0)],
+        )
+    }
+
+    #[test]
+    fn html_report_is_self_contained_with_inline_charts() {
+        let html = render(&report());
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.trim_end().ends_with("</html>"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("src=\"http"));
+        assert!(!html.contains("href="));
+        assert_eq!(html.matches("<svg ").count(), 2);
+        assert!(html.contains("<td>64 KiB</td><td>80.00</td>"));
+        assert!(html.contains("re-run with --include-samples"));
+    }
+
+    #[test]
+    fn time_series_splits_samples_by_pass() {
+        let samples = [sample(4096, 0.1), sample(4096, 0.2), sample(65536, 0.1)];
+        let chart = time_series_chart(&report(), &samples, "Throughput", "MiB/s", |s| {
+            s.throughput_mib_s
+        });
+
+        assert_eq!(chart.series.len(), 2);
+        assert_eq!(chart.series[0].label, "read 4 KiB");
+        assert_eq!(chart.series[0].points.len(), 2);
+        assert_eq!(chart.series[1].label, "read 64 KiB");
+
+        let html = render(&report().with_samples(samples.to_vec()));
+        assert_eq!(html.matches("<svg ").count(), 4);
+    }
+}
@@ -0,0 +1,316 @@
+//! JUnit XML report for CI: one testcase per `(op, chunk size)` pass, prefixed with the scenario
+//! step name in scenario runs. Baseline passes the run never produced add failing testcases, and
+//! a run error adds an erroring `run` testcase.
+
+use super::gate::{GateCheck, GateCheckKind};
+use super::report::escape_xml;
+use super::{BenchmarkReport, PassSummary};
+use crate::bench_config::format_chunk_size;
+
+pub(super) fn render(report: &BenchmarkReport) -> String {
+    let cases = report
+        .passes
+        .iter()
+        .map(|pass| (pass, failure_reasons(report, pass)))
+        .collect::<Vec<_>>();
+    let missing = report
+        .verdict
+        .iter()
+        .flat_map(|verdict| &verdict.checks)
+        .filter(|check| check.kind == GateCheckKind::BaselineMissing)
+        .collect::<Vec<_>>();
+    let failures = cases
+        .iter()
+        .filter(|(_, reasons)| !reasons.is_empty())
+        .count()
+        + missing.len();
+    let errors = usize::from(report.error.is_some());
+    let tests = cases.len() + missing.len() + errors;
+    let time = report
+        .passes
+        .iter()
+        .map(|pass| pass.measured_secs)
+        .sum::<f64>();
+    let suite = format!("dma-speedtest.{}.{}", report.connector, report.mode);
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"dma-speedtest\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\">\n"
+    ));
+    out.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"0\" time=\"{time:.3}\">\n",
+        escape_xml(&suite),
+    ));
+
+    out.push_str("    <properties>\n");
+    let probes = &report.probes;
+    for (name, value) in [
+        ("version", report.version.clone()),
+        ("connector", report.connector.clone()),
+        ("mode", report.mode.clone()),
+        ("duration_secs", report.duration_secs.to_string()),
+        (
+            "generated_unix_secs",
+            report.generated_unix_secs.to_string(),
+        ),
+        ("read_addr", probes.read_addr.clone()),
+        ("write_addr", probes.write_addr.clone().unwrap_or_default()),
+        ("note", report.note.clone().unwrap_or_default()),
+        (
+            "error",
+            report
+                .error
+                .as_ref()
+                .map(|error| error.message.clone())
+                .unwrap_or_default(),
+        ),
+    ] {
+        push_property(&mut out, "      ", name, &value);
+    }
+    for (key, value) in &report.labels {
+        push_property(&mut out, "      ", &format!("label.{key}"), value);
+    }
+    out.push_str("    </properties>\n");
+
+    for (pass, reasons) in &cases {
+        let step = report
+            .step_name(pass.step)
+            .map(|name| format!("{}: ", escape_xml(name)))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "    <testcase name=\"{step}{} {}\" classname=\"dma-speedtest.{}.{}\" time=\"{:.3}\">\n",
+            pass.op.label(),
+            escape_xml(&format_chunk_size(pass.chunk_bytes)),
+            escape_xml(&report.connector),
+            pass.op.label(),
+            pass.measured_secs
+        ));
+        out.push_str("      <properties>\n");
+        for (name, value) in pass_properties(pass) {
+            push_property(&mut out, "        ", name, &value);
+        }
+        out.push_str("      </properties>\n");
+        if !reasons.is_empty() {
+            out.push_str(&format!(
+                "      <failure type=\"benchmark\" message=\"{}\">{}</failure>\n",
+                escape_xml(&reasons.join("; ")),
+                escape_xml(&reasons.join("\n"))
+            ));
+        }
+        out.push_str("    </testcase>\n");
+    }
+
+    for check in missing {
+        push_missing_case(&mut out, report, check);
+    }
+    if let Some(error) = &report.error {
+        let kind = error.kind.map_or("error", |kind| kind.label());
+        out.push_str(&format!(
+            "    <testcase name=\"run\" classname=\"dma-speedtest.{}\" time=\"{time:.3}\">\n",
+            escape_xml(&report.connector)
+        ));
+        out.push_str(&format!(
+            "      <error type=\"{}\" message=\"{}\">{}</error>\n",
+            escape_xml(kind),
+            escape_xml(&error.message),
+            escape_xml(&error.message)
+        ));
+        out.push_str("    </testcase>\n");
+    }
+
+    out.push_str("  </testsuite>\n</testsuites>\n");
+    out
+}
+
+/// Failing testcase for a baseline pass the run did not produce.
+fn push_missing_case(out: &mut String, report: &BenchmarkReport, check: &GateCheck) {
+    let step = report
+        .step_name(check.step)
+        .map(|name| format!("{}: ", escape_xml(name)))
+        .unwrap_or_default();
+    out.push_str(&format!(
+        "    <testcase name=\"{step}{} {}\" classname=\"dma-speedtest.{}.{}\" time=\"0.000\">\n",
+        check.op.label(),
+        escape_xml(&format_chunk_size(check.chunk_bytes)),
+        escape_xml(&report.connector),
+        check.op.label(),
+    ));
+    out.push_str(
+        "      <failure type=\"benchmark\" message=\"in baseline but not run\">in baseline but not run</failure>\n",
+    );
+    out.push_str("    </testcase>\n");
+}
+
+/// Why a pass fails: no samples, skipped ops, or a failed gate check.
+fn failure_reasons(report: &BenchmarkReport, pass: &PassSummary) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if pass.samples == 0 {
+        reasons.push("no samples recorded".to_string());
+    }
+    if pass.interrupted {
+        reasons
+            .push("interrupted by target loss; figures cover only the time before it".to_string());
+    }
+    if pass.skipped_ops > 0 {
+        reasons.push(format!(
+            "{} ops skipped after exhausting retries",
+            pass.skipped_ops
+        ));
+    }
+    if let Some(verdict) = &report.verdict {
+        reasons.extend(
+            verdict
+                .checks_for(pass)
+                .filter(|check| !check.passed)
+                .map(|check| format!("{} (actual {})", check.check, check.format_actual())),
+        );
+    }
+    reasons
+}
+
+fn pass_properties(pass: &PassSummary) -> [(&'static str, String); 15] {
+    [
+        ("op", pass.op.label().to_string()),
+        ("chunk_bytes", pass.chunk_bytes.to_string()),
+        ("samples", pass.samples.to_string()),
+        ("total_ops", pass.total_ops.to_string()),
+        ("skipped_ops", pass.skipped_ops.to_string()),
+        ("measured_secs", format!("{:.6}", pass.measured_secs)),
+        ("min_mib_s", format!("{:.6}", pass.min_mib_s)),
+        ("avg_mib_s", format!("{:.6}", pass.avg_mib_s)),
+        ("max_mib_s", format!("{:.6}", pass.max_mib_s)),
+        ("min_ops_s", format!("{:.6}", pass.min_ops_s)),
+        ("avg_ops_s", format!("{:.6}", pass.avg_ops_s)),
+        ("max_ops_s", format!("{:.6}", pass.max_ops_s)),
+        ("min_latency_us", format!("{:.6}", pass.min_latency_us)),
+        ("avg_latency_us", format!("{:.6}", pass.avg_latency_us)),
+        ("max_latency_us", format!("{:.6}", pass.max_latency_us)),
+    ]
+}
+
+fn push_property(out: &mut String, indent: &str, name: &str, value: &str) {
+    out.push_str(&format!(
+        "{indent}<property name=\"{}\" value=\"{}\"/>\n",
+        escape_xml(name),
+        escape_xml(value)
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::{BenchMode, BenchOp, GateCheck, GateCheckKind, GateVerdict};
+
+    fn pass(chunk_bytes: usize, samples: u64, skipped_ops: u64) -> PassSummary {
+        PassSummary {
+            samples,
+            skipped_ops,
+            ..PassSummary::test_read(chunk_bytes, 20.0)
+        }
+    }
+
+    #[test]
+    fn fails_testcases_for_missing_samples_skips_and_gate_checks() {
+        let report = BenchmarkReport::test_report(
+            BenchMode::Read,
+            &[4096, 8192, 16384, 32768],
+            vec![
+                PassSummary {
+                    interrupted: true,
+                    ..pass(4096, 50, 0)
+                },
+                pass(8192, 0, 0),
+                pass(16384, 50, 3),
+                pass(32768, 50, 0),
+            ],
+        )
+        .with_verdict(GateVerdict {
+            passed: false,
+            checks: vec![GateCheck {
+                op: BenchOp::Read,
+                chunk_bytes: 32768,
+                step: None,
+                kind: GateCheckKind::MinThroughput,
+                check: "avg MiB/s >= 100".to_string(),
+                actual: 20.0,
+                passed: false,
+            }],
+            unmatched_baseline_passes: Vec::new(),
+        });
+
+        let xml = render(&report);
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites"));
+        assert!(xml.contains("tests=\"4\" failures=\"4\""));
+        assert_eq!(xml.matches("<testcase ").count(), 4);
+        assert_eq!(xml.matches("<failure ").count(), 4);
+        assert!(xml.contains(
+            "message=\"interrupted by target loss; figures cover only the time before it\""
+        ));
+        assert!(xml.contains("message=\"no samples recorded\""));
+        assert!(xml.contains("message=\"3 ops skipped after exhausting retries\""));
+        assert!(xml.contains("message=\"avg MiB/s &gt;= 100 (actual 20.00)\""));
+        assert!(xml.contains(
+            "<testcase name=\"read 4 KiB\" classname=\"dma-speedtest.native.read\" time=\"5.000\">"
+        ));
+        assert!(xml.contains("<property name=\"avg_mib_s\" value=\"20.000000\"/>"));
+    }
+
+    #[test]
+    fn missing_baseline_passes_add_failing_testcases() {
+        let report =
+            BenchmarkReport::test_report(BenchMode::Read, &[4096, 8192], vec![pass(4096, 50, 0)])
+                .with_verdict(GateVerdict {
+                    passed: false,
+                    checks: vec![GateCheck {
+                        op: BenchOp::Read,
+                        chunk_bytes: 8192,
+                        step: None,
+                        kind: GateCheckKind::BaselineMissing,
+                        check: "pass present in run".to_string(),
+                        actual: 0.0,
+                        passed: false,
+                    }],
+                    unmatched_baseline_passes: vec!["read 8 KiB".to_string()],
+                });
+
+        let xml = render(&report);
+
+        assert!(xml.contains("tests=\"2\" failures=\"1\" errors=\"0\""));
+        assert!(xml.contains(
+            "<testcase name=\"read 8 KiB\" classname=\"dma-speedtest.native.read\" time=\"0.000\">\n      <failure type=\"benchmark\" message=\"in baseline but not run\">"
+        ));
+    }
+
+    #[test]
+    fn run_errors_add_an_erroring_testcase() {
+        let report =
+            BenchmarkReport::test_report(BenchMode::Read, &[4096], vec![pass(4096, 50, 0)])
+                .with_error(&anyhow::Error::new(crate::speedtest::TargetLost {
+                    kind: crate::speedtest::TargetLossKind::ProcessExited,
+                    detail: "explorer.exe exited".to_string(),
+                }));
+
+        let xml = render(&report);
+
+        assert!(xml.contains("tests=\"2\" failures=\"0\" errors=\"1\""));
+        assert!(xml.contains("<testcase name=\"run\" classname=\"dma-speedtest.native\""));
+        assert!(xml.contains(
+            "<error type=\"target lost\" message=\"target process exited (explorer.exe exited)\">"
+        ));
+    }
+
+    #[test]
+    fn label_keys_are_escaped_in_property_names() {
+        let labels = [("a\"b".to_string(), "1".to_string())]
+            .into_iter()
+            .collect();
+        let report =
+            BenchmarkReport::test_report(BenchMode::Read, &[4096], vec![pass(4096, 50, 0)])
+                .with_labels(labels);
+
+        let xml = render(&report);
+
+        assert!(xml.contains("<property name=\"label.a&quot;b\" value=\"1\"/>"));
+    }
+}
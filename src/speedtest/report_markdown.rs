@@ -0,0 +1,191 @@
+//! Markdown report: run overview, probe targets, scenario steps, grouped summary tables and
+//! gate verdict.
+
+use super::BenchmarkReport;
+use super::report::{overview_rows, probe_rows, steps_table, summary_table, verdict_table};
+use super::stats::summary_groups;
+
+pub(super) fn render(report: &BenchmarkReport) -> String {
+    let mut out = String::from("# DMA speed test report\n\n");
+    push_key_value_table(&mut out, &overview_rows(report));
+
+    out.push_str("\n## Probe targets\n\n");
+    push_key_value_table(&mut out, &probe_rows(report));
+
+    if !report.steps.is_empty() {
+        out.push_str("\n## Scenario steps\n\n");
+        let (header, rows) = steps_table(report);
+        push_table(&mut out, &header, &rows);
+    }
+
+    out.push_str("\n## Summary (weighted averages)\n");
+    if report.passes.is_empty() {
+        out.push_str("\nNo passes recorded.\n");
+    }
+    for (step, passes) in report.step_sections() {
+        let group_heading = match step {
+            Some(step) => {
+                out.push_str(&format!("\n### {}\n", step.name));
+                if passes.is_empty() {
+                    out.push_str("\nNo passes recorded.\n");
+                    continue;
+                }
+                "####"
+            }
+            None => "###",
+        };
+        for group in summary_groups(&passes) {
+            if let Some(title) = group.title {
+                out.push_str(&format!(
+                    "\n{group_heading} {}\n",
+                    title.trim_end_matches(':')
+                ));
+            }
+            out.push('\n');
+            let (header, rows) = summary_table(&group);
+            push_table(&mut out, &header, &rows);
+        }
+    }
+
+    if let Some(verdict) = &report.verdict {
+        out.push_str("\n## Gate verdict\n\n");
+        let (header, rows) = verdict_table(report, verdict);
+        push_table(&mut out, &header, &rows);
+        out.push_str(&format!(
+            "\n**Overall: {}**\n",
+            if verdict.passed { "PASS" } else { "FAIL" }
+        ));
+    }
+
+    if let Some(samples) = &report.samples {
+        out.push_str(&format!(
+            "\n_{} raw interval samples recorded; export JSON or CSV to get the time series._\n",
+            samples.len()
+        ));
+    }
+
+    out
+}
+
+fn push_key_value_table(out: &mut String, rows: &[(&str, String)]) {
+    let header = ["Field".to_string(), "Value".to_string()];
+    let rows = rows
+        .iter()
+        .map(|(key, value)| vec![key.to_string(), value.clone()])
+        .collect::<Vec<_>>();
+    push_table(out, &header, &rows);
+}
+
+fn push_table(out: &mut String, header: &[String], rows: &[Vec<String>]) {
+    push_row(out, header);
+    push_row(out, &vec!["---".to_string(); header.len()]);
+    for row in rows {
+        push_row(out, row);
+    }
+}
+
+fn push_row(out: &mut String, cells: &[String]) {
+    out.push('|');
+    for cell in cells {
+        out.push(' ');
+        // A raw newline would end the row; notes and error messages can span lines.
+        let cell = cell
+            .replace('|', "\\|")
+            .replace("\r\n", "<br>")
+            .replace('\n', "<br>");
+        out.push_str(&cell);
+        out.push_str(" |");
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::{BenchMode, BenchOp, Connector, PassSummary, ProbeTargets, ReportStep};
+    use memflow::prelude::v1::*;
+
+    fn pass(op: BenchOp, chunk_bytes: usize) -> PassSummary {
+        PassSummary {
+            op,
+            ..PassSummary::test_read(chunk_bytes, 20.0)
+        }
+    }
+
+    #[test]
+    fn groups_mixed_runs_by_op_with_probe_details() {
+        let report = BenchmarkReport::new(
+            Connector::Native,
+            BenchMode::Both,
+            1,
+            &[4096],
+            ProbeTargets::new(
+                Address::from(0x1000_u64),
+                Some(Address::from(0x2000_u64)),
+                Some(0x1000),
+            ),
+            vec![pass(BenchOp::Read, 4096), pass(BenchOp::Write, 4096)],
+        );
+
+        let md = render(&report);
+
+        assert!(md.starts_with("# DMA speed test report\n"));
+        assert!(md.contains("| Connector | native |"));
+        assert!(md.contains("## Probe targets"));
+        assert!(md.contains("| Write region | 4 KiB"));
+        assert!(md.contains("### Read summary\n\n| Size | Avg MiB/s | Avg reads/s |"));
+        assert!(md.contains("### Write summary\n\n| Size | Avg MiB/s | Avg writes/s |"));
+        assert!(md.contains("| 4 KiB | 20.00 | 200 | 2.0 | 4 |"));
+        assert!(!md.contains("## Gate verdict"));
+    }
+
+    #[test]
+    fn scenario_reports_get_a_section_per_step() {
+        let step_pass = |op, chunk_bytes, step| PassSummary {
+            step: Some(step),
+            ..pass(op, chunk_bytes)
+        };
+        let report = BenchmarkReport::new(
+            Connector::Native,
+            BenchMode::Both,
+            30,
+            &[8, 4096],
+            ProbeTargets::new(
+                Address::from(0x1000_u64),
+                Some(Address::from(0x2000_u64)),
+                Some(0x1000),
+            ),
+            vec![
+                step_pass(BenchOp::Read, 4096, 0),
+                step_pass(BenchOp::Write, 4096, 1),
+                step_pass(BenchOp::Read, 8, 2),
+            ],
+        )
+        .with_steps(vec![
+            ReportStep::new("reads", BenchMode::Read, 10, &[4096]),
+            ReportStep::new("writes", BenchMode::Write, 30, &[4096]),
+            ReportStep::new("ping", BenchMode::Read, 5, &[8]),
+        ]);
+
+        let md = render(&report);
+
+        assert!(md.contains("| Scenario steps | 3 |"));
+        assert!(!md.contains("Duration per pass"));
+        assert!(md.contains("## Scenario steps\n\n| # | Step | Mode | Duration | Chunk sizes |"));
+        assert!(md.contains("| 2 | writes | write | 30 s | 4 KiB |"));
+        assert!(md.contains("### reads\n\n| Op | Size |"));
+        assert!(md.contains("### ping\n\n| Op | Size |"));
+        assert!(md.contains("| read | 8 B | 20.00 |"));
+    }
+
+    #[test]
+    fn multi_line_notes_stay_in_one_table_row() {
+        let report =
+            BenchmarkReport::test_report(BenchMode::Read, &[4096], vec![pass(BenchOp::Read, 4096)])
+                .with_note("after firmware update\r\nslot 2 | riser");
+
+        let md = render(&report);
+
+        assert!(md.contains("| Note | after firmware update<br>slot 2 \\| riser |\n"));
+    }
+}
@@ -0,0 +1,184 @@
+//! Multi-step scenarios: ordered benchmark steps run over one connection into one report.
+
+use super::profile::load_settings_file;
+use super::{BenchMode, ReportStep};
+use crate::bench_config::{DEFAULT_CHUNK_SIZES, max_chunk_bytes_in_list, validate_chunk_sizes};
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub steps: Vec<ScenarioStep>,
+}
+
+/// One entry of a scenario's `steps` list; unset fields use the CLI defaults.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScenarioStep {
+    /// Section title in reports; `step N` when unset.
+    pub name: Option<String>,
+    pub mode: BenchMode,
+    /// Chunk sizes in bytes.
+    pub sizes: Vec<usize>,
+    /// Seconds per chunk size.
+    pub duration: u64,
+    /// Idle seconds before the step starts, e.g. to let the device settle.
+    pub pause: u64,
+    /// Keep this step's interval samples in the report.
+    pub include_samples: bool,
+}
+
+impl Default for ScenarioStep {
+    fn default() -> Self {
+        Self {
+            name: None,
+            mode: BenchMode::Read,
+            sizes: DEFAULT_CHUNK_SIZES.to_vec(),
+            duration: 10,
+            pause: 0,
+            include_samples: false,
+        }
+    }
+}
+
+impl ScenarioStep {
+    /// `name`, or `step N` counting from 1.
+    pub fn label(&self, index: usize) -> String {
+        match self.name.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => format!("step {}", index + 1),
+        }
+    }
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> Result<Self> {
+        let scenario: Self = load_settings_file(path, "scenario")?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.steps.is_empty() {
+            bail!("scenario has no steps");
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            let label = step.label(index);
+            if !(1..=60).contains(&step.duration) {
+                bail!("scenario step {label:?}: duration must be between 1 and 60 seconds");
+            }
+            validate_chunk_sizes(&step.sizes)
+                .map_err(|e| anyhow::anyhow!("scenario step {label:?}: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Mode to connect with: a write probe is set up when any step writes.
+    pub fn connection_mode(&self) -> BenchMode {
+        let reads = self.steps.iter().any(|step| step.mode != BenchMode::Write);
+        let writes = self.steps.iter().any(|step| step.mode.needs_write_target());
+        match (reads, writes) {
+            (true, true) => BenchMode::Both,
+            (false, true) => BenchMode::Write,
+            _ => BenchMode::Read,
+        }
+    }
+
+    /// Largest chunk of the write steps, for sizing the write probe region.
+    pub fn max_write_chunk_bytes(&self) -> usize {
+        let sizes = self
+            .steps
+            .iter()
+            .filter(|step| step.mode.needs_write_target())
+            .flat_map(|step| step.sizes.iter().copied())
+            .collect::<Vec<_>>();
+        max_chunk_bytes_in_list(&sizes)
+    }
+
+    /// Every chunk size used, sorted, for the report overview.
+    pub fn all_sizes(&self) -> Vec<usize> {
+        let mut sizes = self
+            .steps
+            .iter()
+            .flat_map(|step| step.sizes.iter().copied())
+            .collect::<Vec<_>>();
+        sizes.sort_unstable();
+        sizes.dedup();
+        sizes
+    }
+
+    pub fn longest_duration(&self) -> u64 {
+        self.steps
+            .iter()
+            .map(|step| step.duration)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn report_steps(&self) -> Vec<ReportStep> {
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                ReportStep::new(&step.label(index), step.mode, step.duration, &step.sizes)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_ordered_steps_with_defaults() {
+        let dir =
+            std::env::temp_dir().join(format!("dma-speedtest-scenario-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("nightly.toml");
+        std::fs::write(
+            &path,
+            r#"
+[[steps]]
+name = "reads"
+sizes = [4096, 131072]
+
+[[steps]]
+mode = "write"
+sizes = [4096]
+duration = 30
+pause = 2
+
+[[steps]]
+name = "ping"
+sizes = [8]
+duration = 5
+include_samples = true
+"#,
+        )
+        .unwrap();
+
+        let scenario = Scenario::load(&path).unwrap();
+        assert_eq!(scenario.steps.len(), 3);
+        assert_eq!(scenario.steps[0].duration, 10);
+        assert_eq!(scenario.steps[1].label(1), "step 2");
+        assert_eq!(scenario.connection_mode(), BenchMode::Both);
+        assert_eq!(
+            scenario.max_write_chunk_bytes(),
+            crate::speedtest::MIN_WRITE_REGION_BYTES
+        );
+        assert_eq!(scenario.all_sizes(), vec![8, 4096, 131072]);
+        let steps = scenario.report_steps();
+        assert_eq!(steps[2].name, "ping");
+        assert_eq!(steps[1].mode, "write");
+
+        std::fs::write(&path, "[[steps]]\nduration = 90\n").unwrap();
+        let err = Scenario::load(&path).unwrap_err().to_string();
+        assert!(err.contains("step 1"), "{err}");
+        std::fs::write(&path, "steps = []\n").unwrap();
+        assert!(Scenario::load(&path).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,548 @@
+//! [`BenchmarkSession`]: the pass loop shared by the CLI, the GUI and the control API.
+//!
+//! A session runs its steps on a connected [`SpeedTest`], reconnects after a lost target when
+//! allowed, restores the write probe and returns the [`BenchmarkReport`]. Frontends follow the
+//! run through a [`BenchObserver`].
+
+use super::{
+    BenchMode, BenchObserver, BenchOp, BenchSample, BenchmarkReport, Connector, PassAggregator,
+    PassSummary, RECONNECT_DELAY, ReportStep, RunLabels, Scenario, SpeedTest, TargetLost,
+    WriteTargetPolicy,
+};
+use crate::bench_config::{max_chunk_bytes_in_list, validate_chunk_sizes};
+use anyhow::{Result, bail};
+use parking_lot::Mutex;
+use std::{sync::Arc, time::Duration};
+
+/// A run that stopped on an error, with the passes it finished before the error.
+#[derive(Debug)]
+pub struct RunFailure {
+    /// Finished passes, with [`BenchmarkReport::error`] set from `error`.
+    pub report: Box<BenchmarkReport>,
+    pub error: anyhow::Error,
+}
+
+/// One block of passes run with the same mode, sizes and duration: the whole run, or one
+/// scenario step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionStep {
+    /// Index into the report's scenario steps; `None` for single-step sessions.
+    pub index: Option<usize>,
+    pub name: String,
+    pub mode: BenchMode,
+    pub sizes: Vec<usize>,
+    pub duration_secs: u64,
+    pub pause_secs: u64,
+    pub include_samples: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct BenchmarkSession {
+    connector: Connector,
+    device: String,
+    write_policy: WriteTargetPolicy,
+    mode: BenchMode,
+    sizes: Vec<usize>,
+    duration_secs: u64,
+    steps: Vec<SessionStep>,
+    scenario: bool,
+    reconnect_attempts: u32,
+    labels: RunLabels,
+    note: Option<String>,
+}
+
+impl BenchmarkSession {
+    /// Run `mode` over `sizes` for `duration_secs` each.
+    pub fn new(
+        connector: Connector,
+        mode: BenchMode,
+        sizes: Vec<usize>,
+        duration_secs: u64,
+    ) -> Self {
+        let step = SessionStep {
+            index: None,
+            name: String::new(),
+            mode,
+            sizes: sizes.clone(),
+            duration_secs,
+            pause_secs: 0,
+            include_samples: false,
+        };
+        Self {
+            connector,
+            device: String::new(),
+            write_policy: WriteTargetPolicy::default(),
+            mode,
+            sizes,
+            duration_secs,
+            steps: vec![step],
+            scenario: false,
+            reconnect_attempts: 0,
+            labels: RunLabels::new(),
+            note: None,
+        }
+    }
+
+    /// Run the scenario's steps in order over one connection.
+    pub fn from_scenario(connector: Connector, scenario: &Scenario) -> Self {
+        let steps = scenario
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| SessionStep {
+                index: Some(index),
+                name: step.label(index),
+                mode: step.mode,
+                sizes: step.sizes.clone(),
+                duration_secs: step.duration,
+                pause_secs: step.pause,
+                include_samples: step.include_samples,
+            })
+            .collect();
+        Self {
+            mode: scenario.connection_mode(),
+            sizes: scenario.all_sizes(),
+            duration_secs: scenario.longest_duration(),
+            steps,
+            scenario: true,
+            ..Self::new(connector, BenchMode::Read, Vec::new(), 0)
+        }
+    }
+
+    /// PCILeech device string; ignored by other connectors.
+    pub fn with_device(mut self, device: &str) -> Self {
+        self.device = device.to_string();
+        self
+    }
+
+    pub fn with_write_policy(mut self, write_policy: WriteTargetPolicy) -> Self {
+        self.write_policy = write_policy;
+        self
+    }
+
+    /// Reconnect up to `attempts` times after the target is lost, resuming with the next pass.
+    pub fn with_reconnect_attempts(mut self, attempts: u32) -> Self {
+        self.reconnect_attempts = attempts;
+        self
+    }
+
+    /// Keep every step's interval samples in the report (scenario steps may already opt in).
+    pub fn with_include_samples(mut self, include_samples: bool) -> Self {
+        for step in &mut self.steps {
+            step.include_samples |= include_samples;
+        }
+        self
+    }
+
+    pub fn with_labels(mut self, labels: RunLabels) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn with_note(mut self, note: &str) -> Self {
+        self.note = Some(note.to_string());
+        self
+    }
+
+    pub fn connector(&self) -> Connector {
+        self.connector
+    }
+
+    /// Mode to connect with; covers every step.
+    pub fn mode(&self) -> BenchMode {
+        self.mode
+    }
+
+    /// Every chunk size the run uses, as listed in the report.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// Seconds per size; the longest step for scenarios.
+    pub fn duration_secs(&self) -> u64 {
+        self.duration_secs
+    }
+
+    pub fn steps(&self) -> &[SessionStep] {
+        &self.steps
+    }
+
+    /// `true` for sessions built with [`Self::from_scenario`].
+    pub fn is_scenario(&self) -> bool {
+        self.scenario
+    }
+
+    /// Largest chunk of the steps that write, for sizing the write probe region; read-only steps
+    /// never touch it.
+    pub fn max_write_chunk_bytes(&self) -> usize {
+        let sizes = self
+            .steps
+            .iter()
+            .filter(|step| step.mode.needs_write_target())
+            .flat_map(|step| step.sizes.iter().copied())
+            .collect::<Vec<_>>();
+        max_chunk_bytes_in_list(&sizes)
+    }
+
+    /// Check the settings without touching the device.
+    pub fn validate(&self) -> Result<()> {
+        for step in &self.steps {
+            if !(1..=60).contains(&step.duration_secs) {
+                bail!("duration must be between 1 and 60 seconds");
+            }
+            validate_chunk_sizes(&step.sizes)?;
+        }
+        if matches!(self.connector, Connector::Pcileech) && self.device.trim().is_empty() {
+            bail!("PCILeech requires a non-empty device string");
+        }
+        self.write_policy.validate()
+    }
+
+    /// Connect with the session's connector, mode and write policy, reporting each stage.
+    pub fn connect(&self, observer: &dyn BenchObserver) -> Result<SpeedTest> {
+        let device = match self.connector {
+            Connector::Pcileech => self.device.trim().to_string(),
+            _ => self.device.clone(),
+        };
+        SpeedTest::connect_observed(
+            self.connector,
+            device,
+            self.mode,
+            self.max_write_chunk_bytes(),
+            &self.write_policy,
+            observer,
+        )
+        .inspect_err(|e| observer.error(e))
+    }
+
+    /// Run every step on `test`, restore the write probe and build the report.
+    ///
+    /// Cancelling `test` ends the run early with the passes measured so far. On error the
+    /// observer has still seen every finished pass, then [`BenchObserver::error`], and the
+    /// [`RunFailure`] carries the report of those passes.
+    pub async fn run(
+        &self,
+        test: SpeedTest,
+        observer: Arc<dyn BenchObserver>,
+    ) -> std::result::Result<BenchmarkReport, RunFailure> {
+        let mut test = test;
+        let mut reconnects_left = self.reconnect_attempts;
+        let mut summaries = Vec::new();
+        let mut samples = Vec::new();
+        let mut run_error = None;
+
+        'passes: for step in &self.steps {
+            if let Some(index) = step.index {
+                observer.step_start(index, step);
+                if step.pause_secs > 0 {
+                    tokio::time::sleep(Duration::from_secs(step.pause_secs)).await;
+                }
+            }
+            for &size in &step.sizes {
+                for &op in step.mode.ops_for_size() {
+                    if test.is_cancelled() {
+                        break 'passes;
+                    }
+                    observer.pass_start(&test, op, size, step);
+
+                    let recorder = PassRecorder::new(observer.as_ref(), step, op, size);
+                    let pass_result = test
+                        .run_test_with_size(
+                            op,
+                            size,
+                            Duration::from_secs(step.duration_secs),
+                            &recorder,
+                        )
+                        .await;
+                    let (mut summary, pass_samples) = recorder.finish();
+                    let lost = pass_result
+                        .as_ref()
+                        .err()
+                        .and_then(TargetLost::from_error)
+                        .cloned();
+                    summary.interrupted = lost.is_some();
+                    observer.pass_end(&summary);
+                    summaries.push(summary);
+                    samples.extend(pass_samples);
+
+                    let Err(e) = pass_result else {
+                        continue;
+                    };
+                    if let Some(lost) = lost
+                        && reconnects_left > 0
+                    {
+                        self.restore_write_probe(&test, observer.as_ref());
+                        match reconnect_after_loss(&test, &lost, &mut reconnects_left, &*observer)
+                            .await
+                        {
+                            Ok(next) => {
+                                test = next;
+                                continue;
+                            }
+                            Err(e) => run_error = Some(e),
+                        }
+                    } else {
+                        run_error = Some(e);
+                    }
+                    break 'passes;
+                }
+            }
+        }
+
+        self.restore_write_probe(&test, observer.as_ref());
+        match run_error {
+            Some(e) => {
+                observer.error(&e);
+                Err(RunFailure {
+                    report: Box::new(self.report(&test, summaries, samples).with_error(&e)),
+                    error: e,
+                })
+            }
+            None => Ok(self.report(&test, summaries, samples)),
+        }
+    }
+
+    /// Report for `summaries` measured on `test`, e.g. the passes a failed run finished.
+    pub fn report(
+        &self,
+        test: &SpeedTest,
+        summaries: Vec<PassSummary>,
+        samples: Vec<BenchSample>,
+    ) -> BenchmarkReport {
+        let mut report = BenchmarkReport::new(
+            self.connector,
+            self.mode,
+            self.duration_secs,
+            &self.sizes,
+            test.probe_targets(),
+            summaries,
+        )
+        .with_environment(test.environment().clone())
+        .with_labels(self.labels.clone());
+        if self.scenario {
+            report = report.with_steps(
+                self.steps
+                    .iter()
+                    .map(|step| {
+                        ReportStep::new(&step.name, step.mode, step.duration_secs, &step.sizes)
+                    })
+                    .collect(),
+            );
+        }
+        if let Some(note) = &self.note {
+            report = report.with_note(note);
+        }
+        if self.steps.iter().any(|step| step.include_samples) {
+            report = report.with_samples(samples);
+        }
+        report
+    }
+
+    fn restore_write_probe(&self, test: &SpeedTest, observer: &dyn BenchObserver) {
+        if self.steps.iter().any(|step| step.mode.needs_write_target()) {
+            observer.write_restored(&test.restore_write_target());
+        }
+    }
+}
+
+/// Spend reconnect attempts until one succeeds; the interrupted pass keeps its partial samples
+/// and is marked [`PassSummary::interrupted`].
+async fn reconnect_after_loss(
+    test: &SpeedTest,
+    lost: &TargetLost,
+    attempts_left: &mut u32,
+    observer: &dyn BenchObserver,
+) -> Result<SpeedTest> {
+    observer.reconnecting(lost);
+
+    let mut last_error = None;
+    while *attempts_left > 0 && !test.is_cancelled() {
+        *attempts_left -= 1;
+        tokio::time::sleep(RECONNECT_DELAY).await;
+        match test.reconnect_observed(observer) {
+            Ok(next) => {
+                observer.reconnected(&next);
+                return Ok(next);
+            }
+            Err(e) => {
+                observer.reconnect_failed(&e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    Err(match last_error {
+        Some(e) => e.context(format!("{lost}; reconnect attempts exhausted")),
+        None => lost.clone().into(),
+    })
+}
+
+/// Tags one pass's samples with its step, aggregates them and forwards them to the observer.
+struct PassRecorder<'a> {
+    observer: &'a dyn BenchObserver,
+    step: Option<usize>,
+    include_samples: bool,
+    pass: Mutex<(PassAggregator, Vec<BenchSample>)>,
+}
+
+impl<'a> PassRecorder<'a> {
+    fn new(
+        observer: &'a dyn BenchObserver,
+        step: &SessionStep,
+        op: BenchOp,
+        chunk_bytes: usize,
+    ) -> Self {
+        Self {
+            observer,
+            step: step.index,
+            include_samples: step.include_samples,
+            pass: Mutex::new((PassAggregator::new(op, chunk_bytes), Vec::new())),
+        }
+    }
+
+    /// The pass summary and, if the step keeps them, its samples.
+    fn finish(self) -> (PassSummary, Vec<BenchSample>) {
+        let (aggregator, samples) = self.pass.into_inner();
+        let mut summary = aggregator.finish();
+        summary.step = self.step;
+        (summary, samples)
+    }
+}
+
+impl BenchObserver for PassRecorder<'_> {
+    fn sample(&self, sample: &BenchSample) {
+        let sample = BenchSample {
+            step: self.step,
+            ..*sample
+        };
+        self.observer.sample(&sample);
+        let mut pass = self.pass.lock();
+        pass.0.push(&sample);
+        if self.include_samples {
+            pass.1.push(sample);
+        }
+    }
+
+    fn warning(&self, message: &str) {
+        self.observer.warning(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speedtest::ScenarioStep;
+
+    #[test]
+    fn scenario_sessions_cover_every_step() {
+        let scenario = Scenario {
+            steps: vec![
+                ScenarioStep {
+                    sizes: vec![131072, 4096],
+                    ..ScenarioStep::default()
+                },
+                ScenarioStep {
+                    name: Some("writes".to_string()),
+                    mode: BenchMode::Write,
+                    sizes: vec![4096],
+                    duration: 30,
+                    include_samples: true,
+                    ..ScenarioStep::default()
+                },
+            ],
+        };
+        let session = BenchmarkSession::from_scenario(Connector::Native, &scenario)
+            .with_include_samples(false);
+        assert!(session.is_scenario());
+        assert_eq!(session.mode(), BenchMode::Both);
+        assert_eq!(session.sizes(), [4096, 131072]);
+        assert_eq!(session.duration_secs(), 30);
+        assert_eq!(
+            session.max_write_chunk_bytes(),
+            crate::speedtest::MIN_WRITE_REGION_BYTES
+        );
+        assert_eq!(session.steps()[0].name, "step 1");
+        assert_eq!(session.steps()[1].index, Some(1));
+        assert!(!session.steps()[0].include_samples);
+        assert!(session.steps()[1].include_samples);
+        assert!(session.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_bad_settings_before_connecting() {
+        let session = BenchmarkSession::new(Connector::Native, BenchMode::Read, vec![4096], 10);
+        assert!(!session.is_scenario());
+        assert!(session.validate().is_ok());
+
+        let err = BenchmarkSession::new(Connector::Native, BenchMode::Read, vec![4096], 0)
+            .validate()
+            .unwrap_err();
+        assert!(err.to_string().contains("between 1 and 60"), "{err}");
+        assert!(
+            BenchmarkSession::new(Connector::Native, BenchMode::Read, vec![0], 10)
+                .validate()
+                .is_err()
+        );
+        let err = BenchmarkSession::new(Connector::Pcileech, BenchMode::Read, vec![4096], 10)
+            .with_device("  ")
+            .validate()
+            .unwrap_err();
+        assert!(err.to_string().contains("device"), "{err}");
+    }
+
+    #[derive(Default)]
+    struct Recording {
+        samples: Mutex<Vec<BenchSample>>,
+        warnings: Mutex<Vec<String>>,
+    }
+
+    impl BenchObserver for Recording {
+        fn sample(&self, sample: &BenchSample) {
+            self.samples.lock().push(*sample);
+        }
+
+        fn warning(&self, message: &str) {
+            self.warnings.lock().push(message.to_string());
+        }
+    }
+
+    #[test]
+    fn pass_recorder_tags_aggregates_and_forwards_samples() {
+        let observer = Recording::default();
+        let step = SessionStep {
+            index: Some(2),
+            name: "step 3".to_string(),
+            mode: BenchMode::Read,
+            sizes: vec![4096],
+            duration_secs: 1,
+            pause_secs: 0,
+            include_samples: true,
+        };
+        let recorder = PassRecorder::new(&observer, &step, BenchOp::Read, 4096);
+        let sample = BenchSample {
+            op: BenchOp::Read,
+            throughput_mib_s: 100.0,
+            ops_per_sec: 25600,
+            elapsed_secs: 0.1,
+            interval_secs: 0.1,
+            ops: 2560,
+            chunk_bytes: 4096,
+            latency_us: 39.0,
+            skipped_ops: 0,
+            step: None,
+        };
+        recorder.sample(&sample);
+        recorder.sample(&sample);
+        recorder.warning("warning: retrying");
+
+        let (summary, samples) = recorder.finish();
+        assert_eq!(summary.step, Some(2));
+        assert_eq!(summary.samples, 2);
+        assert_eq!(summary.total_ops, 5120);
+        assert_eq!(samples.len(), 2);
+        assert!(samples.iter().all(|sample| sample.step == Some(2)));
+        assert_eq!(observer.samples.lock().len(), 2);
+        assert_eq!(observer.samples.lock()[0].step, Some(2));
+        assert_eq!(*observer.warnings.lock(), ["warning: retrying"]);
+    }
+}
@@ -2,9 +2,8 @@
 
 use super::bench::{BenchOp, BenchStats};
 use crate::bench_config::format_chunk_size;
-use tokio::sync::mpsc;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct BenchSample {
     pub op: BenchOp,
     pub throughput_mib_s: f64,
@@ -14,6 +13,12 @@ pub struct BenchSample {
     pub ops: u64,
     pub chunk_bytes: usize,
     pub latency_us: f64,
+    /// Absent in reports written before skipped ops were tracked.
+    #[serde(default)]
+    pub skipped_ops: u64,
+    /// Index into the report's scenario steps; absent outside scenario runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<usize>,
 }
 
 impl BenchSample {
@@ -27,11 +32,28 @@ impl BenchSample {
             ops: stats.ops,
             chunk_bytes: stats.chunk_bytes,
             latency_us: stats.latency_us,
+            skipped_ops: stats.skipped_ops,
+            step: None,
+        }
+    }
+
+    /// Back to the worker's stats type, e.g. for samples received from a remote agent.
+    pub fn to_stats(&self) -> BenchStats {
+        BenchStats {
+            op: self.op,
+            chunk_bytes: self.chunk_bytes,
+            elapsed_secs: self.elapsed_secs,
+            interval_secs: self.interval_secs,
+            ops: self.ops,
+            throughput_mib_s: self.throughput_mib_s,
+            ops_per_sec: self.ops_per_sec,
+            latency_us: self.latency_us,
+            skipped_ops: self.skipped_ops,
         }
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PassSummary {
     pub op: BenchOp,
     pub chunk_bytes: usize,
@@ -47,6 +69,15 @@ pub struct PassSummary {
     pub samples: u64,
     pub total_ops: u64,
     pub measured_secs: f64,
+    /// Ops skipped after exhausting retries (partial I/O).
+    #[serde(default)]
+    pub skipped_ops: u64,
+    /// Index into the report's scenario steps; absent outside scenario runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<usize>,
+    /// The target was lost mid-pass; the figures cover only the time before the loss.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub interrupted: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +96,7 @@ pub struct PassAggregator {
     samples: u64,
     total_ops: u64,
     measured_secs: f64,
+    skipped_ops: u64,
 }
 
 impl PassAggregator {
@@ -84,6 +116,7 @@ impl PassAggregator {
             samples: 0,
             total_ops: 0,
             measured_secs: 0.0,
+            skipped_ops: 0,
         }
     }
 
@@ -106,6 +139,7 @@ impl PassAggregator {
         self.samples += 1;
         self.total_ops = self.total_ops.saturating_add(sample.ops);
         self.measured_secs += interval_secs;
+        self.skipped_ops = self.skipped_ops.saturating_add(sample.skipped_ops);
     }
 
     pub fn is_for(&self, op: BenchOp, chunk_bytes: usize) -> bool {
@@ -133,6 +167,9 @@ impl PassAggregator {
             samples: n,
             total_ops: self.total_ops,
             measured_secs: self.measured_secs,
+            skipped_ops: self.skipped_ops,
+            step: None,
+            interrupted: false,
         }
     }
 
@@ -173,9 +210,49 @@ pub fn format_live_sample_line(sample: &BenchSample) -> String {
     live_sample_columns(sample).join("  ")
 }
 
+/// Summary rows split by op when a run mixed reads and writes (CLI table, Markdown/HTML reports).
+pub struct SummaryGroup<'a> {
+    pub title: Option<&'static str>,
+    pub op: Option<BenchOp>,
+    pub rows: Vec<&'a PassSummary>,
+}
+
+pub fn summary_groups(summaries: &[PassSummary]) -> Vec<SummaryGroup<'_>> {
+    let has_read = summaries.iter().any(|s| matches!(s.op, BenchOp::Read));
+    let has_write = summaries.iter().any(|s| matches!(s.op, BenchOp::Write));
+
+    if has_read && has_write {
+        return vec![
+            SummaryGroup {
+                title: Some("Read summary:"),
+                op: Some(BenchOp::Read),
+                rows: summaries
+                    .iter()
+                    .filter(|s| matches!(s.op, BenchOp::Read))
+                    .collect(),
+            },
+            SummaryGroup {
+                title: Some("Write summary:"),
+                op: Some(BenchOp::Write),
+                rows: summaries
+                    .iter()
+                    .filter(|s| matches!(s.op, BenchOp::Write))
+                    .collect(),
+            },
+        ];
+    }
+
+    vec![SummaryGroup {
+        title: None,
+        op: None,
+        rows: summaries.iter().collect(),
+    }]
+}
+
 /// Drain a stats channel until closed; invoke `on_sample` for each live update.
+#[deprecated(note = "implement BenchObserver::sample and aggregate with PassAggregator instead")]
 pub async fn drain_stats_channel(
-    mut rx: mpsc::Receiver<BenchStats>,
+    mut rx: tokio::sync::mpsc::Receiver<BenchStats>,
     op: BenchOp,
     chunk_bytes: usize,
     mut on_sample: impl FnMut(&BenchSample),
@@ -202,6 +279,32 @@ pub fn format_console_log_line(sample: &BenchSample) -> String {
     )
 }
 
+#[cfg(test)]
+impl PassSummary {
+    /// Read pass averaging `mib_s` with fixed rates and latencies, for report and gate tests.
+    pub(crate) fn test_read(chunk_bytes: usize, mib_s: f64) -> Self {
+        Self {
+            op: BenchOp::Read,
+            chunk_bytes,
+            min_mib_s: mib_s,
+            avg_mib_s: mib_s,
+            max_mib_s: mib_s,
+            min_ops_s: 100.0,
+            avg_ops_s: 200.0,
+            max_ops_s: 300.0,
+            min_latency_us: 1.0,
+            avg_latency_us: 2.0,
+            max_latency_us: 3.0,
+            samples: 4,
+            total_ops: 1000,
+            measured_secs: 5.0,
+            skipped_ops: 0,
+            step: None,
+            interrupted: false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,6 +325,8 @@ mod tests {
             ops,
             chunk_bytes: 4096,
             latency_us: latency,
+            skipped_ops: 0,
+            step: None,
         }
     }
 
@@ -244,4 +349,48 @@ mod tests {
         assert_eq!(summary.max_ops_s, 3000.0);
         assert_eq!(summary.avg_latency_us, 28.0);
     }
+
+    fn pass_summary(op: BenchOp, chunk_bytes: usize) -> PassSummary {
+        PassSummary {
+            op,
+            ..PassSummary::test_read(chunk_bytes, 1.0)
+        }
+    }
+
+    #[test]
+    fn summary_groups_split_read_and_write_rows_when_mixed() {
+        let summaries = vec![
+            pass_summary(BenchOp::Read, 4096),
+            pass_summary(BenchOp::Write, 4096),
+            pass_summary(BenchOp::Read, 8192),
+            pass_summary(BenchOp::Write, 8192),
+        ];
+
+        let groups = summary_groups(&summaries);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].title, Some("Read summary:"));
+        assert_eq!(groups[1].title, Some("Write summary:"));
+        assert_eq!(groups[0].op, Some(BenchOp::Read));
+        assert_eq!(groups[1].op, Some(BenchOp::Write));
+        assert_eq!(groups[0].rows.len(), 2);
+        assert_eq!(groups[1].rows.len(), 2);
+        assert!(groups[0].rows.iter().all(|s| s.op == BenchOp::Read));
+        assert!(groups[1].rows.iter().all(|s| s.op == BenchOp::Write));
+    }
+
+    #[test]
+    fn summary_groups_keep_single_op_summary_unsplit() {
+        let summaries = vec![
+            pass_summary(BenchOp::Read, 4096),
+            pass_summary(BenchOp::Read, 8192),
+        ];
+
+        let groups = summary_groups(&summaries);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].title, None);
+        assert_eq!(groups[0].op, None);
+        assert_eq!(groups[0].rows.len(), 2);
+    }
 }
@@ -0,0 +1,118 @@
+//! Machine-readable run events for NDJSON live streaming (`--stream ndjson`).
+
+use super::{
+    BenchMode, BenchOp, BenchSample, Connector, PassSummary, ProbeTargets, RunEnvironment,
+    SpeedTest,
+};
+use memflow::prelude::v1::Address;
+
+/// One line of the live feed; the `event` field names the variant.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum StreamEvent {
+    /// First event of a run: its settings and the probe targets the session connected to.
+    RunStart {
+        connector: Connector,
+        mode: BenchMode,
+        duration_secs: u64,
+        sizes: Vec<usize>,
+        read_addr: u64,
+        write_addr: Option<u64>,
+        write_region_bytes: Option<u64>,
+        environment: Box<RunEnvironment>,
+    },
+    /// A `--scenario` step begins; its passes and samples carry `step: index`.
+    StepStart {
+        index: usize,
+        name: String,
+        mode: BenchMode,
+    },
+    PassStart {
+        op: BenchOp,
+        chunk_bytes: usize,
+        duration_secs: u64,
+    },
+    Sample(BenchSample),
+    Warning {
+        message: String,
+    },
+    PassSummary(PassSummary),
+    /// Last event of a run; `error` is set when it failed.
+    RunEnd {
+        error: Option<String>,
+    },
+}
+
+impl StreamEvent {
+    pub fn run_start(
+        test: &SpeedTest,
+        connector: Connector,
+        mode: BenchMode,
+        duration_secs: u64,
+        sizes: &[usize],
+    ) -> Self {
+        let probes = test.probe_targets();
+        StreamEvent::RunStart {
+            connector,
+            mode,
+            duration_secs,
+            sizes: sizes.to_vec(),
+            read_addr: probes.read_addr.to_umem(),
+            write_addr: probes.write_addr.map(|addr| addr.to_umem()),
+            write_region_bytes: probes.write_region_bytes,
+            environment: Box::new(test.environment().clone()),
+        }
+    }
+
+    /// Probe targets of a [`StreamEvent::RunStart`], for building reports on the viewer side.
+    pub fn probe_targets(&self) -> Option<ProbeTargets> {
+        match self {
+            StreamEvent::RunStart {
+                read_addr,
+                write_addr,
+                write_region_bytes,
+                ..
+            } => Some(ProbeTargets::new(
+                Address::from(*read_addr),
+                write_addr.map(Address::from),
+                *write_region_bytes,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Compact JSON without a trailing newline.
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|e| {
+            format!(r#"{{"event":"warning","message":"could not encode event: {e}"}}"#)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_serialize_as_tagged_single_lines() {
+        let start = StreamEvent::PassStart {
+            op: BenchOp::Write,
+            chunk_bytes: 4096,
+            duration_secs: 5,
+        };
+        assert_eq!(
+            start.to_json_line(),
+            r#"{"event":"pass_start","op":"write","chunk_bytes":4096,"duration_secs":5}"#
+        );
+
+        let warning = StreamEvent::Warning {
+            message: "line one\nline two".to_string(),
+        };
+        let line = warning.to_json_line();
+        assert!(!line.contains('\n'));
+        assert!(matches!(
+            serde_json::from_str::<StreamEvent>(&line).unwrap(),
+            StreamEvent::Warning { message } if message == "line one\nline two"
+        ));
+    }
+}
@@ -0,0 +1,296 @@
+//! Minimal inline SVG line charts for self-contained reports (no scripts, no external assets).
+
+use super::report::escape_xml;
+use std::fmt::Write as _;
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 300.0;
+const MARGIN_LEFT: f64 = 64.0;
+const MARGIN_RIGHT: f64 = 16.0;
+const MARGIN_TOP: f64 = 36.0;
+const MARGIN_BOTTOM: f64 = 48.0;
+const Y_TICKS: usize = 4;
+/// Series with more points than this are drawn without point markers.
+const MAX_MARKED_POINTS: usize = 32;
+
+/// Colors assigned to series in order.
+pub(super) const PALETTE: [&str; 8] = [
+    "#2980b9", "#e67e22", "#27ae60", "#8e44ad", "#c0392b", "#16a085", "#d35400", "#7f8c8d",
+];
+
+pub(super) struct ChartSeries {
+    pub label: String,
+    /// Any SVG color, e.g. `#2980b9`.
+    pub color: String,
+    pub points: Vec<(f64, f64)>,
+    /// Dashed stroke for reference series.
+    pub dashed: bool,
+}
+
+pub(super) struct LineChart<'a> {
+    pub title: &'a str,
+    pub x_label: &'a str,
+    pub y_label: &'a str,
+    /// Fixed x tick positions and labels; evenly spaced numeric ticks when empty.
+    pub x_ticks: Vec<(f64, String)>,
+    pub series: Vec<ChartSeries>,
+}
+
+impl LineChart<'_> {
+    pub fn render(&self) -> String {
+        self.render_sized(WIDTH as u32)
+    }
+
+    /// Same chart scaled to `width_px` wide, keeping the aspect ratio.
+    pub fn render_sized(&self, width_px: u32) -> String {
+        let height_px = (f64::from(width_px) * HEIGHT / WIDTH).round();
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {WIDTH} {HEIGHT}" width="{width_px}" height="{height_px}" font-family="sans-serif" font-size="11">"#
+        );
+        let _ = write!(
+            svg,
+            r##"<rect width="{WIDTH}" height="{HEIGHT}" fill="#ffffff"/><text x="{}" y="20" text-anchor="middle" font-size="13" font-weight="bold">{}</text>"##,
+            WIDTH / 2.0,
+            escape_xml(self.title)
+        );
+
+        let points = self
+            .series
+            .iter()
+            .flat_map(|series| series.points.iter().copied())
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .collect::<Vec<_>>();
+        if points.is_empty() {
+            let _ = write!(
+                svg,
+                r##"<text x="{}" y="{}" text-anchor="middle" fill="#7f8c8d">No data</text></svg>"##,
+                WIDTH / 2.0,
+                HEIGHT / 2.0
+            );
+            return svg;
+        }
+
+        let (x_min, x_max) = padded_range(
+            points
+                .iter()
+                .map(|(x, _)| *x)
+                .chain(self.x_ticks.iter().map(|(x, _)| *x)),
+        );
+        let y_max = points.iter().map(|(_, y)| *y).fold(0.0_f64, f64::max);
+        let y_max = if y_max > 0.0 { y_max * 1.1 } else { 1.0 };
+        let plot = PlotArea {
+            x_min,
+            x_max,
+            y_max,
+        };
+
+        self.render_axes(&mut svg, &plot);
+        for series in &self.series {
+            render_series(&mut svg, &plot, series);
+        }
+        self.render_legend(&mut svg);
+
+        svg.push_str("</svg>");
+        svg
+    }
+
+    fn render_axes(&self, svg: &mut String, plot: &PlotArea) {
+        let left = MARGIN_LEFT;
+        let right = WIDTH - MARGIN_RIGHT;
+        let bottom = HEIGHT - MARGIN_BOTTOM;
+
+        for i in 0..=Y_TICKS {
+            let value = plot.y_max * i as f64 / Y_TICKS as f64;
+            let y = plot.y(value);
+            let _ = write!(
+                svg,
+                r##"<line x1="{left}" y1="{y:.1}" x2="{right}" y2="{y:.1}" stroke="#ecf0f1"/><text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"##,
+                left - 6.0,
+                y + 4.0,
+                format_tick(value)
+            );
+        }
+
+        let x_ticks = if self.x_ticks.is_empty() {
+            (0..=4)
+                .map(|i| {
+                    let value = plot.x_min + (plot.x_max - plot.x_min) * i as f64 / 4.0;
+                    (value, format_tick(value))
+                })
+                .collect()
+        } else {
+            self.x_ticks.clone()
+        };
+        for (value, label) in &x_ticks {
+            let x = plot.x(*value);
+            let _ = write!(
+                svg,
+                r##"<line x1="{x:.1}" y1="{bottom}" x2="{x:.1}" y2="{:.1}" stroke="#7f8c8d"/><text x="{x:.1}" y="{:.1}" text-anchor="middle">{}</text>"##,
+                bottom + 4.0,
+                bottom + 16.0,
+                escape_xml(label)
+            );
+        }
+
+        let _ = write!(
+            svg,
+            r##"<polyline points="{left},{MARGIN_TOP} {left},{bottom} {right},{bottom}" fill="none" stroke="#2c3e50"/>"##
+        );
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+            (left + right) / 2.0,
+            HEIGHT - 10.0,
+            escape_xml(self.x_label)
+        );
+        let _ = write!(
+            svg,
+            r#"<text transform="translate(14 {:.1}) rotate(-90)" text-anchor="middle">{}</text>"#,
+            (MARGIN_TOP + bottom) / 2.0,
+            escape_xml(self.y_label)
+        );
+    }
+
+    fn render_legend(&self, svg: &mut String) {
+        let mut x = WIDTH - MARGIN_RIGHT;
+        for series in self.series.iter().rev() {
+            // Rough text width; good enough to keep entries from overlapping.
+            let width = 18.0 + series.label.chars().count() as f64 * 6.5;
+            x -= width;
+            let _ = write!(
+                svg,
+                r#"<rect x="{x:.1}" y="{:.1}" width="10" height="10" fill="{}"/><text x="{:.1}" y="{:.1}">{}</text>"#,
+                MARGIN_TOP - 12.0,
+                series.color,
+                x + 14.0,
+                MARGIN_TOP - 3.0,
+                escape_xml(&series.label)
+            );
+        }
+    }
+}
+
+struct PlotArea {
+    x_min: f64,
+    x_max: f64,
+    y_max: f64,
+}
+
+impl PlotArea {
+    fn x(&self, value: f64) -> f64 {
+        let span = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+        MARGIN_LEFT + (value - self.x_min) / (self.x_max - self.x_min) * span
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        let span = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+        HEIGHT - MARGIN_BOTTOM - value / self.y_max * span
+    }
+}
+
+fn render_series(svg: &mut String, plot: &PlotArea, series: &ChartSeries) {
+    let coords = series
+        .points
+        .iter()
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+        .map(|&(x, y)| (plot.x(x), plot.y(y)))
+        .collect::<Vec<_>>();
+    if coords.is_empty() {
+        return;
+    }
+
+    let path = coords
+        .iter()
+        .map(|(x, y)| format!("{x:.1},{y:.1}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let dash = if series.dashed {
+        r#" stroke-dasharray="6 4" stroke-opacity="0.6""#
+    } else {
+        ""
+    };
+    let _ = write!(
+        svg,
+        r#"<polyline points="{path}" fill="none" stroke="{}" stroke-width="2"{dash}/>"#,
+        series.color
+    );
+    if series.dashed {
+        return;
+    }
+    if coords.len() <= MAX_MARKED_POINTS {
+        for (x, y) in coords {
+            let _ = write!(
+                svg,
+                r#"<circle cx="{x:.1}" cy="{y:.1}" r="3" fill="{}"/>"#,
+                series.color
+            );
+        }
+    }
+}
+
+/// Min/max of `values`, widened when all values are equal so the scale stays finite.
+fn padded_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    if min < max {
+        (min, max)
+    } else {
+        (min - 1.0, max + 1.0)
+    }
+}
+
+fn format_tick(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude >= 100.0 || magnitude == 0.0 {
+        format!("{value:.0}")
+    } else if magnitude >= 10.0 {
+        format!("{value:.1}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_series_with_escaped_labels() {
+        let chart = LineChart {
+            title: "Throughput <read>",
+            x_label: "Chunk size",
+            y_label: "MiB/s",
+            x_ticks: vec![(12.0, "4 KiB".to_string()), (13.0, "8 KiB".to_string())],
+            series: vec![ChartSeries {
+                label: "read & write".to_string(),
+                color: PALETTE[0].to_string(),
+                points: vec![(12.0, 10.0), (13.0, 20.0)],
+                dashed: false,
+            }],
+        };
+
+        let svg = chart.render();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains("Throughput &lt;read&gt;"));
+        assert!(svg.contains(">read &amp; write<"));
+        assert!(svg.contains(">8 KiB<"));
+        assert_eq!(svg.matches("<circle").count(), 2);
+    }
+
+    #[test]
+    fn empty_chart_says_no_data() {
+        let chart = LineChart {
+            title: "Latency",
+            x_label: "s",
+            y_label: "μs",
+            x_ticks: Vec::new(),
+            series: Vec::new(),
+        };
+        assert!(chart.render().contains(">No data<"));
+    }
+}
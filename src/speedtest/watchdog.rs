@@ -0,0 +1,211 @@
+//! Mid-run integrity checks for the write probe region.
+//!
+//! The connect-time canary only proves the region was idle once. Between write ops the watchdog
+//! reads the whole verified footprint back and compares it with what the benchmark last left
+//! there, and re-checks that the pages are still mapped writable.
+
+use super::error::{BenchError, BenchErrorKind};
+use super::mem_io::{self, IoAttempt};
+use super::probe_targets::ProbeTargets;
+use anyhow::Result;
+use memflow::prelude::v1::*;
+use std::time::{Duration, Instant};
+
+/// Minimum time between footprint read-back checks during a write pass.
+pub const WATCHDOG_CONTENT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Minimum time between page mapping checks during a write pass.
+pub const WATCHDOG_MAPPING_INTERVAL: Duration = Duration::from_secs(2);
+
+pub struct WriteWatchdog {
+    base: Address,
+    expected: Vec<u8>,
+    last_content_check: Instant,
+    last_mapping_check: Instant,
+}
+
+impl WriteWatchdog {
+    /// `previous` is the footprint as the last pass left it; `payload` is this pass's write buffer.
+    pub fn new(base: Address, previous: &[u8], payload: &[u8]) -> Self {
+        let now = Instant::now();
+        Self {
+            base,
+            expected: expected_after_write(previous, payload),
+            last_content_check: now,
+            last_mapping_check: now,
+        }
+    }
+
+    /// Footprint contents once this pass has written at least once.
+    pub fn expected(&self) -> &[u8] {
+        &self.expected
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.last_content_check.elapsed() >= WATCHDOG_CONTENT_INTERVAL
+            || self.last_mapping_check.elapsed() >= WATCHDOG_MAPPING_INTERVAL
+    }
+
+    /// Run whichever checks are due. Call only after a successful write in this pass.
+    pub fn check(&mut self, process: &mut IntoProcessInstanceArcBox<'_>) -> Result<()> {
+        if self.last_mapping_check.elapsed() >= WATCHDOG_MAPPING_INTERVAL {
+            self.check_mapping(process)?;
+            self.last_mapping_check = Instant::now();
+        }
+        if self.last_content_check.elapsed() >= WATCHDOG_CONTENT_INTERVAL {
+            self.check_contents(process)?;
+            self.last_content_check = Instant::now();
+        }
+        Ok(())
+    }
+
+    fn check_contents(&self, process: &mut IntoProcessInstanceArcBox<'_>) -> Result<()> {
+        let mut actual = vec![0u8; self.expected.len()];
+        if mem_io::read_raw_into_with_retry(process, self.base, &mut actual) != IoAttempt::Ok {
+            // A transient read failure is not evidence of foreign writes; retry next interval.
+            return Ok(());
+        }
+
+        if let Some((offset, differing)) = first_mismatch(&self.expected, &actual) {
+            return Err(write_target_compromised(format!(
+                "write probe region at {} was modified by the target process ({differing} B differ starting at +{offset:#x}); aborting write benchmark and skipping restore so live data is not overwritten",
+                ProbeTargets::format_va(self.base)
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_mapping(&self, process: &mut IntoProcessInstanceArcBox<'_>) -> Result<()> {
+        let start = self.base.to_umem();
+        let end = start.saturating_add(self.expected.len() as umem);
+        let map = process.mapped_mem_range_vec(0, self.base, Address::from(end));
+        if let Err(detail) = mapping_covers_writable(&map, start, end) {
+            return Err(write_target_compromised(format!(
+                "write probe region mapping at {} changed ({detail}); aborting write benchmark and skipping restore",
+                ProbeTargets::format_va(self.base)
+            )));
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn write_target_compromised(detail: String) -> anyhow::Error {
+    BenchError::new(BenchErrorKind::WriteTargetCompromised, detail).into()
+}
+
+/// Footprint contents after `payload` overwrote its prefix.
+fn expected_after_write(previous: &[u8], payload: &[u8]) -> Vec<u8> {
+    let mut expected = previous.to_vec();
+    let overlap = expected.len().min(payload.len());
+    expected[..overlap].copy_from_slice(&payload[..overlap]);
+    expected
+}
+
+/// First differing offset and the number of differing bytes.
+fn first_mismatch(expected: &[u8], actual: &[u8]) -> Option<(usize, usize)> {
+    let first = expected
+        .iter()
+        .zip(actual)
+        .position(|(expected, actual)| expected != actual)?;
+    let differing = expected[first..]
+        .iter()
+        .zip(&actual[first..])
+        .filter(|(expected, actual)| expected != actual)
+        .count();
+    Some((first, differing))
+}
+
+/// `Ok` when `[start, end)` is fully covered by writable, non page-table mappings.
+fn mapping_covers_writable(map: &[MemoryRange], start: u64, end: u64) -> Result<(), String> {
+    let mut ranges = map
+        .iter()
+        .map(|range| {
+            (
+                range.0.to_umem(),
+                range.0.to_umem().saturating_add(range.1),
+                range.2,
+            )
+        })
+        .filter(|(range_start, range_end, _)| *range_start < end && start < *range_end)
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|(range_start, _, _)| *range_start);
+
+    let mut cursor = start;
+    for (range_start, range_end, page_type) in ranges {
+        if range_start > cursor {
+            return Err(format!("{:#x} is no longer mapped", cursor));
+        }
+        if !page_type.contains(PageType::WRITEABLE) || page_type.contains(PageType::PAGE_TABLE) {
+            return Err(format!(
+                "{:#x} is now {}",
+                range_start.max(start),
+                super::write_target::format_page_type(page_type)
+            ));
+        }
+        cursor = cursor.max(range_end);
+        if cursor >= end {
+            return Ok(());
+        }
+    }
+
+    Err(format!("{:#x} is no longer mapped", cursor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_image_overlays_payload_on_previous_footprint() {
+        let previous = [9u8; 8];
+        let payload = [1u8, 2, 3];
+
+        assert_eq!(
+            expected_after_write(&previous, &payload),
+            vec![1, 2, 3, 9, 9, 9, 9, 9]
+        );
+    }
+
+    #[test]
+    fn first_mismatch_reports_offset_and_count() {
+        assert_eq!(first_mismatch(&[1, 2, 3, 4], &[1, 2, 3, 4]), None);
+        assert_eq!(first_mismatch(&[1, 2, 3, 4], &[1, 0, 3, 0]), Some((1, 2)));
+    }
+
+    #[test]
+    fn mapping_check_accepts_contiguous_writable_ranges() {
+        let map = vec![
+            CTup3(Address::from(0x1000_u64), 0x1000, PageType::WRITEABLE),
+            CTup3(
+                Address::from(0x2000_u64),
+                0x1000,
+                PageType::WRITEABLE | PageType::NOEXEC,
+            ),
+        ];
+        assert!(mapping_covers_writable(&map, 0x1000, 0x3000).is_ok());
+    }
+
+    #[test]
+    fn mapping_check_detects_unmapped_or_read_only_pages() {
+        let gap = vec![CTup3(
+            Address::from(0x1000_u64),
+            0x1000,
+            PageType::WRITEABLE,
+        )];
+        assert!(
+            mapping_covers_writable(&gap, 0x1000, 0x3000)
+                .unwrap_err()
+                .contains("no longer mapped")
+        );
+
+        let read_only = vec![
+            CTup3(Address::from(0x1000_u64), 0x1000, PageType::WRITEABLE),
+            CTup3(Address::from(0x2000_u64), 0x1000, PageType::READ_ONLY),
+        ];
+        assert!(
+            mapping_covers_writable(&read_only, 0x1000, 0x3000)
+                .unwrap_err()
+                .contains("read-only")
+        );
+    }
+}
@@ -1,9 +1,15 @@
 use super::bench::{BenchMode, BenchOp, BenchStats};
 use super::connector::Connector;
+use super::environment::RunEnvironment;
+use super::error::{BenchError, BenchErrorKind};
 use super::initialization::SpeedTestInit;
+use super::liveness::{self, FailureStreak};
 use super::mem_io::{self, IoAttempt, MAX_IO_RETRIES};
+use super::observer::{BenchObserver, NoopObserver};
 use super::probe_targets::ProbeTargets;
-use super::write_target;
+use super::stats::BenchSample;
+use super::watchdog::{WriteWatchdog, write_target_compromised};
+use super::write_target::{self, SafeWriteRegion, WriteCandidate, WriteTargetPolicy};
 use anyhow::Result;
 use memflow::prelude::v1::*;
 use std::{
@@ -13,15 +19,40 @@ use std::{
     },
     time::Duration,
 };
+use tokio::task;
+
+use super::initialization;
 
 /// Optional hook for retry / skip warnings (GUI console, etc.).
+#[deprecated(note = "implement BenchObserver::warning instead")]
 pub type BenchWarnFn = Arc<dyn Fn(&str) + Send + Sync>;
 
 /// Optional hook before each op/size pass (GUI console, CLI headers, etc.).
+#[deprecated(note = "implement BenchObserver::pass_start instead")]
 pub type BenchPassStartFn = Arc<dyn Fn(BenchOp, usize) + Send + Sync>;
-use tokio::{sync::mpsc, task};
 
-use super::initialization;
+/// What happened to the write probe's original bytes after a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteRestoreOutcome {
+    /// Session had no write probe.
+    NotNeeded,
+    Restored,
+    /// The watchdog saw the target use the region; restoring would clobber live data.
+    SkippedForeignData,
+    /// The process or device went away mid-run; the original mapping no longer exists.
+    SkippedTargetLost,
+}
+
+/// Pause between reconnect attempts after a [`TargetLost`](super::TargetLost) error.
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Everything needed to re-run connection setup after the target is lost.
+struct ConnectParams {
+    connector: Connector,
+    pcileech_device: String,
+    max_chunk_bytes: usize,
+    write_policy: WriteTargetPolicy,
+}
 
 #[derive(Clone)]
 pub struct SpeedTest {
@@ -31,6 +62,15 @@ pub struct SpeedTest {
     write_region_bytes: Option<umem>,
     write_verified_bytes: Option<usize>,
     write_restore_bytes: Option<Arc<[u8]>>,
+    /// Write footprint as the benchmark last left it (starts as the original bytes).
+    write_footprint: Option<Arc<parking_lot::Mutex<Vec<u8>>>>,
+    /// Set when the watchdog saw foreign writes or a mapping change in the write region.
+    write_compromised: Arc<AtomicBool>,
+    write_explicit: bool,
+    environment: Arc<RunEnvironment>,
+    /// Set once a pass fails with [`TargetLost`](super::TargetLost).
+    target_lost: Arc<AtomicBool>,
+    connect: Arc<ConnectParams>,
     mode: BenchMode,
     cancel: Arc<AtomicBool>,
 }
@@ -41,6 +81,62 @@ impl SpeedTest {
         pcileech_device: String,
         mode: BenchMode,
         max_chunk_bytes: usize,
+    ) -> Result<Self> {
+        Self::with_write_policy(
+            connector,
+            pcileech_device,
+            mode,
+            max_chunk_bytes,
+            &WriteTargetPolicy::default(),
+        )
+    }
+
+    /// Connect like [`Self::new`], selecting the write probe under `write_policy`.
+    pub fn with_write_policy(
+        connector: Connector,
+        pcileech_device: String,
+        mode: BenchMode,
+        max_chunk_bytes: usize,
+        write_policy: &WriteTargetPolicy,
+    ) -> Result<Self> {
+        Self::connect_observed(
+            connector,
+            pcileech_device,
+            mode,
+            max_chunk_bytes,
+            write_policy,
+            &NoopObserver,
+        )
+    }
+
+    /// Connect like [`Self::with_write_policy`], reporting each stage to `observer`.
+    pub fn connect_observed(
+        connector: Connector,
+        pcileech_device: String,
+        mode: BenchMode,
+        max_chunk_bytes: usize,
+        write_policy: &WriteTargetPolicy,
+        observer: &dyn BenchObserver,
+    ) -> Result<Self> {
+        let connect = ConnectParams {
+            connector,
+            pcileech_device,
+            max_chunk_bytes,
+            write_policy: write_policy.clone(),
+        };
+        Self::connect(
+            Arc::new(connect),
+            mode,
+            Arc::new(AtomicBool::new(false)),
+            observer,
+        )
+    }
+
+    fn connect(
+        connect: Arc<ConnectParams>,
+        mode: BenchMode,
+        cancel: Arc<AtomicBool>,
+        observer: &dyn BenchObserver,
     ) -> Result<Self> {
         let SpeedTestInit {
             process,
@@ -49,11 +145,14 @@ impl SpeedTest {
             write_region_bytes,
             write_verified_bytes,
             write_restore_bytes,
+            environment,
         } = initialization::initialize_speedtest(
-            connector,
-            pcileech_device,
+            connect.connector,
+            connect.pcileech_device.clone(),
             mode,
-            max_chunk_bytes,
+            connect.max_chunk_bytes,
+            &connect.write_policy,
+            observer,
         )?;
         Ok(Self {
             process: Arc::new(parking_lot::RwLock::new(process)),
@@ -61,16 +160,72 @@ impl SpeedTest {
             write_addr,
             write_region_bytes,
             write_verified_bytes,
+            write_footprint: write_restore_bytes
+                .clone()
+                .map(|bytes| Arc::new(parking_lot::Mutex::new(bytes))),
             write_restore_bytes: write_restore_bytes.map(Arc::from),
+            write_compromised: Arc::new(AtomicBool::new(false)),
+            write_explicit: connect.write_policy.is_explicit(),
+            environment: Arc::new(environment),
+            target_lost: Arc::new(AtomicBool::new(false)),
+            connect,
             mode,
-            cancel: Arc::new(AtomicBool::new(false)),
+            cancel,
         })
     }
 
+    /// Re-run connection setup with the original settings after the target was lost.
+    ///
+    /// Probe targets are resolved again. The new session shares this one's cancel flag.
+    pub fn reconnect(&self) -> Result<Self> {
+        self.reconnect_observed(&NoopObserver)
+    }
+
+    /// [`Self::reconnect`], reporting each connection stage to `observer`.
+    pub fn reconnect_observed(&self, observer: &dyn BenchObserver) -> Result<Self> {
+        Self::connect(
+            self.connect.clone(),
+            self.mode,
+            self.cancel.clone(),
+            observer,
+        )
+    }
+
+    /// `true` once a pass has failed because the target process or device went away.
+    pub fn target_lost(&self) -> bool {
+        self.target_lost.load(Ordering::Relaxed)
+    }
+
+    /// Connect and list write probe candidates without writing anything.
+    pub fn preview_write_candidates(
+        connector: Connector,
+        pcileech_device: &str,
+        max_chunk_bytes: usize,
+        write_policy: &WriteTargetPolicy,
+    ) -> Result<Vec<WriteCandidate>> {
+        initialization::preview_write_candidates(
+            connector,
+            pcileech_device,
+            max_chunk_bytes,
+            write_policy,
+        )
+    }
+
     pub fn request_cancel(&self) {
         self.cancel.store(true, Ordering::Relaxed);
     }
 
+    /// Flag behind [`Self::request_cancel`], to cancel from elsewhere once the test is handed to a
+    /// session; reconnected tests share it.
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancel)
+    }
+
+    /// Clear a previous [`Self::request_cancel`] so the session can run more passes.
+    pub fn reset_cancel(&self) {
+        self.cancel.store(false, Ordering::Relaxed);
+    }
+
     pub fn is_cancelled(&self) -> bool {
         self.cancel.load(Ordering::Relaxed)
     }
@@ -79,28 +234,6 @@ impl SpeedTest {
         self.mode
     }
 
-    /// Run each operation enabled by [`Self::bench_mode`] for one chunk size.
-    pub async fn run_passes_for_size(
-        &self,
-        size: usize,
-        duration: Duration,
-        stats_tx: mpsc::Sender<BenchStats>,
-        on_warn: Option<BenchWarnFn>,
-        on_pass_start: Option<BenchPassStartFn>,
-    ) -> Result<()> {
-        for &op in self.mode.ops_for_size() {
-            if self.is_cancelled() {
-                break;
-            }
-            if let Some(ref hook) = on_pass_start {
-                hook(op, size);
-            }
-            self.run_test_with_size(op, size, duration, stats_tx.clone(), on_warn.clone())
-                .await?;
-        }
-        Ok(())
-    }
-
     pub fn read_addr(&self) -> Address {
         self.read_addr
     }
@@ -114,33 +247,112 @@ impl SpeedTest {
 
     pub fn probe_targets(&self) -> ProbeTargets {
         ProbeTargets::new(self.read_addr, self.write_addr, self.write_region_bytes)
+            .with_explicit_write(self.write_explicit)
+    }
+
+    /// Host, software and target fingerprint captured when this session connected.
+    pub fn environment(&self) -> &RunEnvironment {
+        &self.environment
     }
 
+    /// Probe targets followed by the connect phase timings.
     pub fn probe_connect_detail_lines(&self) -> Vec<String> {
-        self.probe_targets()
-            .connect_detail_lines_with_verified(self.write_verified_bytes)
+        let mut lines = self
+            .probe_targets()
+            .connect_detail_lines_with_verified(self.write_verified_bytes);
+        lines.extend(self.environment.connect_timings.detail_lines());
+        lines
     }
 
-    pub fn restore_write_target(&self) -> Result<()> {
+    /// `true` once the watchdog has seen the target process use the write region.
+    pub fn write_target_compromised(&self) -> bool {
+        self.write_compromised.load(Ordering::Relaxed)
+    }
+
+    pub fn restore_write_target(&self) -> Result<WriteRestoreOutcome> {
         let (Some(addr), Some(original)) = (self.write_addr, self.write_restore_bytes.as_deref())
         else {
+            return Ok(WriteRestoreOutcome::NotNeeded);
+        };
+        if self.write_target_compromised() {
+            return Ok(WriteRestoreOutcome::SkippedForeignData);
+        }
+        if self.target_lost() {
+            return Ok(WriteRestoreOutcome::SkippedTargetLost);
+        }
+
+        let mut process = self.process.write();
+        write_target::restore_write_target(&mut process, addr, original)?;
+        // The next write run's watchdog must expect the restored bytes, not the last payload.
+        if let Some(footprint) = &self.write_footprint {
+            footprint.lock().copy_from_slice(original);
+        }
+        Ok(WriteRestoreOutcome::Restored)
+    }
+
+    /// Before another write run on this session: check that the write region still holds the
+    /// bytes [`Self::restore_write_target`] put back, then repeat the canary check.
+    ///
+    /// A region the target changed in between is flagged compromised; reconnect to pick a new one.
+    pub fn reverify_write_target(&self) -> Result<()> {
+        let (Some(addr), Some(size), Some(original)) = (
+            self.write_addr,
+            self.write_region_bytes,
+            self.write_restore_bytes.as_deref(),
+        ) else {
             return Ok(());
         };
+        if self.write_target_compromised() {
+            return Err(write_target_compromised(format!(
+                "write probe region at {} is in use by the target process; reconnect to select and verify a new region",
+                ProbeTargets::format_va(addr)
+            )));
+        }
 
         let mut process = self.process.write();
-        write_target::restore_write_target(&mut process, addr, original)
+        let mut current = vec![0u8; original.len()];
+        if mem_io::read_raw_into_with_retry(&mut process, addr, &mut current) != IoAttempt::Ok {
+            anyhow::bail!(
+                "read of the write probe region at {} failed after {MAX_IO_RETRIES} retries; refusing to write without checking it",
+                ProbeTargets::format_va(addr)
+            );
+        }
+        if current != original {
+            self.write_compromised.store(true, Ordering::Relaxed);
+            return Err(write_target_compromised(format!(
+                "write probe region at {} changed since it was restored, so the target process is using it; reconnect to select and verify a new region",
+                ProbeTargets::format_va(addr)
+            )));
+        }
+        write_target::verify_write_region(
+            &mut process,
+            SafeWriteRegion { base: addr, size },
+            original.len(),
+        )?;
+        Ok(())
     }
 
+    fn write_watchdog(&self, op: BenchOp, addr: Address, payload: &[u8]) -> Option<WriteWatchdog> {
+        if op != BenchOp::Write {
+            return None;
+        }
+        let footprint = self.write_footprint.as_ref()?.lock();
+        Some(WriteWatchdog::new(addr, &footprint, payload))
+    }
+
+    /// Run one `op` pass over `size`-byte chunks, reporting samples and warnings to `observer`.
     pub async fn run_test_with_size(
         &self,
         op: BenchOp,
         size: usize,
         duration: Duration,
-        stats_tx: mpsc::Sender<BenchStats>,
-        on_warn: Option<BenchWarnFn>,
+        observer: &dyn BenchObserver,
     ) -> Result<()> {
         let addr = self.operation_address(op, size)?;
         let mut buffer = prepare_buffer(op, size);
+        let mut watchdog = self.write_watchdog(op, addr, &buffer);
+        let mut wrote_once = false;
+        let mut failure_streak = FailureStreak::default();
 
         let start_time = std::time::Instant::now();
         let mut ops_this_interval = 0u64;
@@ -148,6 +360,7 @@ impl SpeedTest {
         let mut total_latency = Duration::ZERO;
         let mut latency_count = 0u64;
         let mut skipped_ops = 0u64;
+        let mut skipped_this_interval = 0u64;
         let mut last_retry_warning = std::time::Instant::now()
             .checked_sub(Duration::from_secs(2))
             .unwrap_or_else(std::time::Instant::now);
@@ -167,20 +380,47 @@ impl SpeedTest {
 
             if attempt == IoAttempt::FailedAfterRetries {
                 skipped_ops += 1;
+                skipped_this_interval += 1;
                 if last_retry_warning.elapsed() >= Duration::from_secs(1) {
                     let msg = mem_io::retry_exhausted_message(op, MAX_IO_RETRIES);
-                    emit_warn(&on_warn, &format!("warning: {msg}"));
+                    observer.warning(&format!("warning: {msg}"));
                     last_retry_warning = std::time::Instant::now();
                 }
+                if failure_streak.record_failure() {
+                    let probe = {
+                        let mut process = self.process.write();
+                        liveness::probe_target(&mut process, self.read_addr)
+                    };
+                    if let Err(lost) = probe {
+                        self.target_lost.store(true, Ordering::Relaxed);
+                        return Err(lost.into());
+                    }
+                    failure_streak.reset();
+                }
                 task::yield_now().await;
                 continue;
             }
+            failure_streak.reset();
 
             let latency = op_start.elapsed();
             total_latency += latency;
             latency_count += 1;
             ops_this_interval += 1;
 
+            if let Some(watchdog) = watchdog.as_mut() {
+                wrote_once = true;
+                if watchdog.is_due() {
+                    let check = {
+                        let mut process = self.process.write();
+                        watchdog.check(&mut process)
+                    };
+                    if let Err(e) = check {
+                        self.write_compromised.store(true, Ordering::Relaxed);
+                        return Err(e);
+                    }
+                }
+            }
+
             let now = std::time::Instant::now();
 
             if now.duration_since(last_update) >= update_interval {
@@ -195,15 +435,15 @@ impl SpeedTest {
                         interval_secs,
                         total_latency,
                         latency_count,
+                        skipped_ops: skipped_this_interval,
                         start_time,
                     };
-                    if send_interval_stats(&update, &stats_tx).await {
-                        break;
-                    }
+                    observer.sample(&BenchSample::from_stats(update.stats()));
 
                     ops_this_interval = 0;
                     total_latency = Duration::ZERO;
                     latency_count = 0;
+                    skipped_this_interval = 0;
                 }
 
                 last_update = now;
@@ -215,17 +455,19 @@ impl SpeedTest {
             }
         }
 
+        if wrote_once && let (Some(watchdog), Some(footprint)) = (&watchdog, &self.write_footprint)
+        {
+            footprint.lock().copy_from_slice(watchdog.expected());
+        }
+
         if skipped_ops > 0 {
-            emit_warn(
-                &on_warn,
-                &format!(
-                    "note: {skipped_ops} DMA {} ops skipped after {MAX_IO_RETRIES} retries each (partial I/O)",
-                    op.label()
-                ),
-            );
+            observer.warning(&format!(
+                "note: {skipped_ops} DMA {} ops skipped after {MAX_IO_RETRIES} retries each (partial I/O)",
+                op.label()
+            ));
         }
 
-        if ops_this_interval > 0 && !stats_tx.is_closed() {
+        if ops_this_interval > 0 || skipped_this_interval > 0 {
             let now = std::time::Instant::now();
             let interval_duration = now - last_update;
             let interval_secs = interval_duration.as_secs_f64();
@@ -238,9 +480,10 @@ impl SpeedTest {
                     interval_secs,
                     total_latency,
                     latency_count,
+                    skipped_ops: skipped_this_interval,
                     start_time,
                 };
-                let _ = send_interval_stats(&update, &stats_tx).await;
+                observer.sample(&BenchSample::from_stats(update.stats()));
             }
         }
 
@@ -261,20 +504,31 @@ impl SpeedTest {
         let addr = match op {
             BenchOp::Read => self.read_addr,
             BenchOp::Write => self.write_addr.ok_or_else(|| {
-                anyhow::anyhow!(
-                    "write benchmark requested but no writable probe target was resolved"
+                BenchError::new(
+                    BenchErrorKind::NoWritableRegion,
+                    "write benchmark requested but no writable probe target was resolved",
                 )
             })?,
         };
 
+        if matches!(op, BenchOp::Write) && self.write_target_compromised() {
+            return Err(write_target_compromised(format!(
+                "write probe region at {} is in use by the target process; refusing further writes until a reconnect selects and verifies a new region",
+                ProbeTargets::format_va(addr)
+            )));
+        }
+
         if matches!(op, BenchOp::Write)
             && let Some(region_bytes) = self.write_region_bytes
             && size > region_bytes as usize
         {
-            anyhow::bail!(
-                "write chunk size {size} B exceeds writable probe region ({} B); reduce enabled sizes or reconnect",
-                region_bytes
-            );
+            return Err(BenchError::new(
+                BenchErrorKind::WriteChunkTooLarge,
+                format!(
+                    "write chunk size {size} B exceeds writable probe region ({region_bytes} B); reduce enabled sizes or reconnect"
+                ),
+            )
+            .into());
         }
 
         Ok(addr)
@@ -298,41 +552,30 @@ struct IntervalStatsUpdate {
     interval_secs: f64,
     total_latency: Duration,
     latency_count: u64,
+    skipped_ops: u64,
     start_time: std::time::Instant,
 }
 
-fn emit_warn(on_warn: &Option<BenchWarnFn>, message: &str) {
-    println!("  {message}");
-    if let Some(warn) = on_warn {
-        warn(message);
-    }
-}
+impl IntervalStatsUpdate {
+    fn stats(&self) -> BenchStats {
+        let ops_per_sec_f64 = self.ops_this_interval as f64 / self.interval_secs;
+        let throughput_mib_s = (ops_per_sec_f64 * self.size as f64) / (1024.0 * 1024.0);
+        let avg_latency_us = if self.latency_count > 0 {
+            (self.total_latency.as_nanos() as f64 / self.latency_count as f64) / 1000.0
+        } else {
+            0.0
+        };
 
-/// Returns `true` if the stats channel closed.
-async fn send_interval_stats(
-    update: &IntervalStatsUpdate,
-    stats_tx: &mpsc::Sender<BenchStats>,
-) -> bool {
-    let ops_per_sec_f64 = update.ops_this_interval as f64 / update.interval_secs;
-    let throughput_mib_s = (ops_per_sec_f64 * update.size as f64) / (1024.0 * 1024.0);
-    let avg_latency_us = if update.latency_count > 0 {
-        (update.total_latency.as_nanos() as f64 / update.latency_count as f64) / 1000.0
-    } else {
-        0.0
-    };
-    let elapsed_secs = update.start_time.elapsed().as_secs_f64();
-
-    stats_tx
-        .send(BenchStats {
-            op: update.op,
-            chunk_bytes: update.size,
-            elapsed_secs,
-            interval_secs: update.interval_secs,
-            ops: update.ops_this_interval,
+        BenchStats {
+            op: self.op,
+            chunk_bytes: self.size,
+            elapsed_secs: self.start_time.elapsed().as_secs_f64(),
+            interval_secs: self.interval_secs,
+            ops: self.ops_this_interval,
             throughput_mib_s,
             ops_per_sec: ops_per_sec_f64.round() as u64,
             latency_us: avg_latency_us,
-        })
-        .await
-        .is_err()
+            skipped_ops: self.skipped_ops,
+        }
+    }
 }
@@ -1,9 +1,12 @@
 //! Safe writable target discovery for DMA write benchmarks.
 //!
-//! Writes never use module images or the read probe page; the address is not user-configurable.
+//! Writes never use module images or the read probe page. Selection is automatic unless a
+//! [`WriteTargetPolicy`] pins an explicit address or narrows the candidate set.
 
+use super::error::{BenchError, BenchErrorKind};
 use super::mem_io::{self, IoAttempt, MAX_IO_RETRIES};
 use anyhow::{Result, bail};
+use clap::ValueEnum;
 use memflow::prelude::v1::*;
 
 /// Minimum writable region size when resolving a write target (32 KiB).
@@ -30,6 +33,10 @@ impl VaRange {
         self.end.saturating_sub(self.start)
     }
 
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
     pub fn overlaps(self, other: Self) -> bool {
         self.start < other.end && other.start < self.end
     }
@@ -49,6 +56,146 @@ pub struct SafeWriteRegion {
     pub size: umem,
 }
 
+/// Page attribute used to prefer or avoid write candidates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WritePageFlag {
+    /// Mapped no-execute (data pages).
+    Noexec,
+    /// Mapped executable; pages with unknown attributes never match.
+    Exec,
+    /// Page attributes could not be determined.
+    Unknown,
+}
+
+impl WritePageFlag {
+    pub fn label(self) -> &'static str {
+        match self {
+            WritePageFlag::Noexec => "noexec",
+            WritePageFlag::Exec => "exec",
+            WritePageFlag::Unknown => "unknown",
+        }
+    }
+
+    pub fn matches(self, page_type: PageType) -> bool {
+        match self {
+            WritePageFlag::Noexec => page_type.contains(PageType::NOEXEC),
+            WritePageFlag::Exec => {
+                !page_type.contains(PageType::NOEXEC) && !page_type.contains(PageType::UNKNOWN)
+            }
+            WritePageFlag::Unknown => page_type.contains(PageType::UNKNOWN),
+        }
+    }
+}
+
+/// Caller overrides for write target selection; the default keeps auto-selection.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WriteTargetPolicy {
+    /// Pin the write probe to this address instead of auto-selecting one.
+    pub address: Option<Address>,
+    /// Region length at [`Self::address`]; defaults to the rest of the enclosing writable segment.
+    pub region_bytes: Option<umem>,
+    /// Skip candidates smaller than this (raised to the largest chunk when smaller).
+    pub min_region_bytes: Option<umem>,
+    /// Skip candidates larger than this (large segments are usually heaps).
+    pub max_region_bytes: Option<umem>,
+    /// Rank candidates with these page flags first.
+    pub prefer: Vec<WritePageFlag>,
+    /// Never select candidates with these page flags.
+    pub avoid: Vec<WritePageFlag>,
+}
+
+impl WriteTargetPolicy {
+    pub fn is_explicit(&self) -> bool {
+        self.address.is_some()
+    }
+
+    /// Effective minimum region size for a session whose largest chunk is `min_bytes`.
+    pub fn effective_min_bytes(&self, min_bytes: usize) -> usize {
+        let policy_min = self
+            .min_region_bytes
+            .map(|bytes| usize::try_from(bytes).unwrap_or(usize::MAX))
+            .unwrap_or(0);
+        min_bytes.max(MIN_WRITE_REGION_BYTES).max(policy_min)
+    }
+
+    /// Why `candidate` would not be auto-selected, or `None` if it is eligible.
+    pub fn rejection_reason(&self, candidate: &WriteCandidate) -> Option<String> {
+        if let Some(max) = self.max_region_bytes
+            && candidate.size > max
+        {
+            return Some(format!("larger than --write-max-region ({max} B)"));
+        }
+        self.avoid
+            .iter()
+            .find(|flag| flag.matches(candidate.page_type))
+            .map(|flag| format!("avoided page type ({})", flag.label()))
+    }
+
+    fn preference_score(&self, candidate: &WriteCandidate) -> usize {
+        self.prefer
+            .iter()
+            .filter(|flag| flag.matches(candidate.page_type))
+            .count()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.region_bytes.is_some() && self.address.is_none() {
+            bail!("--write-region-bytes requires --write-addr");
+        }
+        if self.region_bytes == Some(0) {
+            bail!("--write-region-bytes must be positive");
+        }
+        if let (Some(min), Some(max)) = (self.min_region_bytes, self.max_region_bytes)
+            && min > max
+        {
+            bail!("--write-min-region ({min} B) exceeds --write-max-region ({max} B)");
+        }
+        if let Some(flag) = self.prefer.iter().find(|flag| self.avoid.contains(flag)) {
+            bail!("page type {} is both preferred and avoided", flag.label());
+        }
+        Ok(())
+    }
+}
+
+/// Writable segment left after carving out modules and the read probe page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WriteCandidate {
+    pub base: Address,
+    pub size: umem,
+    pub page_type: PageType,
+}
+
+impl WriteCandidate {
+    pub fn region(&self) -> SafeWriteRegion {
+        SafeWriteRegion {
+            base: self.base,
+            size: self.size,
+        }
+    }
+}
+
+/// Human-readable page attribute list (e.g. `writeable, noexec`).
+pub fn format_page_type(page_type: PageType) -> String {
+    let flags = [
+        (PageType::WRITEABLE, "writeable"),
+        (PageType::READ_ONLY, "read-only"),
+        (PageType::NOEXEC, "noexec"),
+        (PageType::PAGE_TABLE, "page-table"),
+        (PageType::UNKNOWN, "unknown"),
+    ];
+    let names = flags
+        .iter()
+        .filter(|(flag, _)| page_type.contains(*flag))
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
 pub struct ResolvedWriteTarget {
     pub base: Address,
     pub region_bytes: umem,
@@ -64,7 +211,7 @@ pub fn collect_module_ranges(process: &mut IntoProcessInstanceArcBox<'_>) -> Res
         .collect())
 }
 
-fn is_writable_candidate(page_type: PageType) -> bool {
+pub(super) fn is_writable_candidate(page_type: PageType) -> bool {
     page_type.contains(PageType::WRITEABLE) && !page_type.contains(PageType::PAGE_TABLE)
 }
 
@@ -103,14 +250,14 @@ fn available_segments(region: VaRange, excluded: &[VaRange]) -> Vec<VaRange> {
     segments
 }
 
-/// Pick the largest writable VA segment after carving out excluded ranges.
-pub fn find_safe_write_region(
+/// All writable VA segments of at least `min_bytes` after carving out excluded ranges.
+pub fn list_write_candidates(
     map: &[MemoryRange],
     excluded: &[VaRange],
     min_bytes: usize,
-) -> Option<SafeWriteRegion> {
+) -> Vec<WriteCandidate> {
     let min_bytes = min_bytes as u64;
-    let mut best: Option<SafeWriteRegion> = None;
+    let mut candidates = Vec::new();
 
     for range in map {
         let base = range.0;
@@ -119,8 +266,7 @@ pub fn find_safe_write_region(
         if !is_writable_candidate(page_type) {
             continue;
         }
-        let size_u = size;
-        if size_u < min_bytes {
+        if size < min_bytes {
             continue;
         }
         let region = VaRange::from_start_size(base, size);
@@ -129,19 +275,107 @@ pub fn find_safe_write_region(
             if segment_size < min_bytes {
                 continue;
             }
-            let candidate = SafeWriteRegion {
+            candidates.push(WriteCandidate {
                 base: Address::from(segment.start),
                 size: segment_size as umem,
-            };
-            match best {
-                None => best = Some(candidate),
-                Some(best_region) if segment_size > best_region.size => best = Some(candidate),
-                _ => {}
+                page_type,
+            });
+        }
+    }
+
+    candidates
+}
+
+/// Pick the best candidate under `policy`: preferred page flags first, then the largest segment.
+pub fn select_write_candidate(
+    candidates: &[WriteCandidate],
+    policy: &WriteTargetPolicy,
+) -> Option<WriteCandidate> {
+    let mut best: Option<(usize, WriteCandidate)> = None;
+
+    for candidate in candidates {
+        if policy.rejection_reason(candidate).is_some() {
+            continue;
+        }
+        let score = policy.preference_score(candidate);
+        match best {
+            None => best = Some((score, *candidate)),
+            Some((best_score, best_candidate))
+                if score > best_score
+                    || (score == best_score && candidate.size > best_candidate.size) =>
+            {
+                best = Some((score, *candidate))
             }
+            _ => {}
         }
     }
 
-    best
+    best.map(|(_, candidate)| candidate)
+}
+
+/// Validate an explicit write address against the memory map and excluded ranges.
+pub fn explicit_write_region(
+    map: &[MemoryRange],
+    excluded: &[VaRange],
+    address: Address,
+    region_bytes: Option<umem>,
+    min_bytes: usize,
+) -> Result<SafeWriteRegion> {
+    let addr = address.to_umem();
+    let Some(range) = map.iter().find(|range| {
+        VaRange::from_start_size(range.0, range.1).overlaps(VaRange {
+            start: addr,
+            end: addr.saturating_add(1),
+        })
+    }) else {
+        return Err(no_writable_region(format!(
+            "write address {address:#x} is not mapped in the target process"
+        )));
+    };
+    if !is_writable_candidate(range.2) {
+        return Err(no_writable_region(format!(
+            "write address {address:#x} is not in a writable data page ({})",
+            format_page_type(range.2)
+        )));
+    }
+
+    let Some(segment) = available_segments(VaRange::from_start_size(range.0, range.1), excluded)
+        .into_iter()
+        .find(|segment| segment.start <= addr && addr < segment.end)
+    else {
+        return Err(no_writable_region(format!(
+            "write address {address:#x} overlaps a loaded module or the read probe page; pick an address outside module images"
+        )));
+    };
+
+    let available = segment.end - addr;
+    let size = match region_bytes {
+        Some(bytes) if bytes > available => {
+            return Err(no_writable_region(format!(
+                "write region {bytes} B at {address:#x} runs past the writable segment ({available} B available)"
+            )));
+        }
+        Some(bytes) => bytes,
+        None => available,
+    };
+    if size < min_bytes as u64 {
+        return Err(no_writable_region(format!(
+            "write region at {address:#x} is {size} B; need at least {min_bytes} B for the largest chunk"
+        )));
+    }
+
+    Ok(SafeWriteRegion {
+        base: address,
+        size,
+    })
+}
+
+fn no_writable_region(detail: String) -> anyhow::Error {
+    BenchError::new(BenchErrorKind::NoWritableRegion, detail).into()
+}
+
+fn verification_mismatch(detail: String) -> anyhow::Error {
+    BenchError::new(BenchErrorKind::WriteVerificationMismatch, detail).into()
 }
 
 fn fill_verify_pattern(buf: &mut [u8]) {
@@ -164,22 +398,22 @@ pub fn verify_write_target(
     fill_verify_pattern(&mut canary);
 
     if mem_io::write_raw_with_retry(process, write_addr, &canary) != IoAttempt::Ok {
-        bail!(
+        return Err(verification_mismatch(format!(
             "write canary ({verify_bytes} B) to candidate region failed after {MAX_IO_RETRIES} retries (partial virtual write)"
-        );
+        )));
     }
 
     let mut read_back = vec![0u8; verify_bytes];
     if mem_io::read_raw_into_with_retry(process, write_addr, &mut read_back) != IoAttempt::Ok {
-        bail!(
+        return Err(verification_mismatch(format!(
             "read back canary ({verify_bytes} B) from candidate region failed after {MAX_IO_RETRIES} retries (partial virtual read)"
-        );
+        )));
     }
 
     if read_back != canary {
-        bail!(
+        return Err(verification_mismatch(format!(
             "write verification failed at {write_addr} ({verify_bytes} B): DMA write path returned mismatched data"
-        );
+        )));
     }
 
     Ok(())
@@ -223,28 +457,100 @@ pub fn restore_write_target(
     Ok(())
 }
 
-/// Resolve a safe write base address inside `process` (same target as reads).
-pub fn resolve_safe_write_target(
+fn write_exclusions(
     process: &mut IntoProcessInstanceArcBox<'_>,
     read_addr: Address,
     min_bytes: usize,
-) -> Result<ResolvedWriteTarget> {
-    let min_bytes = min_bytes.max(MIN_WRITE_REGION_BYTES);
-
-    let modules = collect_module_ranges(process)?;
-    let mut excluded = modules;
+) -> Result<Vec<VaRange>> {
+    let mut excluded = collect_module_ranges(process)?;
     excluded.push(VaRange::from_start_size(
         read_addr,
         min_bytes.try_into().unwrap_or(u32::MAX as umem),
     ));
+    Ok(excluded)
+}
+
+pub(super) fn full_memory_map(process: &mut IntoProcessInstanceArcBox<'_>) -> Vec<MemoryRange> {
+    process.mapped_mem_range_vec(0, Address::null(), Address::invalid())
+}
+
+/// List write candidates without writing anything (dry run for `--list-write-candidates`).
+pub fn preview_write_candidates(
+    process: &mut IntoProcessInstanceArcBox<'_>,
+    read_addr: Address,
+    min_bytes: usize,
+    policy: &WriteTargetPolicy,
+) -> Result<Vec<WriteCandidate>> {
+    let min_bytes = policy.effective_min_bytes(min_bytes);
+    let excluded = write_exclusions(process, read_addr, min_bytes)?;
+    let map = full_memory_map(process);
+    Ok(list_write_candidates(&map, &excluded, min_bytes))
+}
 
-    let map = process.mapped_mem_range_vec(0, Address::null(), Address::invalid());
-    let region = find_safe_write_region(&map, &excluded, min_bytes).ok_or_else(|| {
-        anyhow::anyhow!(
-            "no auto-selected writable probe region found (need at least {min_bytes} bytes outside loaded modules and the read probe page)"
-        )
-    })?;
+/// Pick the write region under `policy` without writing anything (no canary, no restore capture).
+pub fn find_write_region(
+    process: &mut IntoProcessInstanceArcBox<'_>,
+    read_addr: Address,
+    min_bytes: usize,
+    policy: &WriteTargetPolicy,
+) -> Result<SafeWriteRegion> {
+    policy.validate()?;
+    let min_bytes = policy.effective_min_bytes(min_bytes);
+
+    let excluded = write_exclusions(process, read_addr, min_bytes)?;
+    let map = full_memory_map(process);
+    select_write_region(&map, &excluded, min_bytes, policy)
+}
 
+/// Pick the write region from a memory map: the explicit address, or the best auto candidate.
+///
+/// `min_bytes` is used as given; [`find_write_region`] raises it with
+/// [`WriteTargetPolicy::effective_min_bytes`] first.
+pub fn select_write_region(
+    map: &[MemoryRange],
+    excluded: &[VaRange],
+    min_bytes: usize,
+    policy: &WriteTargetPolicy,
+) -> Result<SafeWriteRegion> {
+    match policy.address {
+        Some(address) => {
+            explicit_write_region(map, excluded, address, policy.region_bytes, min_bytes)
+        }
+        None => {
+            let candidates = list_write_candidates(map, excluded, min_bytes);
+            select_write_candidate(&candidates, policy)
+                .map(|candidate| candidate.region())
+                .ok_or_else(|| {
+                    no_writable_region(format!(
+                        "no auto-selected writable probe region found (need at least {min_bytes} bytes outside loaded modules and the read probe page{})",
+                        if policy == &WriteTargetPolicy::default() {
+                            ""
+                        } else {
+                            " matching the write target policy"
+                        }
+                    ))
+                })
+        }
+    }
+}
+
+/// Resolve a safe write base address inside `process` (same target as reads).
+pub fn resolve_safe_write_target(
+    process: &mut IntoProcessInstanceArcBox<'_>,
+    read_addr: Address,
+    min_bytes: usize,
+    policy: &WriteTargetPolicy,
+) -> Result<ResolvedWriteTarget> {
+    let region = find_write_region(process, read_addr, min_bytes, policy)?;
+    verify_write_region(process, region, policy.effective_min_bytes(min_bytes))
+}
+
+/// Write, read back and restore a canary of up to `min_bytes` at the start of `region`.
+pub fn verify_write_region(
+    process: &mut IntoProcessInstanceArcBox<'_>,
+    region: SafeWriteRegion,
+    min_bytes: usize,
+) -> Result<ResolvedWriteTarget> {
     let verify_bytes = usize::try_from(region.size)
         .unwrap_or(usize::MAX)
         .min(min_bytes);
@@ -258,9 +564,9 @@ pub fn resolve_safe_write_target(
         (Err(verify_err), Ok(())) => return Err(verify_err),
         (Ok(()), Err(restore_err)) => return Err(restore_err),
         (Err(verify_err), Err(restore_err)) => {
-            bail!(
+            return Err(verification_mismatch(format!(
                 "{verify_err}; additionally failed to restore original write probe bytes: {restore_err}"
-            );
+            )));
         }
     }
 
@@ -325,7 +631,9 @@ mod tests {
             start: 0x10000,
             end: 0x20000,
         }];
-        let region = find_safe_write_region(&map, &excluded, 32 * 1024).unwrap();
+        let region = select_write_region(&map, &excluded, 32 * 1024, &WriteTargetPolicy::default())
+            .ok()
+            .unwrap();
         assert_eq!(region.base, Address::from(0x30000_u64));
         assert_eq!(region.size, 48 * 1024);
     }
@@ -337,7 +645,11 @@ mod tests {
             64 * 1024,
             PageType::WRITEABLE | PageType::PAGE_TABLE,
         )];
-        assert!(find_safe_write_region(&map, &[], 4096).is_none());
+        assert!(
+            select_write_region(&map, &[], 4096, &WriteTargetPolicy::default())
+                .ok()
+                .is_none()
+        );
     }
 
     #[test]
@@ -347,9 +659,13 @@ mod tests {
             16 * 1024,
             PageType::WRITEABLE,
         )];
-        assert!(find_safe_write_region(&map, &[], 32 * 1024).is_none());
+        assert!(
+            select_write_region(&map, &[], 32 * 1024, &WriteTargetPolicy::default())
+                .ok()
+                .is_none()
+        );
         assert_eq!(
-            find_safe_write_region(&map, &[], 16 * 1024),
+            select_write_region(&map, &[], 16 * 1024, &WriteTargetPolicy::default()).ok(),
             Some(SafeWriteRegion {
                 base: Address::from(0x2000_u64),
                 size: 16 * 1024
@@ -364,7 +680,11 @@ mod tests {
             64 * 1024,
             PageType::READ_ONLY,
         )];
-        assert!(find_safe_write_region(&map, &[], 4096).is_none());
+        assert!(
+            select_write_region(&map, &[], 4096, &WriteTargetPolicy::default())
+                .ok()
+                .is_none()
+        );
     }
 
     #[test]
@@ -378,7 +698,11 @@ mod tests {
             start: 0x4000,
             end: 0x5000,
         }];
-        assert!(find_safe_write_region(&map, &excluded, 64 * 1024).is_none());
+        assert!(
+            select_write_region(&map, &excluded, 64 * 1024, &WriteTargetPolicy::default())
+                .ok()
+                .is_none()
+        );
     }
 
     #[test]
@@ -392,7 +716,9 @@ mod tests {
             start: 0x4000,
             end: 0x5000,
         }];
-        let region = find_safe_write_region(&map, &excluded, 4096).unwrap();
+        let region = select_write_region(&map, &excluded, 4096, &WriteTargetPolicy::default())
+            .ok()
+            .unwrap();
         assert_eq!(region.base, Address::from(0x5000_u64));
         assert_eq!(region.size, 64 * 1024 - 0x1000);
     }
@@ -404,6 +730,136 @@ mod tests {
         assert_eq!(guard.end, 0x1000 + 0x1000 + MODULE_END_GUARD_BYTES);
     }
 
+    #[test]
+    fn policy_max_region_skips_large_heap_segments() {
+        let map = vec![
+            CTup3(Address::from(0x10000_u64), 1024 * 1024, PageType::WRITEABLE),
+            CTup3(Address::from(0x200000_u64), 64 * 1024, PageType::WRITEABLE),
+        ];
+        let candidates = list_write_candidates(&map, &[], 32 * 1024);
+        let policy = WriteTargetPolicy {
+            max_region_bytes: Some(128 * 1024),
+            ..Default::default()
+        };
+
+        let selected = select_write_candidate(&candidates, &policy).unwrap();
+
+        assert_eq!(selected.base, Address::from(0x200000_u64));
+        assert!(policy.rejection_reason(&candidates[0]).is_some());
+    }
+
+    #[test]
+    fn policy_prefers_and_avoids_page_flags() {
+        let map = vec![
+            CTup3(Address::from(0x10000_u64), 256 * 1024, PageType::WRITEABLE),
+            CTup3(
+                Address::from(0x80000_u64),
+                64 * 1024,
+                PageType::WRITEABLE | PageType::NOEXEC,
+            ),
+        ];
+        let candidates = list_write_candidates(&map, &[], 32 * 1024);
+
+        let prefer = WriteTargetPolicy {
+            prefer: vec![WritePageFlag::Noexec],
+            ..Default::default()
+        };
+        assert_eq!(
+            select_write_candidate(&candidates, &prefer).unwrap().base,
+            Address::from(0x80000_u64)
+        );
+
+        let avoid = WriteTargetPolicy {
+            avoid: vec![WritePageFlag::Noexec, WritePageFlag::Exec],
+            ..Default::default()
+        };
+        assert!(select_write_candidate(&candidates, &avoid).is_none());
+    }
+
+    #[test]
+    fn unknown_pages_are_not_executable() {
+        let unknown = PageType::WRITEABLE | PageType::UNKNOWN;
+        assert!(!WritePageFlag::Exec.matches(unknown));
+        assert!(WritePageFlag::Unknown.matches(unknown));
+        assert!(WritePageFlag::Exec.matches(PageType::WRITEABLE));
+        assert!(!WritePageFlag::Exec.matches(PageType::WRITEABLE | PageType::NOEXEC));
+    }
+
+    #[test]
+    fn select_write_region_honours_explicit_address() {
+        let map = vec![CTup3(
+            Address::from(0x10000_u64),
+            64 * 1024,
+            PageType::WRITEABLE,
+        )];
+        let policy = WriteTargetPolicy {
+            address: Some(Address::from(0x18000_u64)),
+            ..Default::default()
+        };
+        let region = select_write_region(&map, &[], 4096, &policy).unwrap();
+        assert_eq!(region.base, Address::from(0x18000_u64));
+        assert_eq!(region.size, 32 * 1024);
+        assert_eq!(
+            select_write_region(&map, &[], 4096, &WriteTargetPolicy::default())
+                .ok()
+                .unwrap()
+                .base,
+            Address::from(0x10000_u64)
+        );
+    }
+
+    #[test]
+    fn explicit_address_must_be_writable_and_outside_exclusions() {
+        let map = vec![
+            CTup3(Address::from(0x10000_u64), 64 * 1024, PageType::WRITEABLE),
+            CTup3(Address::from(0x40000_u64), 64 * 1024, PageType::READ_ONLY),
+        ];
+        let excluded = vec![VaRange {
+            start: 0x10000,
+            end: 0x11000,
+        }];
+
+        let region =
+            explicit_write_region(&map, &excluded, Address::from(0x12000_u64), None, 4096).unwrap();
+        assert_eq!(region.size, 0x20000 - 0x12000);
+
+        assert!(
+            explicit_write_region(&map, &excluded, Address::from(0x10800_u64), None, 4096).is_err()
+        );
+        let err = explicit_write_region(&map, &excluded, Address::from(0x40000_u64), None, 4096)
+            .unwrap_err();
+        assert_eq!(
+            BenchError::from_error(&err).map(|e| e.kind),
+            Some(BenchErrorKind::NoWritableRegion)
+        );
+        assert!(
+            explicit_write_region(
+                &map,
+                &excluded,
+                Address::from(0x12000_u64),
+                Some(0x20000),
+                4096
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn policy_validation_rejects_conflicts() {
+        let orphan_len = WriteTargetPolicy {
+            region_bytes: Some(4096),
+            ..Default::default()
+        };
+        assert!(orphan_len.validate().is_err());
+
+        let inverted = WriteTargetPolicy {
+            min_region_bytes: Some(64 * 1024),
+            max_region_bytes: Some(32 * 1024),
+            ..Default::default()
+        };
+        assert!(inverted.validate().is_err());
+    }
+
     #[test]
     fn verify_pattern_is_deterministic_and_wraps() {
         let mut buf = [0u8; 300];
@@ -1,9 +1,22 @@
-use super::super::test_management::{start_connect, start_test_from_connected};
+use super::super::test_management::{
+    AgentUpdate, start_agent_viewer, start_connect, start_test_from_connected,
+};
 
 use super::state::SpeedTestApp;
-use crate::speedtest::{BenchmarkReport, ReportFormat, default_report_path, write_report_to_path};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::bench_config::default_gui_chunk_sizes;
+use crate::speedtest::{
+    BenchProfile, BenchmarkReport, BenchmarkSession, Connector, ProfileTarget, ProfileThresholds,
+    ReportFormat, default_image_path, default_report_path, parse_labels_csv, plot_svg, table_svg,
+    write_image, write_report_to_path,
+};
 use crate::ui::console::log_to_console;
-use crate::ui::types::ReportExportStatus;
+use crate::ui::constants::GUI_RECONNECT_ATTEMPTS;
+use crate::ui::results_panel::plot::plot_image_series;
+use crate::ui::results_panel::table::{TABLE_HEADERS, results_table_rows};
+use crate::ui::types::{ImageTarget, ReportExportStatus};
 
 impl SpeedTestApp {
     pub fn start_test_impl(&mut self) {
@@ -11,6 +24,50 @@ impl SpeedTestApp {
             return;
         }
 
+        self.reset_run_state();
+        self.connect_rx = Some(start_connect(self.benchmark_session(), &self.console));
+    }
+
+    /// Session for the config panel's connector, mode, sizes, duration and reconnect choice.
+    fn benchmark_session(&self) -> BenchmarkSession {
+        let sizes = self
+            .test_sizes
+            .iter()
+            .filter_map(|(size, enabled)| enabled.then_some(*size))
+            .collect();
+        let reconnect_attempts = if self.auto_reconnect {
+            GUI_RECONNECT_ATTEMPTS
+        } else {
+            0
+        };
+        // Samples are always kept; `build_report` drops them unless sample export is on.
+        BenchmarkSession::new(self.connector, self.bench_mode, sizes, self.duration)
+            .with_device(&self.pcileech_device)
+            .with_write_policy(self.profile.target.write_policy())
+            .with_reconnect_attempts(reconnect_attempts)
+            .with_include_samples(true)
+    }
+
+    /// Follow the run of a CLI started with `--agent-listen` instead of benchmarking locally.
+    pub fn start_remote_view_impl(&mut self) {
+        let addr = self.agent_addr.trim().to_string();
+        if !self.can_start_test() || addr.is_empty() {
+            return;
+        }
+
+        self.reset_run_state();
+
+        let (modal_tx, modal_rx) = std::sync::mpsc::channel::<String>();
+        let (stats_tx, stats_rx) = tokio::sync::mpsc::unbounded_channel();
+        self.stats_rx = Some(stats_rx);
+        self.modal_rx = Some(modal_rx);
+
+        let (agent_rx, done_rx) = start_agent_viewer(addr, &self.console, modal_tx, stats_tx);
+        self.agent_rx = Some(agent_rx);
+        self.bench_done_rx = Some(done_rx);
+    }
+
+    fn reset_run_state(&mut self) {
         self.is_connecting = true;
         self.error_message = None;
         self.show_error_modal = false;
@@ -18,6 +75,7 @@ impl SpeedTestApp {
         self.connection_cancelled = false;
         self.results.lock().unwrap().clear();
         self.probe_targets = None;
+        self.run_environment = None;
         self.current_throughput = 0.0;
         self.current_ops_per_sec = 0;
         self.current_bench_op = None;
@@ -29,19 +87,12 @@ impl SpeedTestApp {
         self.current_test_size = None;
         self.completed_chunks.clear();
         self.pass_aggregators.clear();
+        self.raw_samples.clear();
+        // A fresh slot, so a previous run's thread cannot hand its report to this one.
+        self.run_report = Arc::new(Mutex::new(None));
+        self.history_pending = false;
         self.last_console_stats_log = None;
         self.report_export_status = None;
-
-        let bench_mode = self.bench_mode;
-        let max_chunk = crate::bench_config::max_enabled_chunk_bytes(&self.test_sizes);
-        let rx = start_connect(
-            self.connector,
-            self.pcileech_device.clone(),
-            bench_mode,
-            max_chunk,
-            &self.console,
-        );
-        self.connect_rx = Some(rx);
     }
 
     pub fn poll_connection(&mut self) {
@@ -83,6 +134,7 @@ impl SpeedTestApp {
             Ok(test) => {
                 let targets = test.probe_targets();
                 self.probe_targets = Some(targets);
+                self.run_environment = Some(test.environment().clone());
                 for line in test.probe_connect_detail_lines() {
                     log_to_console(&self.console, &line);
                 }
@@ -92,18 +144,17 @@ impl SpeedTestApp {
                 self.overall_test_start_time = Some(std::time::Instant::now());
 
                 let (modal_tx, modal_rx) = std::sync::mpsc::channel::<String>();
-                let (stats_tx, stats_rx) = tokio::sync::mpsc::channel(100);
+                let (stats_tx, stats_rx) = tokio::sync::mpsc::unbounded_channel();
                 self.stats_rx = Some(stats_rx);
                 self.modal_rx = Some(modal_rx);
 
-                let test_clone = test.clone();
                 self.bench_done_rx = Some(start_test_from_connected(
-                    test_clone,
-                    self.duration,
-                    &self.test_sizes,
+                    self.benchmark_session(),
+                    test.clone(),
                     &self.console,
                     modal_tx,
                     stats_tx,
+                    Arc::clone(&self.run_report),
                 ));
 
                 self.test = Some(test);
@@ -117,7 +168,100 @@ impl SpeedTestApp {
         }
     }
 
+    /// Apply the agent's connection and run announcements while [`Self::agent_rx`] is set.
+    pub fn poll_agent_viewer(&mut self) {
+        let Some(rx) = self.agent_rx.as_ref() else {
+            return;
+        };
+
+        let update = match rx.try_recv() {
+            Ok(update) => update,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.agent_rx = None;
+                self.agent_stop = None;
+                self.connection_cancelled = false;
+                if self.is_connecting {
+                    self.is_connecting = false;
+                    self.show_config = true;
+                }
+                return;
+            }
+        };
+
+        if self.connection_cancelled {
+            if let AgentUpdate::Connected(stop) = &update {
+                stop.stop();
+            }
+            if let Some(stop) = self.agent_stop.take() {
+                stop.stop();
+            }
+            self.agent_rx = None;
+            self.connection_cancelled = false;
+            return;
+        }
+
+        match update {
+            AgentUpdate::Connected(stop) => self.agent_stop = Some(stop),
+            AgentUpdate::RunStarted(run) => {
+                self.agent_rx = None;
+                self.is_connecting = false;
+                self.connector = run.connector;
+                self.bench_mode = run.mode;
+                self.duration = run.duration_secs;
+                let mut test_sizes = default_gui_chunk_sizes()
+                    .into_iter()
+                    .map(|(size, _)| (size, run.sizes.contains(&size)))
+                    .collect::<Vec<_>>();
+                for &size in &run.sizes {
+                    if !test_sizes.iter().any(|(offered, _)| *offered == size) {
+                        test_sizes.push((size, true));
+                    }
+                }
+                test_sizes.sort_unstable_by_key(|(size, _)| *size);
+                self.test_sizes = test_sizes;
+
+                log_to_console(
+                    &self.console,
+                    &format!(
+                        "Remote run: {} connector, {} mode, {}s per size",
+                        run.connector,
+                        run.mode.label(),
+                        run.duration_secs
+                    ),
+                );
+                for line in run.probes.connect_detail_lines() {
+                    log_to_console(&self.console, &line);
+                }
+                for line in run.environment.connect_timings.detail_lines() {
+                    log_to_console(&self.console, &line);
+                }
+                self.probe_targets = Some(run.probes);
+                self.run_environment = Some(run.environment);
+
+                self.is_running = true;
+                self.test_start_time = Some(std::time::Instant::now());
+                self.overall_test_start_time = Some(std::time::Instant::now());
+            }
+            AgentUpdate::Failed(message) => {
+                self.agent_rx = None;
+                self.agent_stop = None;
+                self.is_connecting = false;
+                self.stats_rx = None;
+                self.modal_rx = None;
+                log_to_console(&self.console, &message);
+                self.show_error_modal = true;
+                self.error_modal_message = message;
+                self.show_config = true;
+            }
+        }
+    }
+
     pub fn stop_test_impl(&mut self) {
+        if let Some(stop) = self.agent_stop.take() {
+            stop.stop();
+            self.modal_rx = None;
+        }
         if self.is_connecting {
             self.cancel_connection_impl();
             return;
@@ -178,17 +322,48 @@ impl SpeedTestApp {
         match rx.try_recv() {
             Ok(()) | Err(std::sync::mpsc::TryRecvError::Disconnected) => {
                 self.bench_done_rx = None;
+                if std::mem::take(&mut self.history_pending) {
+                    self.record_history_impl();
+                }
             }
             Err(std::sync::mpsc::TryRecvError::Empty) => {}
         }
     }
 
-    pub fn export_report_impl(&mut self, format: ReportFormat) {
+    /// Record the finished run in history once the runner has handed over its report.
+    pub fn record_history_when_done(&mut self) {
+        if self.bench_thread_active() {
+            self.history_pending = true;
+        } else {
+            self.record_history_impl();
+        }
+    }
+
+    /// Report for the last run, or the reason none can be built yet.
+    ///
+    /// Local runs use the report the session returned; remote views and runs still in progress
+    /// rebuild one from the samples received so far.
+    fn build_report(&self) -> Result<BenchmarkReport, String> {
+        let labels =
+            parse_labels_csv(&self.run_labels).map_err(|e| format!("Invalid run labels: {e}"))?;
+        let session_report = self.run_report.lock().unwrap().clone();
+        let mut report = match session_report {
+            Some(report) => report,
+            None => self.report_from_samples()?,
+        };
+        if report.passes.is_empty() {
+            return Err("No benchmark samples available for report export.".to_string());
+        }
+        report = report.with_labels(labels).with_note(&self.run_note);
+        if !self.export_include_samples {
+            report.samples = None;
+        }
+        Ok(report)
+    }
+
+    fn report_from_samples(&self) -> Result<BenchmarkReport, String> {
         let Some(probes) = self.probe_targets else {
-            let message = "No probe metadata available for report export.";
-            self.report_export_status = Some(ReportExportStatus::error(message));
-            log_to_console(&self.console, message);
-            return;
+            return Err("No probe metadata available for report export.".to_string());
         };
 
         let summaries = self
@@ -198,10 +373,7 @@ impl SpeedTestApp {
             .collect::<Vec<_>>();
 
         if summaries.is_empty() {
-            let message = "No benchmark samples available for report export.";
-            self.report_export_status = Some(ReportExportStatus::error(message));
-            log_to_console(&self.console, message);
-            return;
+            return Err("No benchmark samples available for report export.".to_string());
         }
 
         let sizes = self
@@ -209,19 +381,42 @@ impl SpeedTestApp {
             .iter()
             .filter_map(|(size, enabled)| (*enabled).then_some(*size))
             .collect::<Vec<_>>();
-        let report = BenchmarkReport::new(
+        let mut report = BenchmarkReport::new(
             self.connector,
             self.bench_mode,
             self.duration,
             &sizes,
             probes,
             summaries,
-        );
-        let path = default_report_path(format);
+        )
+        .with_samples(self.raw_samples.clone());
+        if let Some(environment) = &self.run_environment {
+            report = report.with_environment(environment.clone());
+        }
+        Ok(report)
+    }
+
+    pub fn export_report_impl(&mut self, format: ReportFormat) {
+        let report = match self.build_report() {
+            Ok(report) => report,
+            Err(message) => {
+                log_to_console(&self.console, &message);
+                self.report_export_status = Some(ReportExportStatus::error(message));
+                return;
+            }
+        };
+        let path = default_report_path(format, &report.labels);
 
         match write_report_to_path(&report, format, &path) {
             Ok(()) => {
-                let message = format!("Report saved: {}", path.display());
+                let message = match report.samples_companion_path(format, &path) {
+                    Some(samples_path) => format!(
+                        "Report saved: {} (samples: {})",
+                        path.display(),
+                        samples_path.display()
+                    ),
+                    None => format!("Report saved: {}", path.display()),
+                };
                 self.report_export_status = Some(ReportExportStatus::success(message.clone()));
                 log_to_console(&self.console, &message);
             }
@@ -232,4 +427,177 @@ impl SpeedTestApp {
             }
         }
     }
+
+    /// Render the selected plot or results table and save it next to the reports.
+    pub fn export_image_impl(&mut self) {
+        let settings = self.image_export;
+        let svg = match settings.target {
+            ImageTarget::Plot(metric) => plot_svg(
+                metric.plot_title(),
+                metric.unit(),
+                plot_image_series(&self.results, &self.overlays, metric),
+                settings.width_px,
+            ),
+            ImageTarget::Table(metric) => {
+                let rows = results_table_rows(&self.results, &self.overlays, metric)
+                    .into_iter()
+                    .map(|(cells, _)| cells.to_vec())
+                    .collect::<Vec<_>>();
+                table_svg(
+                    metric.table_title(),
+                    &TABLE_HEADERS,
+                    &rows,
+                    settings.width_px,
+                )
+            }
+        };
+        let path = default_image_path(settings.target.file_stem(), settings.format);
+
+        let (message, status) = match write_image(&svg, settings.format, &path) {
+            Ok(()) => {
+                let message = format!("Image saved: {}", path.display());
+                (message.clone(), ReportExportStatus::success(message))
+            }
+            Err(e) => {
+                let message = format!("Image export failed: {e}");
+                (message.clone(), ReportExportStatus::error(message))
+            }
+        };
+        log_to_console(&self.console, &message);
+        self.report_export_status = Some(status);
+    }
+
+    fn profile_path(&self) -> Option<PathBuf> {
+        let input = self.profile_path.trim().trim_matches('"');
+        (!input.is_empty()).then(|| PathBuf::from(input))
+    }
+
+    /// Apply the connector, device, mode, sizes, duration, write target and sample export of a
+    /// profile file.
+    pub fn load_profile_impl(&mut self) {
+        let Some(path) = self.profile_path() else {
+            return;
+        };
+        let profile = match BenchProfile::load(&path) {
+            Ok(profile) => profile,
+            Err(e) => {
+                log_to_console(&self.console, &format!("Profile not loaded: {e}"));
+                return;
+            }
+        };
+        if let Err(e) = profile.target.write_policy().validate() {
+            log_to_console(&self.console, &format!("Profile not loaded: {e}"));
+            return;
+        }
+
+        if let Some(connector) = profile.connector {
+            self.connector = connector;
+        }
+        if let Some(device) = &profile.device {
+            self.pcileech_device = device.clone();
+        }
+        if let Some(mode) = profile.mode {
+            self.bench_mode = mode;
+        }
+        if let Some(duration) = profile.duration {
+            self.duration = duration.clamp(1, 60);
+        }
+        if let Some(sizes) = &profile.sizes {
+            self.test_sizes = default_gui_chunk_sizes()
+                .into_iter()
+                .map(|(size, _)| (size, sizes.contains(&size)))
+                .collect();
+            let skipped: Vec<_> = sizes
+                .iter()
+                .filter(|size| !self.test_sizes.iter().any(|(offered, _)| offered == *size))
+                .collect();
+            if !skipped.is_empty() {
+                log_to_console(
+                    &self.console,
+                    &format!("Profile sizes not offered by the GUI were skipped: {skipped:?}"),
+                );
+            }
+        }
+        if let Some(include_samples) = profile.output.include_samples {
+            self.export_include_samples = include_samples;
+        }
+        if profile.target != ProfileTarget::default() {
+            log_to_console(
+                &self.console,
+                "Profile write target applies from the next connect.",
+            );
+        }
+        let mut ignored = Vec::new();
+        if profile.thresholds != ProfileThresholds::default() {
+            ignored.push("thresholds (the GUI does not gate runs)");
+        }
+        if profile.output.path.is_some() || profile.output.format.is_some() {
+            ignored.push("output path and format (use the export buttons)");
+        }
+        if !ignored.is_empty() {
+            log_to_console(
+                &self.console,
+                &format!(
+                    "Profile settings ignored by the GUI, kept when saving: {}",
+                    ignored.join(", ")
+                ),
+            );
+        }
+        self.profile = profile;
+        log_to_console(
+            &self.console,
+            &format!("Profile loaded: {}", path.display()),
+        );
+    }
+
+    /// Save the GUI settings over the last loaded profile so its thresholds, write target and
+    /// output path survive.
+    pub fn save_profile_impl(&mut self) {
+        let Some(path) = self.profile_path() else {
+            return;
+        };
+        let mut profile = self.profile.clone();
+        profile.connector = Some(self.connector);
+        profile.device = matches!(self.connector, Connector::Pcileech)
+            .then(|| self.pcileech_device.trim().to_string());
+        profile.mode = Some(self.bench_mode);
+        profile.sizes = Some(
+            self.test_sizes
+                .iter()
+                .filter_map(|(size, enabled)| enabled.then_some(*size))
+                .collect(),
+        );
+        profile.duration = Some(self.duration);
+        profile.output.include_samples = self.export_include_samples.then_some(true);
+
+        let message = match profile.save(&path) {
+            Ok(()) => {
+                self.profile = profile;
+                format!("Profile saved: {}", path.display())
+            }
+            Err(e) => format!("Profile not saved: {e}"),
+        };
+        log_to_console(&self.console, &message);
+    }
+
+    /// Append the finished run to the history file shown in the history window.
+    pub fn record_history_impl(&mut self) {
+        let report = match self.build_report() {
+            Ok(report) => report,
+            Err(message) => {
+                log_to_console(&self.console, &format!("History not recorded: {message}"));
+                return;
+            }
+        };
+
+        let history = self.history_window.history();
+        let message = match history.append(&report) {
+            Ok(id) => format!("Recorded as run {id} in {}", history.path().display()),
+            Err(e) => format!("History not recorded: {e}"),
+        };
+        log_to_console(&self.console, &message);
+        if self.history_window.is_visible() {
+            self.history_window.reload();
+        }
+    }
 }
@@ -3,13 +3,19 @@ use std::time::Instant;
 
 use tokio::sync::mpsc;
 
-use crate::speedtest::{BenchMode, BenchStats, Connector, PassAggregator, ProbeTargets, SpeedTest};
+use crate::speedtest::{
+    AgentStopHandle, BenchMode, BenchProfile, BenchSample, BenchStats, BenchmarkReport, Connector,
+    PassAggregator, ProbeTargets, RunEnvironment, SpeedTest,
+};
 use crate::ui::console::ConsoleWindow;
+use crate::ui::history::HistoryWindow;
+use crate::ui::overlays::ReportOverlays;
+use crate::ui::test_management::AgentUpdate;
 
 use super::super::constants::DEFAULT_PLOT_HEIGHT;
 use super::super::constants::DEFAULT_PLOT_WIDTH;
 use super::super::types::TestResults;
-use super::super::types::{PlotResizeDirection, ReportExportStatus};
+use super::super::types::{ImageExportSettings, PlotResizeDirection, ReportExportStatus};
 
 #[cfg(feature = "branding")]
 use crate::branding::BrandingManager;
@@ -19,13 +25,27 @@ pub struct SpeedTestApp {
     pub pcileech_device: String,
     pub duration: u64,
     pub bench_mode: BenchMode,
+    pub auto_reconnect: bool,
+    /// Comma-separated `key=value` labels, parsed when a report is built.
+    pub run_labels: String,
+    pub run_note: String,
+    /// Path in the config panel's profile field.
+    pub profile_path: String,
+    /// Last loaded profile; saving overwrites only the fields the GUI edits.
+    pub profile: BenchProfile,
     pub test: Option<SpeedTest>,
     pub probe_targets: Option<ProbeTargets>,
+    pub run_environment: Option<RunEnvironment>,
     pub results: TestResults,
     pub is_running: bool,
     pub is_connecting: bool,
     pub connect_rx: Option<std::sync::mpsc::Receiver<Result<SpeedTest, String>>>,
     pub connection_cancelled: bool,
+    /// `HOST:PORT` in the config panel's remote agent field.
+    pub agent_addr: String,
+    /// Set while following a remote agent, until its run starts or the viewer fails.
+    pub agent_rx: Option<std::sync::mpsc::Receiver<AgentUpdate>>,
+    pub agent_stop: Option<AgentStopHandle>,
     pub was_running: bool,
     pub error_message: Option<String>,
     pub current_throughput: f64,
@@ -35,15 +55,26 @@ pub struct SpeedTestApp {
     pub show_config: bool,
     pub was_show_config: bool,
     #[allow(clippy::type_complexity)]
-    pub stats_rx: Option<mpsc::Receiver<BenchStats>>,
+    pub stats_rx: Option<mpsc::UnboundedReceiver<BenchStats>>,
     pub test_start_time: Option<Instant>,
     pub overall_test_start_time: Option<Instant>,
     pub test_end_time: Option<f64>,
     pub current_test_size: Option<usize>,
     pub completed_chunks: Vec<(crate::speedtest::BenchOp, usize, f64)>,
     pub pass_aggregators: Vec<PassAggregator>,
+    /// Every interval sample of the current run, for raw report export.
+    pub raw_samples: Vec<BenchSample>,
+    /// Report the local runner returned, error included; set before the runner thread exits.
+    pub run_report: Arc<Mutex<Option<BenchmarkReport>>>,
+    /// Record the run in history once the benchmark thread has exited.
+    pub history_pending: bool,
+    pub export_include_samples: bool,
     pub max_throughput: f64,
     pub console: ConsoleWindow,
+    pub history_window: HistoryWindow,
+    pub record_history: bool,
+    pub overlays: ReportOverlays,
+    pub image_export: ImageExportSettings,
     pub ui_scale: f32,
     pub ui_scale_text: String,
     pub test_sizes: Vec<(usize, bool)>,
@@ -77,7 +108,7 @@ impl SpeedTestApp {
     }
 
     pub fn connection_thread_active(&self) -> bool {
-        self.connect_rx.is_some()
+        self.connect_rx.is_some() || self.agent_rx.is_some()
     }
 
     pub fn can_start_test(&self) -> bool {
@@ -90,13 +121,22 @@ impl SpeedTestApp {
             pcileech_device: String::new(),
             duration: 10,
             bench_mode: BenchMode::Read,
+            auto_reconnect: false,
+            run_labels: String::new(),
+            run_note: String::new(),
+            profile_path: String::new(),
+            profile: BenchProfile::default(),
             test: None,
             probe_targets: None,
+            run_environment: None,
             results: Arc::new(Mutex::new(Vec::new())),
             is_running: false,
             is_connecting: false,
             connect_rx: None,
             connection_cancelled: false,
+            agent_addr: String::new(),
+            agent_rx: None,
+            agent_stop: None,
             was_running: false,
             error_message: None,
             current_throughput: 0.0,
@@ -112,8 +152,16 @@ impl SpeedTestApp {
             current_test_size: None,
             completed_chunks: Vec::new(),
             pass_aggregators: Vec::new(),
+            raw_samples: Vec::new(),
+            run_report: Arc::new(Mutex::new(None)),
+            history_pending: false,
+            export_include_samples: false,
             max_throughput: 0.0,
             console: ConsoleWindow::new(),
+            history_window: HistoryWindow::new(),
+            record_history: false,
+            overlays: ReportOverlays::default(),
+            image_export: ImageExportSettings::default(),
             ui_scale: 1.0,
             ui_scale_text: "1.0".to_string(),
             show_error_modal: false,
@@ -2,11 +2,14 @@ use anyhow::Result;
 use eframe::egui;
 
 use crate::ui::config_panel::render_config_panel;
+use crate::ui::console::log_to_console;
 use crate::ui::modal::show_modal;
 use crate::ui::results_panel::render_results_panel;
+use crate::ui::settings::{GuiSettings, SETTINGS_KEY};
 use crate::ui::test_management::handle_stats_update;
 use crate::ui::types::{
-    ConfigParams, PlotControls, ResultsPanelParams, StatsUpdateParams, TestState,
+    ConfigParams, ExportRequest, PlotControls, ProfileAction, ResultsPanelParams,
+    StatsUpdateParams, TestState,
 };
 
 #[cfg(feature = "branding")]
@@ -80,6 +83,8 @@ impl SpeedTestApp {
             viewport,
             renderer,
             wgpu_options,
+            // Window size follows the config / results view, so only app settings are restored.
+            persist_window: false,
             ..Default::default()
         };
 
@@ -112,9 +117,14 @@ impl SpeedTestApp {
 
                 crate::ui::win_utils::setup_window_controls();
 
-                Ok::<Box<dyn eframe::App>, Box<dyn std::error::Error + Send + Sync>>(Box::new(
-                    SpeedTestApp::new(),
-                ))
+                let mut app = SpeedTestApp::new();
+                if let Some(storage) = cc.storage
+                    && let Some(settings) = eframe::get_value::<GuiSettings>(storage, SETTINGS_KEY)
+                {
+                    settings.apply_to(&mut app);
+                }
+
+                Ok::<Box<dyn eframe::App>, Box<dyn std::error::Error + Send + Sync>>(Box::new(app))
             }),
         )
         .map_err(|e| anyhow::anyhow!("Failed to run GUI: {e}"))?;
@@ -124,6 +134,10 @@ impl SpeedTestApp {
 }
 
 impl eframe::App for SpeedTestApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, SETTINGS_KEY, &GuiSettings::from_app(self));
+    }
+
     fn logic(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Force dark mode if system is overriding it
         if !ctx.global_style().visuals.dark_mode {
@@ -147,6 +161,7 @@ impl eframe::App for SpeedTestApp {
         ctx.set_pixels_per_point(self.ui_scale * 1.3);
 
         self.poll_connection();
+        self.poll_agent_viewer();
 
         if self.is_running && !self.was_running {
             ctx.send_viewport_cmd(egui::ViewportCommand::MinInnerSize(egui::vec2(
@@ -217,6 +232,7 @@ impl eframe::App for SpeedTestApp {
                     completed_chunks: &mut self.completed_chunks,
                     last_console_stats_log: &mut self.last_console_stats_log,
                     pass_aggregators: &mut self.pass_aggregators,
+                    raw_samples: &mut self.raw_samples,
                 };
 
                 let stats_closed =
@@ -224,6 +240,9 @@ impl eframe::App for SpeedTestApp {
 
                 if stats_closed {
                     self.stop_test_impl();
+                    if self.record_history {
+                        self.record_history_when_done();
+                    }
                 }
             }
 
@@ -234,6 +253,10 @@ impl eframe::App for SpeedTestApp {
                 self.error_modal_message = error_msg;
                 self.stop_test_impl();
                 self.show_config = true;
+                // Passes finished before the error are still worth a history entry.
+                if self.record_history {
+                    self.record_history_when_done();
+                }
             }
         }
 
@@ -252,6 +275,18 @@ impl eframe::App for SpeedTestApp {
         let ctx = ui.ctx().clone();
 
         self.console.show(&ctx);
+        self.history_window.show(&ctx);
+
+        let dropped = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter_map(|file| file.path.clone())
+                .collect::<Vec<_>>()
+        });
+        for path in dropped {
+            self.overlays.load(&path);
+        }
 
         if self.show_error_modal {
             egui::CentralPanel::default().show_inside(ui, |_ui| {
@@ -283,7 +318,12 @@ impl eframe::App for SpeedTestApp {
                     connector: &mut self.connector,
                     pcileech_device: &mut self.pcileech_device,
                     bench_mode: &mut self.bench_mode,
+                    auto_reconnect: &mut self.auto_reconnect,
                     duration: &mut self.duration,
+                    run_labels: &mut self.run_labels,
+                    run_note: &mut self.run_note,
+                    profile_path: &mut self.profile_path,
+                    agent_addr: &mut self.agent_addr,
                     ui_scale: &mut self.ui_scale,
                     ui_scale_text: &mut self.ui_scale_text,
                     test_sizes: &mut self.test_sizes,
@@ -293,10 +333,32 @@ impl eframe::App for SpeedTestApp {
                     can_start,
                     is_connecting,
                 };
-                render_config_panel(ui, &mut config_params, || should_start_test = true);
+                let mut should_reset_settings = false;
+                let mut profile_action = None;
+                let mut should_view_agent = false;
+                render_config_panel(
+                    ui,
+                    &mut config_params,
+                    || should_start_test = true,
+                    || should_reset_settings = true,
+                    &mut profile_action,
+                    || should_view_agent = true,
+                );
+                match profile_action {
+                    Some(ProfileAction::Load) => self.load_profile_impl(),
+                    Some(ProfileAction::Save) => self.save_profile_impl(),
+                    None => {}
+                }
+                if should_reset_settings {
+                    GuiSettings::default().apply_to(self);
+                    log_to_console(&self.console, "Settings reset to defaults.");
+                }
                 if should_start_test {
                     self.start_test_impl();
                 }
+                if should_view_agent {
+                    self.start_remote_view_impl();
+                }
             });
         } else {
             egui::CentralPanel::default().show_inside(ui, |ui| {
@@ -306,8 +368,8 @@ impl eframe::App for SpeedTestApp {
                 let mut should_stop_test = false;
                 let mut should_start_test = false;
                 let mut should_toggle_console = false;
-                let mut should_export_csv = false;
-                let mut should_export_json = false;
+                let mut should_toggle_history = false;
+                let mut export_request = None;
 
                 let can_restart = self.can_start_test();
                 let plot_controls = PlotControls {
@@ -345,6 +407,11 @@ impl eframe::App for SpeedTestApp {
                     test_state,
                     test_sizes: &self.test_sizes,
                     show_config: &mut self.show_config,
+                    export_include_samples: &mut self.export_include_samples,
+                    history_visible: self.history_window.is_visible(),
+                    record_history: &mut self.record_history,
+                    overlays: &mut self.overlays,
+                    image_export: &mut self.image_export,
                 };
 
                 render_results_panel(
@@ -353,8 +420,8 @@ impl eframe::App for SpeedTestApp {
                     || should_stop_test = true,
                     || should_start_test = true,
                     &mut should_toggle_console,
-                    &mut should_export_csv,
-                    &mut should_export_json,
+                    &mut should_toggle_history,
+                    &mut export_request,
                 );
 
                 if should_stop_test {
@@ -366,17 +433,19 @@ impl eframe::App for SpeedTestApp {
                 if should_toggle_console {
                     self.console.toggle();
                 }
-                let mut report_exported = false;
-                if should_export_csv {
-                    self.export_report_impl(crate::speedtest::ReportFormat::Csv);
-                    report_exported = true;
-                }
-                if should_export_json {
-                    self.export_report_impl(crate::speedtest::ReportFormat::Json);
-                    report_exported = true;
+                if should_toggle_history {
+                    self.history_window.toggle();
                 }
-                if report_exported {
-                    ctx.request_repaint();
+                match export_request {
+                    Some(ExportRequest::Report(format)) => {
+                        self.export_report_impl(format);
+                        ctx.request_repaint();
+                    }
+                    Some(ExportRequest::Image) => {
+                        self.export_image_impl();
+                        ctx.request_repaint();
+                    }
+                    None => {}
                 }
             });
         }
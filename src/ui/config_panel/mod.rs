@@ -2,6 +2,9 @@ mod bench_mode;
 mod connector_section;
 mod header;
 mod panel;
+mod profile;
+mod remote_agent;
+mod run_labels;
 mod start_button;
 mod test_sizes;
 mod ui_scale;
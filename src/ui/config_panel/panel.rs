@@ -5,12 +5,16 @@ use egui_phosphor::regular::*;
 use crate::branding;
 
 use crate::speedtest::Connector;
-use crate::ui::types::ConfigParams;
+use crate::ui::constants::GUI_RECONNECT_ATTEMPTS;
+use crate::ui::types::{ConfigParams, ProfileAction};
 
 use super::{
     bench_mode::render_bench_mode_controls,
     connector_section::render_connector_section,
     header::render_header,
+    profile::render_profile_controls,
+    remote_agent::render_remote_agent_controls,
+    run_labels::render_run_labels,
     start_button::{StartButtonParams, render_start_button},
     test_sizes::render_test_size_controls,
     ui_scale::render_ui_scale_controls,
@@ -20,6 +24,9 @@ pub fn render_config_panel(
     ui: &mut egui::Ui,
     params: &mut ConfigParams<'_>,
     on_start_test: impl FnOnce(),
+    on_reset_settings: impl FnOnce(),
+    on_profile: &mut Option<ProfileAction>,
+    on_view_agent: impl FnOnce(),
 ) {
     render_header(ui);
 
@@ -49,6 +56,16 @@ pub fn render_config_panel(
             render_connector_section(ui, params.connector, params.pcileech_device);
             render_bench_mode_controls(ui, params.bench_mode);
             render_duration_slider(ui, params.duration);
+            render_reconnect_toggle(ui, params.auto_reconnect);
+            render_run_labels(ui, params.run_labels, params.run_note);
+            render_profile_controls(ui, params.profile_path, on_profile);
+            render_remote_agent_controls(
+                ui,
+                params.agent_addr,
+                params.can_start && !params.is_connecting,
+                params.show_config,
+                on_view_agent,
+            );
 
             render_test_size_controls(params.test_sizes, ui);
 
@@ -64,9 +81,37 @@ pub fn render_config_panel(
                 show_config: params.show_config,
             };
             render_start_button(ui, &mut start_button, on_start_test);
+
+            ui.add_space(8.0);
+            ui.vertical_centered(|ui| {
+                if ui
+                    .small_button(format!("{ARROW_COUNTER_CLOCKWISE} Reset to defaults"))
+                    .on_hover_text(
+                        "Restore connector, device, duration, mode, test sizes, UI scale and plot size.",
+                    )
+                    .clicked()
+                {
+                    on_reset_settings();
+                }
+            });
         });
 }
 
+fn render_reconnect_toggle(ui: &mut egui::Ui, auto_reconnect: &mut bool) {
+    ui.add_space(8.0);
+    ui.checkbox(
+        auto_reconnect,
+        format!("{ARROWS_CLOCKWISE} Reconnect if target is lost"),
+    );
+    ui.label(
+        egui::RichText::new(format!(
+            "If the target process exits or the device drops, reconnect up to {GUI_RECONNECT_ATTEMPTS} times and resume the remaining passes."
+        ))
+        .small()
+        .weak(),
+    );
+}
+
 fn render_duration_slider(ui: &mut egui::Ui, duration: &mut u64) {
     ui.add_space(8.0);
     ui.label(format!("{CLOCK} Test Duration"));
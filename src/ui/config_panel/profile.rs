@@ -0,0 +1,48 @@
+use eframe::egui;
+use egui_phosphor::regular::*;
+
+#[cfg(feature = "branding")]
+use crate::branding;
+
+use crate::ui::types::ProfileAction;
+
+/// Path field with Load / Save buttons for `.toml` / `.json` benchmark profiles.
+pub fn render_profile_controls(
+    ui: &mut egui::Ui,
+    profile_path: &mut String,
+    on_profile: &mut Option<ProfileAction>,
+) {
+    ui.add_space(8.0);
+    ui.label(format!("{FILE_TEXT} Profile"));
+    ui.horizontal(|ui| {
+        ui.scope(|ui| {
+            #[cfg(feature = "branding")]
+            {
+                let (r, g, b) = branding::BACKGROUND_COLOR;
+                let alpha = (branding::UI_ELEMENT_OPACITY * 255.0) as u8;
+                ui.visuals_mut().extreme_bg_color =
+                    egui::Color32::from_rgba_unmultiplied(r, g, b, alpha);
+            }
+            ui.add(
+                egui::TextEdit::singleline(profile_path)
+                    .hint_text("profiles/bench.toml")
+                    .desired_width(ui.available_width() - 140.0),
+            );
+        });
+        let has_path = !profile_path.trim().is_empty();
+        if ui
+            .add_enabled(has_path, egui::Button::new(format!("{FOLDER_OPEN} Load")))
+            .on_hover_text("Apply connector, device, mode, sizes and duration from the file.")
+            .clicked()
+        {
+            *on_profile = Some(ProfileAction::Load);
+        }
+        if ui
+            .add_enabled(has_path, egui::Button::new(format!("{FLOPPY_DISK} Save")))
+            .on_hover_text("Write the current settings; fields the GUI does not edit are kept.")
+            .clicked()
+        {
+            *on_profile = Some(ProfileAction::Save);
+        }
+    });
+}
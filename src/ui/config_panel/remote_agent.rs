@@ -0,0 +1,44 @@
+use eframe::egui;
+use egui_phosphor::regular::*;
+
+#[cfg(feature = "branding")]
+use crate::branding;
+
+/// Address field and button to follow a CLI run started with `--agent-listen`.
+pub fn render_remote_agent_controls(
+    ui: &mut egui::Ui,
+    agent_addr: &mut String,
+    can_start: bool,
+    show_config: &mut bool,
+    on_view_agent: impl FnOnce(),
+) {
+    ui.add_space(8.0);
+    ui.label(format!("{DESKTOP} Remote agent"));
+    ui.horizontal(|ui| {
+        ui.scope(|ui| {
+            #[cfg(feature = "branding")]
+            {
+                let (r, g, b) = branding::BACKGROUND_COLOR;
+                let alpha = (branding::UI_ELEMENT_OPACITY * 255.0) as u8;
+                ui.visuals_mut().extreme_bg_color =
+                    egui::Color32::from_rgba_unmultiplied(r, g, b, alpha);
+            }
+            ui.add(
+                egui::TextEdit::singleline(agent_addr)
+                    .hint_text("rig-host:9900")
+                    .desired_width(ui.available_width() - 90.0),
+            );
+        });
+        let enabled = can_start && !agent_addr.trim().is_empty();
+        if ui
+            .add_enabled(enabled, egui::Button::new(format!("{PLUG} Connect")))
+            .on_hover_text(
+                "Follow the run of a CLI started with --agent-listen; its mode, sizes and duration are adopted.",
+            )
+            .clicked()
+        {
+            *show_config = false;
+            on_view_agent();
+        }
+    });
+}
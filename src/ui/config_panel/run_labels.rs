@@ -0,0 +1,35 @@
+use eframe::egui;
+use egui_phosphor::regular::*;
+
+#[cfg(feature = "branding")]
+use crate::branding;
+
+use crate::speedtest::parse_labels_csv;
+
+/// Labels and note stored with exported and recorded reports.
+pub fn render_run_labels(ui: &mut egui::Ui, labels: &mut String, note: &mut String) {
+    ui.add_space(8.0);
+    ui.label(format!("{TAG} Run Labels"));
+    ui.scope(|ui| {
+        #[cfg(feature = "branding")]
+        {
+            let (r, g, b) = branding::BACKGROUND_COLOR;
+            let alpha = (branding::UI_ELEMENT_OPACITY * 255.0) as u8;
+            ui.visuals_mut().extreme_bg_color =
+                egui::Color32::from_rgba_unmultiplied(r, g, b, alpha);
+        }
+        ui.add(
+            egui::TextEdit::singleline(labels)
+                .hint_text("card=A1, slot=2")
+                .desired_width(f32::INFINITY),
+        );
+        if let Err(e) = parse_labels_csv(labels) {
+            ui.colored_label(egui::Color32::from_rgb(231, 76, 60), e);
+        }
+        ui.add(
+            egui::TextEdit::singleline(note)
+                .hint_text("Note")
+                .desired_width(f32::INFINITY),
+        );
+    });
+}
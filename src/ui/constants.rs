@@ -37,6 +37,9 @@ pub const MAX_CONSOLE_LOG_ENTRIES: usize = 2000;
 /// Minimum interval between live stats lines for the same chunk (seconds).
 pub const CONSOLE_STATS_LOG_INTERVAL_SECS: f64 = 1.0;
 
+/// Reconnect attempts per run when "Reconnect if target is lost" is enabled.
+pub const GUI_RECONNECT_ATTEMPTS: u32 = 3;
+
 // Plot options
 pub const PLOT_SHOW_GRID: bool = true;
 pub const PLOT_ALLOW_DRAG: bool = false;
@@ -1,3 +1,4 @@
+use crate::speedtest::BenchErrorKind;
 use eframe::egui;
 
 pub fn color_for_size(size: usize) -> egui::Color32 {
@@ -16,3 +17,15 @@ pub fn color_for_size(size: usize) -> egui::Color32 {
 }
 
 pub use crate::bench_config::format_chunk_size as get_size_label;
+
+/// `message` followed by the category and common fixes when `error` has a [`BenchErrorKind`].
+pub fn error_message_with_hints(message: String, error: &anyhow::Error) -> String {
+    let Some(kind) = BenchErrorKind::of(error) else {
+        return message;
+    };
+    let mut out = format!("{message}\n\nCommon fixes ({}):", kind.label());
+    for (index, hint) in kind.hints().iter().enumerate() {
+        out.push_str(&format!("\n  {}. {hint}", index + 1));
+    }
+    out
+}
@@ -0,0 +1,210 @@
+use eframe::egui;
+use egui_plot::{Line, Plot, PlotPoints, Points};
+
+use crate::bench_config::format_chunk_size;
+use crate::speedtest::{
+    BenchOp, CompareMetric, DEFAULT_HISTORY_PATH, HistoryEntry, RunHistory, format_labels,
+    metric_trend,
+};
+
+/// Run history browser: recorded runs plus the trend of one `(op, size)` metric.
+pub struct HistoryWindow {
+    history: RunHistory,
+    entries: Vec<HistoryEntry>,
+    load_error: Option<String>,
+    visible: bool,
+    op: BenchOp,
+    chunk_bytes: Option<usize>,
+    metric: CompareMetric,
+}
+
+impl Default for HistoryWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HistoryWindow {
+    pub fn new() -> Self {
+        Self {
+            history: RunHistory::new(DEFAULT_HISTORY_PATH),
+            entries: Vec::new(),
+            load_error: None,
+            visible: false,
+            op: BenchOp::Read,
+            chunk_bytes: None,
+            metric: CompareMetric::Throughput,
+        }
+    }
+
+    pub fn history(&self) -> &RunHistory {
+        &self.history
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        if self.visible {
+            self.reload();
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn reload(&mut self) {
+        match self.history.load() {
+            Ok(entries) => {
+                self.entries = entries;
+                self.load_error = None;
+            }
+            Err(e) => self.load_error = Some(e.to_string()),
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if !self.visible {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Run history")
+            .open(&mut open)
+            .default_pos([80.0, 80.0])
+            .default_size([900.0, 600.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(self.history.path().display().to_string());
+                    if ui.button("Reload").clicked() {
+                        self.reload();
+                    }
+                });
+                if let Some(error) = &self.load_error {
+                    ui.colored_label(egui::Color32::from_rgb(231, 76, 60), error);
+                }
+                ui.separator();
+
+                if self.entries.is_empty() {
+                    ui.label("No runs recorded yet. Enable \"Record history\" before a test.");
+                    return;
+                }
+
+                self.render_run_table(ui);
+                ui.separator();
+                self.render_trend(ui);
+            });
+        self.visible = open;
+    }
+
+    fn render_run_table(&self, ui: &mut egui::Ui) {
+        egui::ScrollArea::vertical()
+            .id_salt("history_runs")
+            .max_height(220.0)
+            .show(ui, |ui| {
+                egui::Grid::new("history_runs_grid")
+                    .striped(true)
+                    .num_columns(7)
+                    .show(ui, |ui| {
+                        for heading in [
+                            "Run",
+                            "Generated",
+                            "Connector",
+                            "Mode",
+                            "Host",
+                            "Passes",
+                            "Labels",
+                        ] {
+                            ui.strong(heading);
+                        }
+                        ui.end_row();
+
+                        for entry in self.entries.iter().rev() {
+                            let report = &entry.report;
+                            ui.label(entry.id.to_string());
+                            ui.label(report.generated_unix_secs.to_string());
+                            ui.label(&report.connector);
+                            ui.label(&report.mode);
+                            ui.label(
+                                report
+                                    .environment
+                                    .as_ref()
+                                    .map(|environment| environment.hostname.as_str())
+                                    .unwrap_or("-"),
+                            );
+                            ui.label(report.passes.len().to_string());
+                            let labels = ui.label(format_labels(&report.labels));
+                            if let Some(note) = &report.note {
+                                labels.on_hover_text(note);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn render_trend(&mut self, ui: &mut egui::Ui) {
+        let mut sizes = self
+            .entries
+            .iter()
+            .flat_map(|entry| entry.report.passes.iter())
+            .filter(|pass| pass.op == self.op)
+            .map(|pass| pass.chunk_bytes)
+            .collect::<Vec<_>>();
+        sizes.sort_unstable();
+        sizes.dedup();
+        if self.chunk_bytes.is_none_or(|size| !sizes.contains(&size)) {
+            self.chunk_bytes = sizes.first().copied();
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Trend");
+            egui::ComboBox::from_id_salt("history_trend_op")
+                .selected_text(self.op.label())
+                .show_ui(ui, |ui| {
+                    for op in [BenchOp::Read, BenchOp::Write] {
+                        ui.selectable_value(&mut self.op, op, op.label());
+                    }
+                });
+            egui::ComboBox::from_id_salt("history_trend_size")
+                .selected_text(self.chunk_bytes.map(format_chunk_size).unwrap_or_default())
+                .show_ui(ui, |ui| {
+                    for size in &sizes {
+                        ui.selectable_value(
+                            &mut self.chunk_bytes,
+                            Some(*size),
+                            format_chunk_size(*size),
+                        );
+                    }
+                });
+            egui::ComboBox::from_id_salt("history_trend_metric")
+                .selected_text(self.metric.label())
+                .show_ui(ui, |ui| {
+                    for metric in CompareMetric::ALL {
+                        ui.selectable_value(&mut self.metric, metric, metric.label());
+                    }
+                });
+        });
+
+        let Some(chunk_bytes) = self.chunk_bytes else {
+            ui.label(format!("No {} passes recorded.", self.op.label()));
+            return;
+        };
+        let points = metric_trend(&self.entries, self.op, chunk_bytes, self.metric)
+            .iter()
+            .map(|point| [point.run_id as f64, point.value])
+            .collect::<Vec<_>>();
+
+        Plot::new("history_trend_plot")
+            .height(240.0)
+            .x_axis_label("Run")
+            .y_axis_label(self.metric.label())
+            .include_y(0.0)
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(
+                    self.metric.label(),
+                    PlotPoints::from(points.clone()),
+                ));
+                plot_ui.points(Points::new(self.metric.label(), points).radius(3.0));
+            });
+    }
+}
@@ -3,9 +3,12 @@ pub mod config_panel;
 pub mod console;
 pub mod constants;
 pub mod helpers;
+pub mod history;
 pub mod modal;
+pub mod overlays;
 pub mod plot_controls;
 pub mod results_panel;
+pub mod settings;
 pub mod test_management;
 pub mod types;
 pub mod win_utils;
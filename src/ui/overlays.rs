@@ -0,0 +1,133 @@
+use std::path::{Path, PathBuf};
+
+use crate::speedtest::{
+    BenchOp, BenchSample, BenchmarkReport, PassSummary, format_labels, load_report_from_path,
+};
+use crate::ui::types::PlotMetric;
+
+/// A saved JSON report drawn as ghost series next to the live run.
+pub struct ReferenceReport {
+    pub name: String,
+    pub path: PathBuf,
+    pub report: BenchmarkReport,
+}
+
+impl ReferenceReport {
+    /// Labels and note for hover text; empty for untagged reports.
+    pub fn tags(&self) -> String {
+        let labels = format_labels(&self.report.labels);
+        match &self.report.note {
+            Some(note) if labels.is_empty() => note.clone(),
+            Some(note) => format!("{labels}\n{note}"),
+            None => labels,
+        }
+    }
+
+    /// `(op, chunk_bytes, points)` per pass: raw samples when the report has them,
+    /// otherwise a flat line at the pass average over its measured time.
+    pub fn series(&self, metric: PlotMetric) -> Vec<(BenchOp, usize, Vec<[f64; 2]>)> {
+        self.report
+            .passes
+            .iter()
+            .map(|pass| {
+                let same_pass = |sample: &&BenchSample| {
+                    sample.op == pass.op
+                        && sample.chunk_bytes == pass.chunk_bytes
+                        && sample.step == pass.step
+                };
+                let sampled = self
+                    .report
+                    .samples
+                    .iter()
+                    .flatten()
+                    .filter(same_pass)
+                    .map(|sample| {
+                        let value = match metric {
+                            PlotMetric::Throughput => sample.throughput_mib_s,
+                            PlotMetric::Reads => sample.ops_per_sec as f64,
+                            PlotMetric::Latency => sample.latency_us,
+                        };
+                        [sample.elapsed_secs, value]
+                    })
+                    .collect::<Vec<_>>();
+                let points = if sampled.is_empty() {
+                    let avg = summary_stats(pass, metric).1;
+                    vec![[0.0, avg], [pass.measured_secs, avg]]
+                } else {
+                    sampled
+                };
+                (pass.op, pass.chunk_bytes, points)
+            })
+            .collect()
+    }
+}
+
+/// `(min, avg, max)` of one pass for a plot metric.
+pub fn summary_stats(pass: &PassSummary, metric: PlotMetric) -> (f64, f64, f64) {
+    match metric {
+        PlotMetric::Throughput => (pass.min_mib_s, pass.avg_mib_s, pass.max_mib_s),
+        PlotMetric::Reads => (pass.min_ops_s, pass.avg_ops_s, pass.max_ops_s),
+        PlotMetric::Latency => (
+            pass.min_latency_us,
+            pass.avg_latency_us,
+            pass.max_latency_us,
+        ),
+    }
+}
+
+/// Reference reports loaded from disk or dropped onto the window.
+#[derive(Default)]
+pub struct ReportOverlays {
+    pub references: Vec<ReferenceReport>,
+    /// Path typed into the "Overlay report" field.
+    pub path_input: String,
+    pub last_error: Option<String>,
+}
+
+impl ReportOverlays {
+    pub fn load(&mut self, path: &Path) {
+        if self
+            .references
+            .iter()
+            .any(|reference| reference.path == path)
+        {
+            return;
+        }
+        match load_report_from_path(path) {
+            Ok(report) => {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string());
+                self.references.push(ReferenceReport {
+                    name,
+                    path: path.to_path_buf(),
+                    report,
+                });
+                self.last_error = None;
+            }
+            Err(e) => self.last_error = Some(e.to_string()),
+        }
+    }
+
+    pub fn load_input(&mut self) {
+        let input = self.path_input.trim().trim_matches('"').to_string();
+        if input.is_empty() {
+            return;
+        }
+        self.load(Path::new(&input));
+        if self.last_error.is_none() {
+            self.path_input.clear();
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.references.len() {
+            self.references.remove(index);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.references.is_empty()
+    }
+}
@@ -1,4 +1,5 @@
-use crate::ui::types::ResultsPanelParams;
+use crate::speedtest::{IMAGE_WIDTH_RANGE, ImageFormat};
+use crate::ui::types::{ExportRequest, ImageExportSettings, ImageTarget, ResultsPanelParams};
 use eframe::egui;
 use egui_phosphor::regular::*;
 
@@ -6,6 +7,7 @@ pub fn render_console_and_scale_controls(
     ui: &mut egui::Ui,
     params: &mut ResultsPanelParams<'_>,
     on_toggle_console: &mut bool,
+    on_toggle_history: &mut bool,
 ) {
     ui.horizontal(|ui| {
         let console_text = if params.console.is_visible() {
@@ -20,6 +22,20 @@ pub fn render_console_and_scale_controls(
             *on_toggle_console = true;
         }
 
+        let history_text = if params.history_visible {
+            format!("{EYE_SLASH} Hide History")
+        } else {
+            format!("{CLOCK_COUNTER_CLOCKWISE} History")
+        };
+        if ui
+            .add_sized([140.0, 35.0], egui::Button::new(history_text))
+            .clicked()
+        {
+            *on_toggle_history = true;
+        }
+        ui.checkbox(params.record_history, "Record history")
+            .on_hover_text("Append each finished run to reports/history.jsonl.");
+
         ui.separator();
 
         ui.label(format!("{MAGNIFYING_GLASS} UI Scale"));
@@ -49,3 +65,39 @@ pub fn render_console_and_scale_controls(
         }
     });
 }
+
+/// Target, format and width for PNG/SVG export of one plot or results table.
+pub fn render_image_export_controls(
+    ui: &mut egui::Ui,
+    settings: &mut ImageExportSettings,
+    on_export: &mut Option<ExportRequest>,
+) {
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        ui.label(format!("{IMAGE} Image"));
+        egui::ComboBox::from_id_salt("image_export_target")
+            .selected_text(settings.target.label())
+            .show_ui(ui, |ui| {
+                for target in ImageTarget::ALL {
+                    ui.selectable_value(&mut settings.target, target, target.label());
+                }
+            });
+        for format in [ImageFormat::Png, ImageFormat::Svg] {
+            ui.selectable_value(
+                &mut settings.format,
+                format,
+                format.extension().to_uppercase(),
+            );
+        }
+        ui.add(
+            egui::DragValue::new(&mut settings.width_px)
+                .range(IMAGE_WIDTH_RANGE)
+                .speed(10.0)
+                .suffix(" px"),
+        )
+        .on_hover_text("Image width; height follows the plot or table.");
+        if ui.button(format!("{FLOPPY_DISK} Save image")).clicked() {
+            *on_export = Some(ExportRequest::Image);
+        }
+    });
+}
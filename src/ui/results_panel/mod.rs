@@ -1,5 +1,6 @@
 pub mod controls;
 pub mod metrics;
+pub mod overlays;
 pub mod panel;
 pub mod plot;
 pub mod progress;
@@ -0,0 +1,57 @@
+use crate::ui::overlays::ReportOverlays;
+use eframe::egui;
+use egui_phosphor::regular::*;
+
+/// Path field plus one removable chip per loaded reference report.
+pub fn render_overlay_controls(ui: &mut egui::Ui, overlays: &mut ReportOverlays) {
+    ui.add_space(6.0);
+    ui.horizontal(|ui| {
+        ui.label(format!("{STACK} Overlay report"));
+        let input = ui.add(
+            egui::TextEdit::singleline(&mut overlays.path_input)
+                .hint_text("reports/reference.json")
+                .desired_width(260.0),
+        );
+        let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        if ui.button(format!("{PLUS} Add")).clicked() || submitted {
+            overlays.load_input();
+        }
+        if overlays.is_empty() {
+            ui.label(
+                egui::RichText::new("or drop JSON reports onto the window")
+                    .small()
+                    .weak(),
+            );
+        }
+    });
+
+    if let Some(error) = &overlays.last_error {
+        ui.colored_label(egui::Color32::from_rgb(231, 76, 60), error);
+    }
+
+    let mut remove = None;
+    ui.horizontal_wrapped(|ui| {
+        for (index, reference) in overlays.references.iter().enumerate() {
+            let samples = if reference.report.samples.is_some() {
+                "raw samples"
+            } else {
+                "pass averages only"
+            };
+            let mut hover = format!("{} ({samples})", reference.path.display());
+            let tags = reference.tags();
+            if !tags.is_empty() {
+                hover = format!("{hover}\n{tags}");
+            }
+            if ui
+                .button(format!("{X} {}", reference.name))
+                .on_hover_text(hover)
+                .clicked()
+            {
+                remove = Some(index);
+            }
+        }
+    });
+    if let Some(index) = remove {
+        overlays.remove(index);
+    }
+}
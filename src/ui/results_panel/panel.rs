@@ -1,9 +1,14 @@
 use super::{
-    controls::render_console_and_scale_controls, metrics::render_running_metrics,
-    plot::render_plot_column, progress::render_chunk_progress, table::render_results_table,
+    controls::{render_console_and_scale_controls, render_image_export_controls},
+    metrics::render_running_metrics,
+    overlays::render_overlay_controls,
+    plot::render_plot_column,
+    progress::render_chunk_progress,
+    table::render_results_table,
 };
+use crate::speedtest::ReportFormat;
 use crate::ui::plot_controls::render_plot_size_controls;
-use crate::ui::types::{PlotMetric, ResultsPanelParams};
+use crate::ui::types::{ExportRequest, PlotMetric, ResultsPanelParams};
 use eframe::egui;
 use egui_phosphor::regular::*;
 
@@ -16,8 +21,8 @@ pub fn render_results_panel(
     on_stop_test: impl FnOnce(),
     on_test_again: impl FnOnce(),
     on_toggle_console: &mut bool,
-    on_export_csv: &mut bool,
-    on_export_json: &mut bool,
+    on_toggle_history: &mut bool,
+    on_export: &mut Option<ExportRequest>,
 ) {
     ui.vertical_centered(|ui| {
         ui.add_space(5.0);
@@ -55,7 +60,13 @@ pub fn render_results_panel(
                     );
 
                     ui.add_space(8.0);
-                    render_console_and_scale_controls(ui, params, on_toggle_console);
+                    render_console_and_scale_controls(
+                        ui,
+                        params,
+                        on_toggle_console,
+                        on_toggle_history,
+                    );
+                    render_overlay_controls(ui, params.overlays);
 
                     if params.test_state.is_connecting {
                         ui.separator();
@@ -136,7 +147,7 @@ pub fn render_results_panel(
                             egui::Color32::from_rgb(41, 128, 185),
                         ));
                         if ui.add_sized([100.0, 40.0], csv_button).clicked() {
-                            *on_export_csv = true;
+                            *on_export = Some(ExportRequest::Report(ReportFormat::Csv));
                         }
 
                         ui.add_space(8.0);
@@ -151,12 +162,40 @@ pub fn render_results_panel(
                             egui::Color32::from_rgb(142, 68, 173),
                         ));
                         if ui.add_sized([110.0, 40.0], json_button).clicked() {
-                            *on_export_json = true;
+                            *on_export = Some(ExportRequest::Report(ReportFormat::Json));
                         }
+
+                        ui.add_space(8.0);
+
+                        let html_button = egui::Button::new(
+                            egui::RichText::new(format!("{FLOPPY_DISK} HTML"))
+                                .color(egui::Color32::BLACK),
+                        )
+                        .fill(egui::Color32::from_rgb(230, 126, 34))
+                        .stroke(egui::Stroke::new(
+                            2.0_f32,
+                            egui::Color32::from_rgb(211, 84, 0),
+                        ));
+                        if ui
+                            .add_sized([110.0, 40.0], html_button)
+                            .on_hover_text("Single file with charts; no external assets.")
+                            .clicked()
+                        {
+                            *on_export = Some(ExportRequest::Report(ReportFormat::Html));
+                        }
+
+                        ui.add_space(8.0);
+                        ui.checkbox(params.export_include_samples, "Raw samples")
+                            .on_hover_text(
+                                "Include every 100 ms interval sample (JSON body, a companion -samples.csv, or HTML time-series charts).",
+                            );
                     }
                 });
             });
 
+            if params.test_state.test_end_time.is_some() && !params.test_state.is_running {
+                render_image_export_controls(ui, params.image_export, on_export);
+            }
             render_report_export_status(ui, params);
         });
 
@@ -168,33 +207,27 @@ pub fn render_results_panel(
 
         let specs = [
             (
-                "Throughput (MiB/s)",
                 "throughput_plot",
                 PlotMetric::Throughput,
                 "throughput_results",
-                "Results (MiB/s)",
-            ),
-            (
-                "Ops per Second",
-                "reads_plot",
-                PlotMetric::Reads,
-                "reads_results",
-                "Results (ops/s)",
-            ),
-            (
-                "Latency (μs)",
-                "latency_plot",
-                PlotMetric::Latency,
-                "latency_results",
-                "Results (μs)",
             ),
+            ("reads_plot", PlotMetric::Reads, "reads_results"),
+            ("latency_plot", PlotMetric::Latency, "latency_results"),
         ];
 
-        for (column, (heading, plot_id, metric, table_id, title)) in columns.iter_mut().zip(specs) {
+        for (column, (plot_id, metric, table_id)) in columns.iter_mut().zip(specs) {
             column.vertical(|ui| {
-                render_plot_column(ui, heading, plot_id, metric, params, width, height);
+                render_plot_column(
+                    ui,
+                    metric.plot_title(),
+                    plot_id,
+                    metric,
+                    params,
+                    width,
+                    height,
+                );
                 ui.add_space(10.0);
-                render_results_table(ui, table_id, params.results, metric, title);
+                render_results_table(ui, table_id, params.results, params.overlays, metric);
             });
         }
     });
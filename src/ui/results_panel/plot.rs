@@ -1,8 +1,10 @@
+use crate::speedtest::ImageSeries;
 use crate::ui::constants::*;
 use crate::ui::helpers::{color_for_size, get_size_label};
-use crate::ui::types::{PlotMetric, ResultsPanelParams, TestResults};
+use crate::ui::overlays::ReportOverlays;
+use crate::ui::types::{PlotMetric, PlotParams, ResultsPanelParams, TestResults};
 use eframe::egui;
-use egui_plot::{Corner, Legend, Line, Plot, PlotPoints};
+use egui_plot::{Corner, Legend, Line, LineStyle, Plot, PlotPoints, PlotUi};
 
 #[cfg(feature = "branding")]
 use crate::branding;
@@ -21,11 +23,14 @@ pub fn render_plot_column(
     render_plot(
         ui,
         plot_id,
-        params.results,
-        metric,
-        params.duration,
-        width,
-        height,
+        &PlotParams {
+            results: params.results,
+            overlays: params.overlays,
+            metric,
+            duration: params.duration,
+            width,
+            height,
+        },
     );
 }
 
@@ -43,7 +48,7 @@ fn base_plot(id: &'static str, duration: u64, width: f32, height: f32) -> Plot<'
         .allow_double_click_reset(PLOT_ALLOW_DOUBLE_CLICK_RESET)
 }
 
-fn y_range_for(results: &TestResults, metric: PlotMetric) -> (f64, f64) {
+fn y_range_for(results: &TestResults, overlays: &ReportOverlays, metric: PlotMetric) -> (f64, f64) {
     let mut min_v: Option<f64> = None;
     let mut max_v: Option<f64> = None;
     if let Ok(results) = results.lock() {
@@ -59,6 +64,14 @@ fn y_range_for(results: &TestResults, metric: PlotMetric) -> (f64, f64) {
             }
         }
     }
+    for reference in &overlays.references {
+        for (_, _, points) in reference.series(metric) {
+            for [_, y] in points {
+                min_v = Some(min_v.map_or(y, |m| m.min(y)));
+                max_v = Some(max_v.map_or(y, |m| m.max(y)));
+            }
+        }
+    }
     match (min_v, max_v) {
         (Some(miny), Some(maxy)) if maxy > miny => {
             let range = maxy - miny;
@@ -68,16 +81,16 @@ fn y_range_for(results: &TestResults, metric: PlotMetric) -> (f64, f64) {
     }
 }
 
-fn render_plot(
-    ui: &mut egui::Ui,
-    plot_id: &'static str,
-    results: &TestResults,
-    metric: PlotMetric,
-    duration: u64,
-    width: f32,
-    height: f32,
-) {
-    let (y_min, y_max) = y_range_for(results, metric);
+fn render_plot(ui: &mut egui::Ui, plot_id: &'static str, params: &PlotParams<'_>) {
+    let PlotParams {
+        results,
+        overlays,
+        metric,
+        duration,
+        width,
+        height,
+    } = *params;
+    let (y_min, y_max) = y_range_for(results, overlays, metric);
 
     #[cfg(feature = "branding")]
     {
@@ -91,6 +104,7 @@ fn render_plot(
         .include_y(y_max)
         .legend(Legend::default().position(Corner::RightTop))
         .show(ui, |plot_ui| {
+            render_reference_series(plot_ui, overlays, metric);
             if let Ok(results) = results.lock() {
                 let mut sorted_results: Vec<_> = results.iter().collect();
                 sorted_results.sort_by_key(|k| std::cmp::Reverse(k.1));
@@ -113,3 +127,73 @@ fn render_plot(
             }
         });
 }
+
+/// Faded dashed lines in the chunk size colour, drawn under the live series.
+fn render_reference_series(
+    plot_ui: &mut PlotUi<'_>,
+    overlays: &ReportOverlays,
+    metric: PlotMetric,
+) {
+    for reference in &overlays.references {
+        for (op, chunk_bytes, points) in reference.series(metric) {
+            let legend = format!(
+                "{} {} ({})",
+                op.label(),
+                get_size_label(chunk_bytes),
+                reference.name
+            );
+            plot_ui.line(
+                Line::new(legend, PlotPoints::from(points))
+                    .color(color_for_size(chunk_bytes).gamma_multiply(0.45))
+                    .width(1.5_f32)
+                    .style(LineStyle::dashed_loose()),
+            );
+        }
+    }
+}
+
+/// Live series plus reference overlays as shown in one plot, for image export.
+pub fn plot_image_series(
+    results: &TestResults,
+    overlays: &ReportOverlays,
+    metric: PlotMetric,
+) -> Vec<ImageSeries> {
+    let mut series = Vec::new();
+    if let Ok(results) = results.lock() {
+        let mut sorted_results: Vec<_> = results.iter().collect();
+        sorted_results.sort_by_key(|k| std::cmp::Reverse(k.1));
+        for (op, read_size, (throughput_points, reads_points, latency_points)) in sorted_results {
+            let points = match metric {
+                PlotMetric::Throughput => throughput_points,
+                PlotMetric::Reads => reads_points,
+                PlotMetric::Latency => latency_points,
+            };
+            if !points.is_empty() {
+                let color = color_for_size(*read_size);
+                series.push(ImageSeries {
+                    label: format!("{} {}", op.label(), get_size_label(*read_size)),
+                    rgb: [color.r(), color.g(), color.b()],
+                    points: points.clone(),
+                    dashed: false,
+                });
+            }
+        }
+    }
+    for reference in &overlays.references {
+        for (op, chunk_bytes, points) in reference.series(metric) {
+            let color = color_for_size(chunk_bytes);
+            series.push(ImageSeries {
+                label: format!(
+                    "{} {} ({})",
+                    op.label(),
+                    get_size_label(chunk_bytes),
+                    reference.name
+                ),
+                rgb: [color.r(), color.g(), color.b()],
+                points: points.into_iter().map(|[x, y]| (x, y)).collect(),
+                dashed: true,
+            });
+        }
+    }
+    series
+}
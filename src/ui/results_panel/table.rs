@@ -1,61 +1,102 @@
 use crate::ui::helpers::get_size_label;
+use crate::ui::overlays::{ReportOverlays, summary_stats};
 use crate::ui::types::{PlotMetric, TestResults};
 use eframe::egui;
 
+pub const TABLE_HEADERS: [&str; 5] = ["Op", "Size", "Min", "Avg", "Max"];
+
+/// Table rows as text: live passes (largest size first), then reference passes.
+/// The flag marks reference rows.
+pub fn results_table_rows(
+    results: &TestResults,
+    overlays: &ReportOverlays,
+    metric: PlotMetric,
+) -> Vec<([String; 5], bool)> {
+    let mut rows = Vec::new();
+    if let Ok(results) = results.lock() {
+        let mut sorted_results: Vec<_> = results.iter().collect();
+        sorted_results.sort_by_key(|k| std::cmp::Reverse(k.1));
+        for (op, read_size, (throughput_points, reads_points, latency_points)) in sorted_results {
+            let points = match metric {
+                PlotMetric::Throughput => throughput_points,
+                PlotMetric::Reads => reads_points,
+                PlotMetric::Latency => latency_points,
+            };
+
+            if !points.is_empty() {
+                let max_val = points.iter().map(|&(_, y)| y).fold(0.0, f64::max);
+                let min_val = points.iter().map(|&(_, y)| y).fold(f64::INFINITY, f64::min);
+                let avg_val = points.iter().map(|&(_, y)| y).sum::<f64>() / points.len() as f64;
+                rows.push((
+                    row(op.label(), *read_size, metric, (min_val, avg_val, max_val)),
+                    false,
+                ));
+            }
+        }
+    }
+
+    for reference in &overlays.references {
+        for pass in &reference.report.passes {
+            let op = match reference.report.step_name(pass.step) {
+                Some(step) => format!("{} ({}, {step})", pass.op.label(), reference.name),
+                None => format!("{} ({})", pass.op.label(), reference.name),
+            };
+            rows.push((
+                row(&op, pass.chunk_bytes, metric, summary_stats(pass, metric)),
+                true,
+            ));
+        }
+    }
+    rows
+}
+
+fn row(
+    op: &str,
+    chunk_bytes: usize,
+    metric: PlotMetric,
+    (min_val, avg_val, max_val): (f64, f64, f64),
+) -> [String; 5] {
+    let [min, avg, max] = match metric {
+        PlotMetric::Reads => [
+            format!("{}", min_val as u64),
+            format!("{avg_val:.0}"),
+            format!("{}", max_val as u64),
+        ],
+        _ => [
+            format!("{min_val:.1}"),
+            format!("{avg_val:.1}"),
+            format!("{max_val:.1}"),
+        ],
+    };
+    [op.to_string(), get_size_label(chunk_bytes), min, avg, max]
+}
+
 pub fn render_results_table(
     ui: &mut egui::Ui,
     table_id: &str,
     results: &TestResults,
+    overlays: &ReportOverlays,
     metric: PlotMetric,
-    title: &str,
 ) {
-    ui.heading(title);
-    if let Ok(results) = results.lock() {
-        egui::Grid::new(table_id)
-            .striped(true)
-            .spacing([15.0, 4.0])
-            .show(ui, |ui| {
-                ui.label("Op");
-                ui.label("Size");
-                ui.label("Min");
-                ui.label("Avg");
-                ui.label("Max");
-                ui.end_row();
+    ui.heading(metric.table_title());
+    egui::Grid::new(table_id)
+        .striped(true)
+        .spacing([15.0, 4.0])
+        .show(ui, |ui| {
+            for header in TABLE_HEADERS {
+                ui.label(header);
+            }
+            ui.end_row();
 
-                let mut sorted_results: Vec<_> = results.iter().collect();
-                sorted_results.sort_by_key(|k| std::cmp::Reverse(k.1));
-                for (op, read_size, (throughput_points, reads_points, latency_points)) in
-                    sorted_results
-                {
-                    let points = match metric {
-                        PlotMetric::Throughput => throughput_points,
-                        PlotMetric::Reads => reads_points,
-                        PlotMetric::Latency => latency_points,
-                    };
-
-                    if !points.is_empty() {
-                        let max_val = points.iter().map(|&(_, y)| y).fold(0.0, f64::max);
-                        let min_val = points.iter().map(|&(_, y)| y).fold(f64::INFINITY, f64::min);
-                        let avg_val =
-                            points.iter().map(|&(_, y)| y).sum::<f64>() / points.len() as f64;
-
-                        ui.label(op.label());
-                        ui.label(get_size_label(*read_size));
-                        match metric {
-                            PlotMetric::Reads => {
-                                ui.label(format!("{}", min_val as u64));
-                                ui.label(format!("{avg_val:.0}"));
-                                ui.label(format!("{}", max_val as u64));
-                            }
-                            _ => {
-                                ui.label(format!("{min_val:.1}"));
-                                ui.label(format!("{avg_val:.1}"));
-                                ui.label(format!("{max_val:.1}"));
-                            }
-                        }
-                        ui.end_row();
+            for (cells, is_reference) in results_table_rows(results, overlays, metric) {
+                for cell in cells {
+                    if is_reference {
+                        ui.weak(cell);
+                    } else {
+                        ui.label(cell);
                     }
                 }
-            });
-    }
+                ui.end_row();
+            }
+        });
 }
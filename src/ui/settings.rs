@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bench_config::default_gui_chunk_sizes;
+use crate::speedtest::{BenchMode, Connector};
+use crate::ui::app::SpeedTestApp;
+use crate::ui::constants::{DEFAULT_PLOT_HEIGHT, DEFAULT_PLOT_WIDTH, PLOT_MAX_SIZE, PLOT_MIN_SIZE};
+
+/// eframe storage key for [`GuiSettings`].
+pub const SETTINGS_KEY: &str = "gui_settings";
+
+/// Config panel and layout choices restored on the next launch.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuiSettings {
+    pub connector: Connector,
+    pub pcileech_device: String,
+    pub duration: u64,
+    pub bench_mode: BenchMode,
+    /// Enabled chunk sizes; sizes the GUI no longer offers are ignored.
+    pub test_sizes: Vec<usize>,
+    pub ui_scale: f32,
+    pub plot_width: f32,
+    pub plot_height: f32,
+}
+
+impl Default for GuiSettings {
+    fn default() -> Self {
+        Self {
+            connector: Connector::default(),
+            pcileech_device: "FPGA".to_string(),
+            duration: 10,
+            bench_mode: BenchMode::Read,
+            test_sizes: default_gui_chunk_sizes()
+                .into_iter()
+                .filter_map(|(size, enabled)| enabled.then_some(size))
+                .collect(),
+            ui_scale: 1.0,
+            plot_width: DEFAULT_PLOT_WIDTH,
+            plot_height: DEFAULT_PLOT_HEIGHT,
+        }
+    }
+}
+
+impl GuiSettings {
+    pub fn from_app(app: &SpeedTestApp) -> Self {
+        Self {
+            connector: app.connector,
+            pcileech_device: app.pcileech_device.clone(),
+            duration: app.duration,
+            bench_mode: app.bench_mode,
+            test_sizes: app
+                .test_sizes
+                .iter()
+                .filter_map(|(size, enabled)| enabled.then_some(*size))
+                .collect(),
+            ui_scale: app.ui_scale,
+            plot_width: app.custom_plot_width,
+            plot_height: app.custom_plot_height,
+        }
+    }
+
+    /// Copy into the app, clamping values an edited or older file could have out of range.
+    pub fn apply_to(&self, app: &mut SpeedTestApp) {
+        app.connector = self.connector;
+        app.pcileech_device = self.pcileech_device.clone();
+        app.duration = self.duration.clamp(1, 60);
+        app.bench_mode = self.bench_mode;
+        app.test_sizes = default_gui_chunk_sizes()
+            .into_iter()
+            .map(|(size, _)| (size, self.test_sizes.contains(&size)))
+            .collect();
+        if !app.test_sizes.iter().any(|(_, enabled)| *enabled) {
+            app.test_sizes = default_gui_chunk_sizes();
+        }
+        app.ui_scale = if self.ui_scale.is_finite() {
+            self.ui_scale.clamp(0.3, 3.0)
+        } else {
+            1.0
+        };
+        app.ui_scale_text = format!("{:.1}", app.ui_scale);
+        app.custom_plot_width = clamp_plot_size(self.plot_width, DEFAULT_PLOT_WIDTH);
+        app.custom_plot_height = clamp_plot_size(self.plot_height, DEFAULT_PLOT_HEIGHT);
+    }
+}
+
+fn clamp_plot_size(size: f32, default: f32) -> f32 {
+    if size.is_finite() {
+        size.clamp(PLOT_MIN_SIZE, PLOT_MAX_SIZE)
+    } else {
+        default
+    }
+}
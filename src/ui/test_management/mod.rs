@@ -1,6 +1,8 @@
+mod remote;
 mod starter;
 mod updater;
 
+pub use remote::{AgentUpdate, start_agent_viewer};
 pub use starter::start_connect;
 pub use starter::start_test_from_connected;
 pub use updater::handle_stats_update;
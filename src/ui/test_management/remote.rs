@@ -0,0 +1,165 @@
+use crate::{
+    speedtest::{
+        AgentStopHandle, AgentViewer, BenchMode, BenchOp, BenchStats, Connector, ProbeTargets,
+        RunEnvironment, StreamEvent,
+    },
+    ui::console::{ConsoleWindow, log_to_console},
+    ui::helpers::get_size_label,
+};
+use std::sync::mpsc::{Receiver, Sender};
+use tokio::sync::mpsc;
+
+use super::starter::DoneNotify;
+
+/// Settings the agent announced with `run_start`; the viewer adopts them for progress and reports.
+pub struct RemoteRun {
+    pub connector: Connector,
+    pub mode: BenchMode,
+    pub duration_secs: u64,
+    pub sizes: Vec<usize>,
+    pub probes: ProbeTargets,
+    pub environment: RunEnvironment,
+}
+
+pub enum AgentUpdate {
+    /// The TCP connection is up; stopping the handle ends the viewer thread.
+    Connected(AgentStopHandle),
+    RunStarted(Box<RemoteRun>),
+    Failed(String),
+}
+
+/// Follow a run on a remote agent (`--agent-listen`). Samples go to `stats_tx` like a local run.
+pub fn start_agent_viewer(
+    addr: String,
+    console: &ConsoleWindow,
+    modal_tx: Sender<String>,
+    stats_tx: mpsc::UnboundedSender<BenchStats>,
+) -> (Receiver<AgentUpdate>, Receiver<()>) {
+    let (update_tx, update_rx) = std::sync::mpsc::channel();
+    let (done_tx, done_rx) = std::sync::mpsc::channel();
+    let console = console.clone();
+
+    log_to_console(&console, &format!("Connecting to agent at {addr}..."));
+
+    std::thread::spawn(move || {
+        let _notify_done = DoneNotify(done_tx);
+        let mut viewer = match AgentViewer::connect(&addr) {
+            Ok(viewer) => viewer,
+            Err(e) => {
+                let _ = update_tx.send(AgentUpdate::Failed(format!("Failed to reach agent: {e}")));
+                return;
+            }
+        };
+        match viewer.stop_handle() {
+            Ok(stop) => {
+                let _ = update_tx.send(AgentUpdate::Connected(stop));
+            }
+            Err(e) => {
+                let _ = update_tx.send(AgentUpdate::Failed(format!("Failed to follow agent: {e}")));
+                return;
+            }
+        }
+        log_to_console(
+            &console,
+            "Connected; waiting for the agent's run to start...",
+        );
+
+        let mut probes = None;
+        loop {
+            let event = match viewer.next_event() {
+                Ok(Some(event)) => event,
+                Ok(None) => {
+                    if probes.is_none() {
+                        let _ = update_tx.send(AgentUpdate::Failed(
+                            "Agent closed the connection before the run started".to_string(),
+                        ));
+                    } else {
+                        log_to_console(&console, "Agent closed the connection");
+                    }
+                    return;
+                }
+                Err(e) => {
+                    let message = format!("Agent stream failed: {e}");
+                    log_to_console(&console, &message);
+                    let _ = modal_tx.send(message);
+                    return;
+                }
+            };
+
+            match event {
+                StreamEvent::RunStart {
+                    connector,
+                    mode,
+                    duration_secs,
+                    ref sizes,
+                    ref environment,
+                    ..
+                } => {
+                    let Some(targets) = event.probe_targets() else {
+                        continue;
+                    };
+                    probes = Some(targets);
+                    let run = RemoteRun {
+                        connector,
+                        mode,
+                        duration_secs,
+                        sizes: sizes.clone(),
+                        probes: targets,
+                        environment: (**environment).clone(),
+                    };
+                    if update_tx
+                        .send(AgentUpdate::RunStarted(Box::new(run)))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+                StreamEvent::StepStart { index, name, mode } => log_to_console(
+                    &console,
+                    &format!("Step {}: {name} ({})", index + 1, mode.label()),
+                ),
+                StreamEvent::PassStart {
+                    op, chunk_bytes, ..
+                } => log_pass_start(&console, probes.as_ref(), op, chunk_bytes),
+                StreamEvent::Sample(sample) => {
+                    // The receiver is gone once the GUI stops viewing.
+                    if stats_tx.send(sample.to_stats()).is_err() {
+                        return;
+                    }
+                }
+                StreamEvent::Warning { message } => {
+                    log_to_console(&console, &format!("Warning: {message}"));
+                }
+                StreamEvent::PassSummary(_) => {}
+                StreamEvent::RunEnd { error } => {
+                    if let Some(error) = error {
+                        let message = format!("Remote run failed: {error}");
+                        log_to_console(&console, &message);
+                        let _ = modal_tx.send(message);
+                    } else {
+                        log_to_console(&console, "Remote run finished");
+                    }
+                    return;
+                }
+            }
+        }
+    });
+
+    (update_rx, done_rx)
+}
+
+fn log_pass_start(
+    console: &ConsoleWindow,
+    probes: Option<&ProbeTargets>,
+    op: BenchOp,
+    size: usize,
+) {
+    let detail = match (op, probes) {
+        (BenchOp::Read, Some(targets)) => targets.format_read_pass(size),
+        (BenchOp::Write, Some(targets)) => targets
+            .format_write_pass(size)
+            .unwrap_or_else(|| format!("write chunk {}", get_size_label(size))),
+        (op, None) => format!("{} chunk {}", op.label(), get_size_label(size)),
+    };
+    log_to_console(console, &detail);
+}
@@ -1,25 +1,27 @@
 use crate::{
-    speedtest::{BenchMode, BenchOp, BenchPassStartFn, BenchStats, Connector, SpeedTest},
+    speedtest::{
+        BenchObserver, BenchOp, BenchSample, BenchStats, BenchmarkReport, BenchmarkSession,
+        ConnectPhase, ConnectTimings, SessionStep, SpeedTest, TargetLost, WriteRestoreOutcome,
+    },
     ui::console::{ConsoleWindow, log_to_console},
-    ui::helpers::get_size_label,
+    ui::helpers::{error_message_with_hints, get_size_label},
 };
-use std::{sync::Arc, sync::mpsc::Sender, time::Duration};
+use std::sync::{Arc, Mutex, mpsc::Sender};
 use tokio::sync::mpsc;
 
 pub fn start_connect(
-    connector: Connector,
-    pcileech_device: String,
-    bench_mode: BenchMode,
-    max_chunk_bytes: usize,
+    session: BenchmarkSession,
     console: &ConsoleWindow,
 ) -> std::sync::mpsc::Receiver<Result<SpeedTest, String>> {
     let (tx, rx) = std::sync::mpsc::channel();
 
     log_to_console(console, "Connecting to device...");
 
+    let console = console.clone();
     std::thread::spawn(move || {
-        let result = SpeedTest::new(connector, pcileech_device, bench_mode, max_chunk_bytes)
-            .map_err(|e| format!("Failed to initialize test: {e}"));
+        let result = session
+            .connect(&ConnectLog(console))
+            .map_err(|e| error_message_with_hints(format!("Failed to initialize test: {e}"), &e));
         let _ = tx.send(result);
     });
 
@@ -28,96 +30,62 @@ pub fn start_connect(
 
 /// Start the test runner with an already-connected `SpeedTest`.
 pub fn start_test_from_connected(
+    session: BenchmarkSession,
     test: SpeedTest,
-    duration: u64,
-    test_sizes: &[(usize, bool)],
     console: &ConsoleWindow,
     modal_tx: Sender<String>,
-    stats_tx: mpsc::Sender<BenchStats>,
+    stats_tx: mpsc::UnboundedSender<BenchStats>,
+    run_report: Arc<Mutex<Option<BenchmarkReport>>>,
 ) -> std::sync::mpsc::Receiver<()> {
-    let selected_sizes = collect_enabled_sizes(test_sizes);
-
     log_to_console(console, "Starting speed test...");
 
     spawn_test_runner(
-        selected_sizes,
-        duration,
+        session,
         console.clone(),
         test,
         modal_tx,
         stats_tx,
+        run_report,
     )
 }
 
-fn collect_enabled_sizes(test_sizes: &[(usize, bool)]) -> Vec<usize> {
-    test_sizes
-        .iter()
-        .filter_map(|(size, enabled)| (*enabled).then_some(*size))
-        .collect()
-}
-
 fn spawn_test_runner(
-    test_sizes: Vec<usize>,
-    duration: u64,
+    session: BenchmarkSession,
     console: ConsoleWindow,
     test: SpeedTest,
     modal_tx: Sender<String>,
-    stats_tx: mpsc::Sender<BenchStats>,
+    stats_tx: mpsc::UnboundedSender<BenchStats>,
+    run_report: Arc<Mutex<Option<BenchmarkReport>>>,
 ) -> std::sync::mpsc::Receiver<()> {
     let (done_tx, done_rx) = std::sync::mpsc::channel();
-    let warn_console = console.clone();
-    let on_warn: crate::speedtest::BenchWarnFn = Arc::new(move |msg| {
-        log_to_console(&warn_console, msg);
-    });
-
-    let on_pass_start: BenchPassStartFn = {
-        let console = console.clone();
-        let test = test.clone();
-        Arc::new(move |op, size| log_test_start(&console, &test, op, size))
-    };
 
     std::thread::spawn(move || {
         let _notify_done = DoneNotify(done_tx);
-        let runtime = tokio::runtime::Runtime::new().expect("failed to create runtime");
-        runtime.block_on(async move {
-            if test_sizes.is_empty() {
-                log_to_console(&console, "No test sizes selected!");
-                return;
-            }
-
-            let test_duration = Duration::from_secs(duration);
-            let mut run_error = None;
-            for size in test_sizes {
-                if test.is_cancelled() {
-                    break;
-                }
-                if let Err(e) = test
-                    .run_passes_for_size(
-                        size,
-                        test_duration,
-                        stats_tx.clone(),
-                        Some(on_warn.clone()),
-                        Some(on_pass_start.clone()),
-                    )
-                    .await
-                {
-                    run_error = Some(e);
-                    break;
-                }
-            }
+        if session.sizes().is_empty() {
+            log_to_console(&console, "No test sizes selected!");
+            return;
+        }
 
-            restore_write_probe_after_run(&console, &test);
-            if let Some(e) = run_error {
-                handle_test_error(&console, e, &modal_tx);
-            }
+        let observer = Arc::new(GuiObserver {
+            console,
+            modal_tx,
+            stats_tx,
         });
+        let runtime = tokio::runtime::Runtime::new().expect("failed to create runtime");
+        // The observer already showed a failure; its report carries the error and the passes
+        // finished before it.
+        let report = match runtime.block_on(session.run(test, observer)) {
+            Ok(report) => report,
+            Err(failure) => *failure.report,
+        };
+        *run_report.lock().unwrap() = Some(report);
     });
 
     done_rx
 }
 
 /// Signals the UI when the benchmark thread has fully exited (join equivalent).
-struct DoneNotify(std::sync::mpsc::Sender<()>);
+pub(super) struct DoneNotify(pub(super) std::sync::mpsc::Sender<()>);
 
 impl Drop for DoneNotify {
     fn drop(&mut self) {
@@ -125,6 +93,104 @@ impl Drop for DoneNotify {
     }
 }
 
+/// Logs connection stages to the console while connecting.
+struct ConnectLog(ConsoleWindow);
+
+impl BenchObserver for ConnectLog {
+    fn connect_phase(&self, phase: ConnectPhase) {
+        log_to_console(&self.0, &format!("Connect: {}...", phase.label()));
+    }
+
+    fn connect_failed(&self, timings: &ConnectTimings) {
+        for line in timings.detail_lines() {
+            log_to_console(&self.0, &line);
+        }
+    }
+}
+
+/// Feeds samples to the results panel and logs the run to the console.
+struct GuiObserver {
+    console: ConsoleWindow,
+    modal_tx: Sender<String>,
+    /// Dropped with the observer when the run ends, which tells the UI the test finished.
+    stats_tx: mpsc::UnboundedSender<BenchStats>,
+}
+
+impl BenchObserver for GuiObserver {
+    fn connect_phase(&self, phase: ConnectPhase) {
+        log_to_console(&self.console, &format!("Reconnect: {}...", phase.label()));
+    }
+
+    fn connect_failed(&self, timings: &ConnectTimings) {
+        for line in timings.detail_lines() {
+            log_to_console(&self.console, &line);
+        }
+    }
+
+    fn pass_start(&self, test: &SpeedTest, op: BenchOp, size: usize, _step: &SessionStep) {
+        log_test_start(&self.console, test, op, size);
+    }
+
+    fn sample(&self, sample: &BenchSample) {
+        // Unbounded so samples queue up while the UI is not drawing (minimized window, error
+        // modal); the results panel, pass summaries and report need every one of them.
+        let _ = self.stats_tx.send(sample.to_stats());
+    }
+
+    fn warning(&self, message: &str) {
+        log_to_console(&self.console, message);
+    }
+
+    fn error(&self, error: &anyhow::Error) {
+        let error_msg = error_message_with_hints(format!("Test error: {error}"), error);
+        log_to_console(&self.console, &error_msg);
+        let _ = self.modal_tx.send(error_msg);
+    }
+
+    fn reconnecting(&self, lost: &TargetLost) {
+        log_to_console(
+            &self.console,
+            &format!("Warning: {lost}; reconnecting and resuming with the next pass"),
+        );
+    }
+
+    fn reconnect_failed(&self, error: &anyhow::Error) {
+        log_to_console(
+            &self.console,
+            &format!("Warning: reconnect failed: {error}"),
+        );
+    }
+
+    fn reconnected(&self, test: &SpeedTest) {
+        log_to_console(&self.console, "Reconnected.");
+        for line in test.probe_connect_detail_lines() {
+            log_to_console(&self.console, &line);
+        }
+    }
+
+    fn write_restored(&self, outcome: &anyhow::Result<WriteRestoreOutcome>) {
+        let console = &self.console;
+        match outcome {
+            Ok(WriteRestoreOutcome::Restored) => {
+                log_to_console(console, "Write probe original bytes restored.")
+            }
+            Ok(WriteRestoreOutcome::SkippedForeignData) => log_to_console(
+                console,
+                "Warning: write probe restore skipped; the target process is using the region.",
+            ),
+            Ok(WriteRestoreOutcome::SkippedTargetLost) => log_to_console(
+                console,
+                "Warning: write probe restore skipped; the target is no longer reachable.",
+            ),
+            Ok(WriteRestoreOutcome::NotNeeded) => {}
+            Err(e) => log_to_console(
+                console,
+                &format!("Warning: failed to restore write probe bytes: {e}"),
+            ),
+        }
+    }
+}
+
 fn log_test_start(console: &ConsoleWindow, test: &SpeedTest, op: BenchOp, size: usize) {
     let targets = test.probe_targets();
     let detail = match op {
@@ -135,23 +201,3 @@ fn log_test_start(console: &ConsoleWindow, test: &SpeedTest, op: BenchOp, size:
     };
     log_to_console(console, &detail);
 }
-
-fn handle_test_error(console: &ConsoleWindow, error: anyhow::Error, modal_tx: &Sender<String>) {
-    let error_msg = format!("Test error: {error}");
-    log_to_console(console, &error_msg);
-    let _ = modal_tx.send(error_msg);
-}
-
-fn restore_write_probe_after_run(console: &ConsoleWindow, test: &SpeedTest) {
-    if !test.bench_mode().needs_write_target() {
-        return;
-    }
-
-    match test.restore_write_target() {
-        Ok(()) => log_to_console(console, "Write probe original bytes restored."),
-        Err(e) => log_to_console(
-            console,
-            &format!("Warning: failed to restore write probe bytes: {e}"),
-        ),
-    }
-}
@@ -6,7 +6,7 @@ use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 pub fn handle_stats_update(
-    stats_rx: &mut mpsc::Receiver<BenchStats>,
+    stats_rx: &mut mpsc::UnboundedReceiver<BenchStats>,
     params: &mut StatsUpdateParams<'_>,
     results: &TestResults,
     console: &ConsoleWindow,
@@ -37,7 +37,7 @@ enum StatsUpdate {
     Pending,
 }
 
-fn try_recv(stats_rx: &mut mpsc::Receiver<BenchStats>) -> Option<StatsUpdate> {
+fn try_recv(stats_rx: &mut mpsc::UnboundedReceiver<BenchStats>) -> Option<StatsUpdate> {
     match stats_rx.try_recv() {
         Ok(stats) => Some(StatsUpdate::Data(BenchSample::from_stats(stats))),
         Err(tokio::sync::mpsc::error::TryRecvError::Empty) => Some(StatsUpdate::Pending),
@@ -74,6 +74,7 @@ fn apply_sample(
     }
 
     record_pass_summary_sample(params.pass_aggregators, &sample);
+    params.raw_samples.push(sample);
     append_plot_point(results, params, &sample);
 }
 
@@ -1,6 +1,8 @@
 use std::sync::{Arc, Mutex};
 
-use crate::speedtest::{BenchMode, BenchOp, ProbeTargets};
+use crate::speedtest::{
+    BenchMode, BenchOp, DEFAULT_IMAGE_WIDTH, ImageFormat, ProbeTargets, ReportFormat,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PlotResizeDirection {
@@ -11,13 +13,107 @@ pub enum PlotResizeDirection {
     HeightDecrease,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq)]
 pub enum PlotMetric {
     Throughput,
     Reads,
     Latency,
 }
 
+impl PlotMetric {
+    pub fn plot_title(self) -> &'static str {
+        match self {
+            PlotMetric::Throughput => "Throughput (MiB/s)",
+            PlotMetric::Reads => "Ops per Second",
+            PlotMetric::Latency => "Latency (μs)",
+        }
+    }
+
+    pub fn table_title(self) -> &'static str {
+        match self {
+            PlotMetric::Throughput => "Results (MiB/s)",
+            PlotMetric::Reads => "Results (ops/s)",
+            PlotMetric::Latency => "Results (μs)",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            PlotMetric::Throughput => "MiB/s",
+            PlotMetric::Reads => "ops/s",
+            PlotMetric::Latency => "μs",
+        }
+    }
+}
+
+/// Which plot or results table an image export renders.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum ImageTarget {
+    Plot(PlotMetric),
+    Table(PlotMetric),
+}
+
+impl ImageTarget {
+    pub const ALL: [ImageTarget; 6] = [
+        ImageTarget::Plot(PlotMetric::Throughput),
+        ImageTarget::Plot(PlotMetric::Reads),
+        ImageTarget::Plot(PlotMetric::Latency),
+        ImageTarget::Table(PlotMetric::Throughput),
+        ImageTarget::Table(PlotMetric::Reads),
+        ImageTarget::Table(PlotMetric::Latency),
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ImageTarget::Plot(metric) => metric.plot_title(),
+            ImageTarget::Table(metric) => metric.table_title(),
+        }
+    }
+
+    /// File name fragment, e.g. `throughput-plot`.
+    pub fn file_stem(self) -> &'static str {
+        match self {
+            ImageTarget::Plot(PlotMetric::Throughput) => "throughput-plot",
+            ImageTarget::Plot(PlotMetric::Reads) => "ops-plot",
+            ImageTarget::Plot(PlotMetric::Latency) => "latency-plot",
+            ImageTarget::Table(PlotMetric::Throughput) => "throughput-table",
+            ImageTarget::Table(PlotMetric::Reads) => "ops-table",
+            ImageTarget::Table(PlotMetric::Latency) => "latency-table",
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct ImageExportSettings {
+    pub target: ImageTarget,
+    pub format: ImageFormat,
+    pub width_px: u32,
+}
+
+impl Default for ImageExportSettings {
+    fn default() -> Self {
+        Self {
+            target: ImageTarget::Plot(PlotMetric::Throughput),
+            format: ImageFormat::Png,
+            width_px: DEFAULT_IMAGE_WIDTH,
+        }
+    }
+}
+
+/// Export button clicked in the results panel.
+#[derive(Copy, Clone)]
+pub enum ExportRequest {
+    Report(ReportFormat),
+    Image,
+}
+
+/// Profile button clicked in the config panel.
+#[derive(Copy, Clone)]
+pub enum ProfileAction {
+    Load,
+    Save,
+}
+
 /// Type alias for data points (time, value)
 pub type DataPoints = Vec<(f64, f64)>;
 
@@ -56,7 +152,12 @@ pub struct ConfigParams<'a> {
     pub connector: &'a mut crate::speedtest::Connector,
     pub pcileech_device: &'a mut String,
     pub bench_mode: &'a mut BenchMode,
+    pub auto_reconnect: &'a mut bool,
     pub duration: &'a mut u64,
+    pub run_labels: &'a mut String,
+    pub run_note: &'a mut String,
+    pub profile_path: &'a mut String,
+    pub agent_addr: &'a mut String,
     pub ui_scale: &'a mut f32,
     pub ui_scale_text: &'a mut String,
     pub test_sizes: &'a mut [(usize, bool)],
@@ -92,6 +193,16 @@ pub struct PlotControls<'a> {
     pub plot_resize_last_repeat: &'a mut Option<std::time::Instant>,
 }
 
+/// Inputs of one results plot: live series, reference overlays, metric and size.
+pub struct PlotParams<'a> {
+    pub results: &'a TestResults,
+    pub overlays: &'a crate::ui::overlays::ReportOverlays,
+    pub metric: PlotMetric,
+    pub duration: u64,
+    pub width: f32,
+    pub height: f32,
+}
+
 pub struct StatsUpdateParams<'a> {
     pub current_throughput: &'a mut f64,
     pub current_ops_per_sec: &'a mut u64,
@@ -103,6 +214,7 @@ pub struct StatsUpdateParams<'a> {
     pub completed_chunks: &'a mut Vec<(BenchOp, usize, f64)>,
     pub last_console_stats_log: &'a mut Option<std::time::Instant>,
     pub pass_aggregators: &'a mut Vec<crate::speedtest::PassAggregator>,
+    pub raw_samples: &'a mut Vec<crate::speedtest::BenchSample>,
 }
 
 pub struct ResultsPanelParams<'a> {
@@ -115,4 +227,9 @@ pub struct ResultsPanelParams<'a> {
     pub test_state: TestState<'a>,
     pub test_sizes: &'a [(usize, bool)],
     pub show_config: &'a mut bool,
+    pub export_include_samples: &'a mut bool,
+    pub history_visible: bool,
+    pub record_history: &'a mut bool,
+    pub overlays: &'a mut crate::ui::overlays::ReportOverlays,
+    pub image_export: &'a mut ImageExportSettings,
 }